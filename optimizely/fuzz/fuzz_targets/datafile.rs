@@ -0,0 +1,34 @@
+//! Parse arbitrary input as datafile, and make decisions for every flag if parsing succeeded
+//!
+//! Run with `cargo +nightly fuzz run datafile` from the `optimizely` directory.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use optimizely::{AttributeValue, Client};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(content) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    // Parsing should never panic, only return an error
+    let Ok(client) = Client::from_string(content).map(|client| client.initialize()) else {
+        return;
+    };
+
+    // Deciding should never panic either, whatever the datafile contains
+    let mut user_context = client.create_user_context("user");
+    user_context.set_attribute("currentPath", AttributeValue::String(String::from("/checkout")));
+    user_context.set_attribute("numberOfProductsInCart", AttributeValue::Integer(3));
+    user_context.set_attribute("appVersion", AttributeValue::String(String::from("1.2.3")));
+    user_context.set_attribute("isLoggedIn", AttributeValue::Boolean(true));
+
+    // Collect the flag keys from the raw JSON, since the client does not list its flags
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(content) else {
+        return;
+    };
+    let flags = value["featureFlags"].as_array().cloned().unwrap_or_default();
+    for flag_key in flags.iter().filter_map(|flag| flag["key"].as_str()) {
+        user_context.decide(flag_key);
+    }
+});
@@ -29,6 +29,26 @@
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
 //!
+//! Creating an SDK client that downloads the datafile in the background
+//! ```
+//! use optimizely::{datafile::Datafile, Client};
+//! use std::time::Duration;
+//! # const SDK_KEY: &str = "KVpGWnzPGKvvQ8yeEWmJZ";
+//!
+//! // Use a bundled datafile until the latest datafile is downloaded
+//! let fallback_datafile = Datafile::from_local_datafile("../datafiles/sandbox.json")?;
+//!
+//! let client = Client::from_sdk_key_in_background(SDK_KEY)
+//!     .with_fallback_datafile(fallback_datafile)
+//!     .initialize();
+//!
+//! // Optionally, wait a limited amount of time for the download to complete
+//! if !client.wait_until_ready(Duration::from_secs(1)) {
+//!     // Continue with the fallback datafile
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
 //! Creating an SDK client with the batched event dispatcher
 //! ```
 //! use optimizely::{event_api::BatchedEventDispatcher, Client};
@@ -42,21 +62,33 @@
 //! ```
 
 // External imports
-use std::sync::{Arc, RwLock, RwLockReadGuard};
-#[cfg(feature = "online")]
-use std::thread::{self, sleep};
+use error_stack::{Report, Result};
+use std::sync::{Arc, RwLockReadGuard};
+use std::time::Duration;
 
 // Imports from crate
-use crate::{datafile::Datafile, DecideOptions};
+use crate::{config::OptimizelyConfig, datafile::Datafile, error::ClientError, DecideOptions};
 
 // Optional import
 #[cfg(feature = "online")]
-use crate::event_api::{EventDispatcher, SimpleEventDispatcher};
+use crate::datafile::DEFAULT_CDN_URL;
+#[cfg(not(feature = "online"))]
+use crate::event_api::DiscardEventDispatcher;
+use crate::event_api::EventDispatcher;
+#[cfg(feature = "online")]
+use crate::event_api::SimpleEventDispatcher;
+#[cfg(feature = "testing")]
+use crate::testing::FlagOverrides;
 
 // Relative imports of sub modules
+use datafile_store::DatafileStore;
 pub use initialization::UninitializedClient;
 pub use user_context::UserContext;
 
+mod datafile_store;
+#[cfg(feature = "online")]
+mod datafile_updater;
+mod file_watcher;
 mod initialization;
 mod user_context;
 
@@ -64,10 +96,12 @@ mod user_context;
 ///
 /// See [super] for examples.
 pub struct Client {
-    datafile_lock: Arc<RwLock<Datafile>>,
+    datafile_store: Arc<DatafileStore>,
     default_decide_options: DecideOptions,
-    #[cfg(feature = "online")]
     event_dispatcher: Box<dyn EventDispatcher>,
+    event_api_url: Option<String>,
+    #[cfg(feature = "testing")]
+    flag_overrides: Option<FlagOverrides>,
 }
 
 type DatafileReadGuard<'a> = RwLockReadGuard<'a, Datafile>;
@@ -80,61 +114,59 @@ impl From<UninitializedClient> for Client {
             .event_dispatcher
             .unwrap_or_else(|| Box::new(SimpleEventDispatcher::new(&options.datafile)));
 
+        // Without the Event API, events are only dispatched to a custom event dispatcher
+        #[cfg(not(feature = "online"))]
+        let event_dispatcher = options
+            .event_dispatcher
+            .unwrap_or_else(|| Box::new(DiscardEventDispatcher));
+
         let default_decide_options = options.default_decide_options.unwrap_or_default();
 
         #[cfg(not(feature = "online"))]
-        // Store the datafile in a reference counted read/write lock
-        let datafile_lock = Arc::new(RwLock::new(options.datafile));
+        // Store the datafile in a reference counted store
+        let datafile_store = Arc::new(DatafileStore::new(options.datafile, true, options.strict_validation));
 
         #[cfg(feature = "online")]
-        let datafile_lock = {
+        let datafile_store = {
+            // The client is only ready once the datafile is downloaded, if it is fetched in the background
+            let ready = options.pending_sdk_key.is_none();
+
             // Clone SDK key so it can be moved to the polling thread
-            let sdk_key = options.datafile.sdk_key().to_owned();
-            let mut current_revision = options.datafile.revision();
-
-            // Store the datafile in a reference counted read/write lock
-            let datafile_lock = Arc::new(RwLock::new(options.datafile));
-
-            // Clone the reference
-            let datafile_lock_clone = datafile_lock.clone();
-
-            // Spawn a thread to update the datafile in the background if update interval is set
-            if let Some(interval) = options.update_interval {
-                thread::spawn(move || {
-                    log::debug!("Starting thread for datafile polling");
-
-                    loop {
-                        log::debug!("Fetching latest datafile");
-
-                        // Request new datafile
-                        if let Ok(datafile) = Datafile::from_sdk_key(&sdk_key) {
-                            let latest_revision = datafile.revision();
-
-                            // Only acquire write lock if revision changed
-                            if current_revision < latest_revision {
-                                log::info!("Updating datafile from {current_revision} to {latest_revision}");
-                                if let Ok(mut lock_guard) = datafile_lock_clone.write() {
-                                    *lock_guard = datafile;
-                                    current_revision = latest_revision;
-                                } else {
-                                    log::error!("Failed to acquire write lock on datafile")
-                                }
-                            }
-                        }
-
-                        sleep(interval);
-                    }
-                });
+            let sdk_key = options
+                .pending_sdk_key
+                .unwrap_or_else(|| options.datafile.sdk_key().to_owned());
+
+            // Store the datafile in a reference counted store
+            let datafile_store = Arc::new(DatafileStore::new(options.datafile, ready, options.strict_validation));
+
+            // Spawn a thread to update the datafile in the background if needed
+            if !ready || options.update_interval.is_some() {
+                datafile_updater::spawn(
+                    options
+                        .cdn_url
+                        .unwrap_or_else(|| DEFAULT_CDN_URL.to_owned()),
+                    sdk_key,
+                    Arc::downgrade(&datafile_store),
+                    options.update_interval,
+                    options.datafile_cache,
+                );
             }
 
-            datafile_lock
+            datafile_store
         };
 
+        // Spawn a thread to watch the local datafile if file watch interval is set
+        if let (Some(file_path), Some(interval)) = (options.file_path, options.file_watch_interval) {
+            file_watcher::spawn(file_path, options.file_fingerprint, Arc::downgrade(&datafile_store), interval);
+        }
+
         Client {
-            datafile_lock,
+            datafile_store,
             default_decide_options,
-            #[cfg(feature = "online")]
             event_dispatcher,
+            event_api_url: options.event_api_url,
+            #[cfg(feature = "testing")]
+            flag_overrides: options.flag_overrides,
         }
     }
 }
@@ -147,11 +179,85 @@ impl Client {
 
     /// Get the datafile within the client
     pub fn datafile(&self) -> DatafileReadGuard<'_> {
-        // Obtain read lock
-        let lock_result = self.datafile_lock.read();
+        self.datafile_store.read()
+    }
+
+    /// Get a read-only view of the current datafile
+    ///
+    /// See [OptimizelyConfig] for more details.
+    pub fn optimizely_config(&self) -> OptimizelyConfig {
+        OptimizelyConfig::from(&*self.datafile())
+    }
+
+    /// Whether the client has received a datafile from its actual source
+    ///
+    /// A client that fetches its datafile in the background is not ready until the first download succeeded.
+    /// Until then, it uses the fallback datafile or a datafile without any flags.
+    pub fn is_ready(&self) -> bool {
+        self.datafile_store.is_ready()
+    }
+
+    /// Block the current thread until the client is ready or the timeout elapsed
+    ///
+    /// Returns whether the client is ready.
+    pub fn wait_until_ready(&self, timeout: Duration) -> bool {
+        self.datafile_store.wait_until_ready(timeout)
+    }
+
+    /// Replace the datafile of a running client
+    ///
+    /// The content is parsed and only used if it has a newer revision than the current datafile.
+    /// If strict validation is enabled (see [UninitializedClient::with_strict_validation]),
+    /// the datafile is also rejected if it has any validation issue.
+    /// The datafile is swapped atomically, so decisions never see a partially updated datafile.
+    /// Afterwards, all config update listeners are notified.
+    ///
+    /// ```
+    /// use optimizely::{error::ClientError, Client};
+    /// # let content = std::fs::read_to_string("../datafiles/sandbox.json")?;
+    ///
+    /// let client = Client::from_local_datafile("../datafiles/sandbox.json")?
+    ///     .initialize();
+    ///
+    /// // Same revision as the current datafile, so the update is rejected
+    /// let report = client.update_datafile(&content).unwrap_err();
+    /// assert_eq!(report.current_context(), &ClientError::OutdatedDatafile);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn update_datafile<S>(&self, content: S) -> Result<(), ClientError>
+    where
+        S: AsRef<str>,
+    {
+        // Parse outside of the write lock, so decisions are not blocked
+        let datafile = self
+            .datafile_store
+            .parse(content)
+            .map_err(ClientError::from_datafile_report)?;
+        let latest_revision = datafile.revision();
+
+        // Compare revisions and swap within the write lock
+        if !self.datafile_store.update(datafile) {
+            let current_revision = self.datafile().revision();
+            return Err(Report::new(ClientError::OutdatedDatafile)
+                .attach_printable(format!("Current revision: {current_revision}"))
+                .attach_printable(format!("Rejected revision: {latest_revision}")));
+        }
 
-        // The lock should not be poisoned, since the writing thread should not panic
-        lock_result.expect("The read/write lock on datafile should not be poisoned.")
+        // The client received a datafile, even if it was still waiting for the CDN
+        self.datafile_store.set_ready();
+
+        Ok(())
+    }
+
+    /// Add a function that is called with the new revision whenever the datafile is replaced
+    ///
+    /// This includes updates from polling the CDN, watching a local file and [Client::update_datafile].
+    pub fn add_config_update_listener<F>(&self, listener: F)
+    where
+        F: Fn(u32) + Send + Sync + 'static,
+    {
+        self.datafile_store
+            .add_config_update_listener(Box::new(listener));
     }
 
     /// Get the default DecideOptions
@@ -160,8 +266,31 @@ impl Client {
     }
 
     /// Get the event dispatcher within the client
-    #[cfg(feature = "online")]
     pub fn event_dispatcher(&self) -> &dyn EventDispatcher {
         &*self.event_dispatcher
     }
+
+    /// Block until the event dispatcher sent every queued event, or the timeout elapsed
+    ///
+    /// Call this at the end of a request, job or test, so no events are left in a batch.
+    /// Returns whether all events were sent in time.
+    pub fn flush_events(&self, timeout: Duration) -> bool {
+        self.event_dispatcher.flush(timeout)
+    }
+
+    /// Get the URL to which event dispatchers should send their requests
+    ///
+    /// This is the Event API of the region in the current datafile, so the endpoint follows datafile updates.
+    /// See [UninitializedClient::with_event_api_url] to use a different Event API in every region.
+    pub fn event_endpoint_url(&self) -> String {
+        self.event_endpoint_url_for(&self.datafile())
+    }
+
+    /// Get the URL to which events that were produced using the given datafile are sent
+    pub(crate) fn event_endpoint_url_for(&self, datafile: &Datafile) -> String {
+        match &self.event_api_url {
+            Some(event_api_url) => format!("{}/v1/events", event_api_url.trim_end_matches('/')),
+            None => format!("{}/v1/events", datafile.region().event_api_url()),
+        }
+    }
 }
@@ -0,0 +1,141 @@
+// External imports
+use error_stack::Result;
+use std::sync::{Condvar, Mutex, RwLock};
+use std::time::Duration;
+
+// Imports from crate
+use crate::datafile::Datafile;
+use crate::error::DatafileError;
+
+// Imports from super
+use super::DatafileReadGuard;
+
+/// Shared storage of the datafile, which can be updated by background threads
+///
+/// Besides the datafile itself, it keeps track of whether the client is ready.
+/// A client is ready once it has received a datafile from its actual source (CDN, local file or string).
+pub(super) struct DatafileStore {
+    datafile: RwLock<Datafile>,
+    ready: Mutex<bool>,
+    ready_condvar: Condvar,
+    config_update_listeners: RwLock<Vec<ConfigUpdateListener>>,
+    strict_validation: bool,
+}
+
+/// Function that is called with the new revision whenever the datafile is replaced
+type ConfigUpdateListener = Box<dyn Fn(u32) + Send + Sync>;
+
+impl DatafileStore {
+    /// Create a new store
+    pub fn new(datafile: Datafile, ready: bool, strict_validation: bool) -> DatafileStore {
+        DatafileStore {
+            datafile: RwLock::new(datafile),
+            ready: Mutex::new(ready),
+            ready_condvar: Condvar::new(),
+            config_update_listeners: RwLock::new(Vec::new()),
+            strict_validation,
+        }
+    }
+
+    /// Get the current datafile
+    pub fn read(&self) -> DatafileReadGuard<'_> {
+        // Obtain read lock
+        let lock_result = self.datafile.read();
+
+        // A poisoned lock still holds a complete datafile, since the datafile is only ever replaced as a whole
+        lock_result.unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Parse the content of a new datafile
+    ///
+    /// If strict validation is enabled, datafiles with any validation issue are rejected.
+    pub fn parse<S>(&self, content: S) -> Result<Datafile, DatafileError>
+    where
+        S: AsRef<str>,
+    {
+        let datafile = Datafile::from_string(content)?;
+
+        if self.strict_validation {
+            datafile.ensure_valid()?;
+        }
+
+        Ok(datafile)
+    }
+
+    /// Replace the current datafile if the given datafile has a newer revision
+    ///
+    /// Returns whether the datafile was replaced.
+    /// All config update listeners are notified after the datafile was replaced.
+    pub fn update(&self, datafile: Datafile) -> bool {
+        let latest_revision = datafile.revision();
+
+        // Replace the datafile in a separate scope, so the write lock is released before notifying listeners
+        {
+            // Acquire write lock
+            let mut lock_guard = self
+                .datafile
+                .write()
+                .unwrap_or_else(|error| error.into_inner());
+
+            // Only replace the datafile if revision changed
+            let current_revision = lock_guard.revision();
+            if current_revision >= latest_revision {
+                return false;
+            }
+
+            log::info!("Updating datafile from {current_revision} to {latest_revision}");
+            *lock_guard = datafile;
+        }
+
+        // Notify listeners
+        let listeners = self
+            .config_update_listeners
+            .read()
+            .unwrap_or_else(|error| error.into_inner());
+        for listener in listeners.iter() {
+            listener(latest_revision);
+        }
+
+        true
+    }
+
+    /// Add a function that is called whenever the datafile is replaced
+    pub fn add_config_update_listener(&self, listener: ConfigUpdateListener) {
+        self.config_update_listeners
+            .write()
+            .unwrap_or_else(|error| error.into_inner())
+            .push(listener);
+    }
+
+    /// Mark the client as ready and wake up any threads that are waiting for it
+    pub fn set_ready(&self) {
+        let mut ready = self.ready.lock().unwrap_or_else(|error| error.into_inner());
+        if !*ready {
+            log::debug!("Client is ready");
+            *ready = true;
+            self.ready_condvar.notify_all();
+        }
+    }
+
+    /// Whether the client has received a datafile from its actual source
+    pub fn is_ready(&self) -> bool {
+        *self.ready.lock().unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Block the current thread until the client is ready or the timeout elapsed
+    ///
+    /// Returns whether the client is ready
+    pub fn wait_until_ready(&self, timeout: Duration) -> bool {
+        let ready = self.ready.lock().unwrap_or_else(|error| error.into_inner());
+
+        // Wait for the condition variable, which also handles spurious wake ups
+        let result = self
+            .ready_condvar
+            .wait_timeout_while(ready, timeout, |ready| !*ready);
+
+        match result {
+            Ok((ready, _)) => *ready,
+            Err(error) => *error.into_inner().0,
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// External imports
+use std::sync::Weak;
+use std::thread::{self, sleep};
+use std::time::Duration;
+
+// Imports from crate
+use crate::datafile::{Datafile, DatafileCache, DatafileResponse};
+use crate::error::DatafileError;
+
+// Imports from super
+use super::DatafileStore;
+
+/// Time between attempts to download the initial datafile, if no update interval is set
+const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Spawn a thread that downloads the datafile from the CDN in the background
+///
+/// The thread keeps running until the first successful download if no update interval is given.
+/// Otherwise, the thread keeps polling the CDN for the latest datafile.
+/// In both cases, the thread stops once the client is dropped or the CDN rejects the SDK key.
+/// If a cache is given, every successfully downloaded datafile is stored in the cache.
+/// The ETag of the last response is sent along, so the CDN can respond with 304 Not Modified.
+pub(super) fn spawn(
+    cdn_url: String, sdk_key: String, store: Weak<DatafileStore>, update_interval: Option<Duration>,
+    cache: Option<DatafileCache>,
+) {
+    thread::spawn(move || {
+        log::debug!("Starting thread for datafile polling");
+
+        let mut etag = None;
+
+        loop {
+            // Only hold on to the store while fetching, so it is dropped together with the client
+            let Some(store) = store.upgrade() else {
+                break;
+            };
+
+            log::debug!("Fetching latest datafile");
+
+            // Request new datafile
+            let result = Datafile::download_if_modified(&cdn_url, &sdk_key, etag.as_deref()).and_then(|response| {
+                response
+                    .map(|response| {
+                        let datafile = store.parse(&response.content)?;
+                        Ok((datafile, response))
+                    })
+                    .transpose()
+            });
+
+            match result {
+                Ok(None) => {
+                    log::debug!("Datafile was not modified");
+                }
+                Ok(Some((
+                    datafile,
+                    DatafileResponse {
+                        content,
+                        etag: latest_etag,
+                    },
+                ))) => {
+                    etag = latest_etag;
+
+                    // Store as last known good datafile
+                    if let Some(cache) = &cache {
+                        cache.store_or_log(&sdk_key, &content);
+                    }
+
+                    // Only acquires write lock if revision changed
+                    store.update(datafile);
+
+                    // The datafile was received from the CDN, so the client is ready
+                    store.set_ready();
+                }
+                Err(report) if report.current_context() == &DatafileError::RejectedRequest => {
+                    log::error!("CDN rejected the request for the datafile, stopping updates");
+                    log::error!("\n{report:?}");
+                    break;
+                }
+                Err(report) => {
+                    log::warn!("Failed to fetch latest datafile");
+                    log::debug!("\n{report:?}");
+                }
+            }
+
+            // Stop the thread if there are no further updates needed
+            let interval = match update_interval {
+                Some(interval) => interval,
+                None if store.is_ready() => break,
+                None => DEFAULT_RETRY_INTERVAL,
+            };
+
+            drop(store);
+            sleep(interval);
+        }
+
+        log::debug!("Stopping thread for datafile polling");
+    });
+}
@@ -0,0 +1,97 @@
+// External imports
+use error_stack::ResultExt;
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::Weak;
+use std::thread::{self, sleep};
+use std::time::{Duration, SystemTime};
+
+// Imports from crate
+use crate::error::DatafileError;
+
+// Imports from super
+use super::DatafileStore;
+
+/// Properties of a file that change whenever the file is modified or replaced
+#[derive(Debug, PartialEq)]
+pub(crate) struct Fingerprint {
+    modified: Option<SystemTime>,
+    length: u64,
+    inode: u64,
+}
+
+impl Fingerprint {
+    /// Read the fingerprint of a file, without reading its content
+    pub(crate) fn read(file_path: &Path) -> Option<Fingerprint> {
+        let metadata = fs::metadata(file_path).ok()?;
+
+        // Config management tools often replace a file instead of modifying it, which changes the inode
+        #[cfg(unix)]
+        let inode = metadata.ino();
+        #[cfg(not(unix))]
+        let inode = 0;
+
+        Some(Fingerprint {
+            modified: metadata.modified().ok(),
+            length: metadata.len(),
+            inode,
+        })
+    }
+}
+
+/// Spawn a thread that watches the local datafile for changes
+///
+/// The file is polled at the given interval.
+/// Whenever the file changed, it is parsed again and the datafile is replaced if its revision is newer.
+/// An invalid file is ignored, so the client keeps using the last valid datafile.
+/// The fingerprint should be read before the file was loaded, so any change after loading is picked up.
+/// The thread stops once the client is dropped.
+pub(super) fn spawn(
+    file_path: PathBuf, initial_fingerprint: Option<Fingerprint>, store: Weak<DatafileStore>, interval: Duration,
+) {
+    thread::spawn(move || {
+        log::debug!("Starting thread for watching {}", file_path.display());
+
+        let mut previous_fingerprint = initial_fingerprint;
+
+        loop {
+            sleep(interval);
+
+            // Only hold on to the store while reloading, so it is dropped together with the client
+            let Some(store) = store.upgrade() else {
+                break;
+            };
+
+            // Only read the file if it changed
+            let fingerprint = Fingerprint::read(&file_path);
+            if fingerprint == previous_fingerprint {
+                continue;
+            }
+            previous_fingerprint = fingerprint;
+
+            log::debug!("Datafile {} changed", file_path.display());
+
+            // Read and parse the file
+            let result = fs::read_to_string(&file_path)
+                .change_context(DatafileError::FailedFileRead)
+                .and_then(|content| store.parse(content));
+
+            match result {
+                Ok(datafile) => {
+                    // Only acquires write lock if revision changed
+                    if !store.update(datafile) {
+                        log::warn!("Ignoring datafile {}, since its revision is not newer", file_path.display());
+                    }
+                }
+                Err(report) => {
+                    log::warn!("Ignoring invalid datafile {}", file_path.display());
+                    log::warn!("\n{report:?}");
+                }
+            }
+        }
+
+        log::debug!("Stopping thread for watching {}", file_path.display());
+    });
+}
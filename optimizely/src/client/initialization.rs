@@ -1,13 +1,19 @@
-use error_stack::{Result, ResultExt};
-#[cfg(feature = "online")]
+use error_stack::Result;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 // Imports from crate
+use crate::client::file_watcher::Fingerprint;
 use crate::client::Client;
 use crate::datafile::Datafile;
+#[cfg(feature = "online")]
+use crate::datafile::DatafileCache;
 use crate::error::ClientError;
 #[cfg(feature = "online")]
+use crate::error::DatafileError;
 use crate::event_api::EventDispatcher;
+#[cfg(feature = "testing")]
+use crate::testing::FlagOverrides;
 use crate::DecideOptions;
 
 /// Intermediate struct that is used to initialize a new [Client].
@@ -16,24 +22,135 @@ use crate::DecideOptions;
 pub struct UninitializedClient {
     pub(crate) datafile: Datafile,
     pub(crate) default_decide_options: Option<DecideOptions>,
+    pub(crate) file_path: Option<PathBuf>,
+    pub(crate) file_fingerprint: Option<Fingerprint>,
+    pub(crate) file_watch_interval: Option<Duration>,
+    pub(crate) strict_validation: bool,
     #[cfg(feature = "online")]
-    pub(crate) update_interval: Option<Duration>,
+    pub(crate) pending_sdk_key: Option<String>,
+    #[cfg(feature = "online")]
+    pub(crate) placeholder_datafile: bool,
     #[cfg(feature = "online")]
+    pub(crate) datafile_cache: Option<DatafileCache>,
+    #[cfg(feature = "online")]
+    pub(crate) update_interval: Option<Duration>,
     pub(crate) event_dispatcher: Option<Box<dyn EventDispatcher>>,
+    #[cfg(feature = "online")]
+    pub(crate) cdn_url: Option<String>,
+    pub(crate) event_api_url: Option<String>,
+    #[cfg(feature = "testing")]
+    pub(crate) flag_overrides: Option<FlagOverrides>,
 }
 
 impl Client {
     /// Download the datafile from the CDN using an SDK key
+    ///
+    /// If the CDN is unreachable, the client continues as if it was created with [Client::from_sdk_key_in_background],
+    /// so a datafile cache configured with [UninitializedClient::with_datafile_cache] or a fallback datafile is used
+    /// until the download succeeds.
+    /// This fails if the CDN rejects the SDK key or the downloaded datafile is invalid.
     #[cfg(feature = "online")]
     pub fn from_sdk_key(sdk_key: &str) -> Result<UninitializedClient, ClientError> {
-        let datafile = Datafile::from_sdk_key(sdk_key).change_context(ClientError::InvalidDatafile)?;
-        Client::from_datafile(datafile)
+        Client::from_download(sdk_key, Datafile::from_sdk_key(sdk_key))
+    }
+
+    /// Download the datafile from the CDN, and fall back to a cached datafile if the CDN is unreachable
+    ///
+    /// Every datafile that is successfully downloaded (also by the polling thread) is stored in the cache directory.
+    /// See [UninitializedClient::with_datafile_cache] for more details.
+    #[cfg(feature = "online")]
+    pub fn from_sdk_key_with_cache<P>(sdk_key: &str, cache_directory: P) -> Result<UninitializedClient, ClientError>
+    where
+        P: AsRef<Path>,
+    {
+        let datafile_cache = DatafileCache::new(cache_directory);
+        let datafile =
+            Datafile::from_sdk_key_with_cache(sdk_key, &datafile_cache).map_err(ClientError::from_datafile_report)?;
+
+        let mut uninitialized_client = Client::from_datafile(datafile);
+        uninitialized_client.datafile_cache = Some(datafile_cache);
+        Ok(uninitialized_client)
+    }
+
+    /// Download the datafile using an SDK key from a different CDN, such as a proxy or a local test server
+    ///
+    /// The datafile is requested from `{cdn_url}/datafiles/{sdk_key}.json`.
+    /// Later downloads, such as polling (see [UninitializedClient::with_update_interval]), use the same CDN.
+    /// If the CDN is unreachable, the download continues in the background like [Client::from_sdk_key].
+    #[cfg(feature = "online")]
+    pub fn from_cdn(cdn_url: &str, sdk_key: &str) -> Result<UninitializedClient, ClientError> {
+        let mut uninitialized_client = Client::from_download(sdk_key, Datafile::from_cdn(cdn_url, sdk_key))?;
+        uninitialized_client.cdn_url = Some(cdn_url.to_owned());
+        Ok(uninitialized_client)
+    }
+
+    /// Download the datafile from a different CDN, and fall back to a cached datafile if the CDN is unreachable
+    ///
+    /// See [Client::from_cdn] and [Client::from_sdk_key_with_cache].
+    #[cfg(feature = "online")]
+    pub fn from_cdn_with_cache<P>(
+        cdn_url: &str, sdk_key: &str, cache_directory: P,
+    ) -> Result<UninitializedClient, ClientError>
+    where
+        P: AsRef<Path>,
+    {
+        let datafile_cache = DatafileCache::new(cache_directory);
+        let datafile = Datafile::from_cdn_with_cache(cdn_url, sdk_key, &datafile_cache)
+            .map_err(ClientError::from_datafile_report)?;
+
+        let mut uninitialized_client = Client::from_datafile(datafile);
+        uninitialized_client.cdn_url = Some(cdn_url.to_owned());
+        uninitialized_client.datafile_cache = Some(datafile_cache);
+        Ok(uninitialized_client)
+    }
+
+    /// Download the datafile from the CDN in the background
+    ///
+    /// Unlike [Client::from_sdk_key], this does not wait for the download and does not fail if the CDN is unreachable.
+    /// Until the datafile is downloaded, the client uses the fallback datafile (see
+    /// [UninitializedClient::with_fallback_datafile]) or a datafile without any flags, for which every decision is "off".
+    ///
+    /// Use [Client::is_ready] or [Client::wait_until_ready] to find out whether the datafile was downloaded.
+    #[cfg(feature = "online")]
+    pub fn from_sdk_key_in_background(sdk_key: &str) -> UninitializedClient {
+        let mut uninitialized_client = UninitializedClient::new(Datafile::empty(sdk_key));
+        uninitialized_client.pending_sdk_key = Some(sdk_key.to_owned());
+        uninitialized_client.placeholder_datafile = true;
+        uninitialized_client
+    }
+
+    /// Use the downloaded datafile, or continue downloading in the background if the CDN is unreachable
+    #[cfg(feature = "online")]
+    fn from_download(
+        sdk_key: &str, result: Result<Datafile, DatafileError>,
+    ) -> Result<UninitializedClient, ClientError> {
+        match result {
+            Ok(datafile) => Ok(Client::from_datafile(datafile)),
+            // A datafile cache or fallback datafile that is configured later on can still be used
+            Err(report)
+                if matches!(report.current_context(), DatafileError::FailedRequest | DatafileError::FailedResponse) =>
+            {
+                log::warn!("Failed to download datafile, continuing the download in the background");
+                log::debug!("\n{report:?}");
+                Ok(Client::from_sdk_key_in_background(sdk_key))
+            }
+            Err(report) => Err(ClientError::from_datafile_report(report)),
+        }
     }
 
     /// Read the datafile from the local filesystem
-    pub fn from_local_datafile(file_path: &str) -> Result<UninitializedClient, ClientError> {
-        let datafile = Datafile::from_local_datafile(file_path).change_context(ClientError::InvalidDatafile)?;
-        Client::from_datafile(datafile)
+    pub fn from_local_datafile<P>(file_path: P) -> Result<UninitializedClient, ClientError>
+    where
+        P: AsRef<Path>,
+    {
+        // Read the fingerprint before the content, so a file watcher also picks up changes during loading
+        let file_fingerprint = Fingerprint::read(file_path.as_ref());
+        let datafile = Datafile::from_local_datafile(&file_path).map_err(ClientError::from_datafile_report)?;
+
+        let mut uninitialized_client = Client::from_datafile(datafile);
+        uninitialized_client.file_path = Some(file_path.as_ref().to_path_buf());
+        uninitialized_client.file_fingerprint = file_fingerprint;
+        Ok(uninitialized_client)
     }
 
     /// Use a string variable as the datafile
@@ -41,13 +158,14 @@ impl Client {
     where
         S: AsRef<str>,
     {
-        let datafile = Datafile::from_string(content).change_context(ClientError::InvalidDatafile)?;
-        Client::from_datafile(datafile)
+        let datafile = Datafile::from_string(content).map_err(ClientError::from_datafile_report)?;
+        Ok(Client::from_datafile(datafile))
     }
 
-    fn from_datafile(datafile: Datafile) -> Result<UninitializedClient, ClientError> {
+    /// Use a datafile that was already parsed, for example one built with `testing::DatafileBuilder`
+    pub fn from_datafile(datafile: Datafile) -> UninitializedClient {
         // Return uninitialized client
-        Ok(UninitializedClient::new(datafile))
+        UninitializedClient::new(datafile)
     }
 }
 
@@ -56,10 +174,24 @@ impl UninitializedClient {
         UninitializedClient {
             datafile,
             default_decide_options: None,
+            file_path: None,
+            file_fingerprint: None,
+            file_watch_interval: None,
+            strict_validation: false,
             #[cfg(feature = "online")]
-            update_interval: None,
+            pending_sdk_key: None,
+            #[cfg(feature = "online")]
+            placeholder_datafile: false,
             #[cfg(feature = "online")]
+            datafile_cache: None,
+            #[cfg(feature = "online")]
+            update_interval: None,
             event_dispatcher: None,
+            #[cfg(feature = "online")]
+            cdn_url: None,
+            event_api_url: None,
+            #[cfg(feature = "testing")]
+            flag_overrides: None,
         }
     }
 
@@ -74,7 +206,6 @@ impl UninitializedClient {
     ///
     /// Or you could call this method with an anonymous function like so:
     /// `.with_event_dispatcher(|_| EventStore::default())`
-    #[cfg(feature = "online")]
     pub fn with_event_dispatcher<F, D>(mut self, dispatcher: F) -> UninitializedClient
     where
         F: FnOnce(&Datafile) -> D,
@@ -109,6 +240,175 @@ impl UninitializedClient {
         self
     }
 
+    /// Use this datafile until the datafile is downloaded in the background
+    ///
+    /// This only has effect on clients created with [Client::from_sdk_key_in_background],
+    /// or with [Client::from_sdk_key] if the CDN was unreachable.
+    #[cfg(feature = "online")]
+    pub fn with_fallback_datafile(mut self, datafile: Datafile) -> UninitializedClient {
+        if self.pending_sdk_key.is_some() {
+            // Replace the empty datafile
+            self.datafile = datafile;
+            self.placeholder_datafile = false;
+        } else {
+            log::warn!("Fallback datafile is ignored, since the datafile is not fetched in the background");
+        }
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Download datafiles from a different CDN, such as a proxy or a local test server
+    ///
+    /// Datafiles are requested from `{cdn_url}/datafiles/{sdk_key}.json`.
+    /// This applies to downloads after initialization, which is polling (see [UninitializedClient::with_update_interval])
+    /// and downloading in the background (see [Client::from_sdk_key_in_background]).
+    /// A blocking initial download happens before this is known, so use [Client::from_cdn] or
+    /// [Client::from_cdn_with_cache] to download the initial datafile from the same CDN.
+    ///
+    /// ```
+    /// use optimizely::Client;
+    ///
+    /// let client = Client::from_sdk_key_in_background("sdk-key")
+    ///     .with_cdn_url("http://127.0.0.1:8080")
+    ///     .with_event_api_url("http://127.0.0.1:8080")
+    ///     .initialize();
+    /// ```
+    #[cfg(feature = "online")]
+    pub fn with_cdn_url(mut self, cdn_url: impl Into<String>) -> UninitializedClient {
+        // Store URL
+        self.cdn_url = Some(cdn_url.into());
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Send events to a different Event API, such as a proxy or a local test server
+    ///
+    /// Events are sent to `{event_api_url}/v1/events` by the built-in event dispatchers,
+    /// see [LogEvent::endpoint_url](crate::event_api::LogEvent::endpoint_url).
+    /// This overrides the Event API of the region in the datafile.
+    pub fn with_event_api_url(mut self, event_api_url: impl Into<String>) -> UninitializedClient {
+        // Store URL
+        self.event_api_url = Some(event_api_url.into());
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Watch the local datafile for changes and reload it
+    ///
+    /// The modification time, size and inode of the file are checked at the given interval.
+    /// When the file changed, it is parsed again and only used if it is valid and has a newer revision.
+    ///
+    /// This only has effect on clients created with [Client::from_local_datafile].
+    pub fn with_file_watch(mut self, interval: Duration) -> UninitializedClient {
+        if self.file_path.is_some() {
+            // Store interval
+            self.file_watch_interval = Some(interval);
+        } else {
+            log::warn!("File watch is ignored, since the datafile was not read from a local file");
+        }
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Store every datafile that is downloaded from the CDN in the given directory
+    ///
+    /// If the datafile is fetched in the background (see [Client::from_sdk_key_in_background]),
+    /// the cached datafile is used until the download completes, unless the fallback datafile has a newer revision.
+    /// The same applies to [Client::from_sdk_key] if the CDN was unreachable, so it falls back to the cache.
+    ///
+    /// ```no_run
+    /// use optimizely::Client;
+    ///
+    /// let client = Client::from_sdk_key("sdk-key")?
+    ///     .with_datafile_cache("/var/cache/optimizely")
+    ///     .initialize();
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "online")]
+    pub fn with_datafile_cache<P>(mut self, cache_directory: P) -> UninitializedClient
+    where
+        P: AsRef<Path>,
+    {
+        let datafile_cache = DatafileCache::new(cache_directory);
+
+        // Use the cached datafile while waiting for the download
+        if let Some(sdk_key) = &self.pending_sdk_key {
+            match datafile_cache.load(sdk_key) {
+                Ok(datafile) if datafile.revision() > self.datafile.revision() => {
+                    log::info!("Using cached datafile with revision {}", datafile.revision());
+                    self.datafile = datafile;
+                    self.placeholder_datafile = false;
+                }
+                Ok(_) => {
+                    log::debug!("Cached datafile is not newer than the fallback datafile");
+                }
+                Err(report) => {
+                    log::debug!("No cached datafile available\n{report:?}");
+                }
+            }
+        }
+
+        // Store cache
+        self.datafile_cache = Some(datafile_cache);
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Reject any datafile that has validation issues (see [Datafile::validate])
+    ///
+    /// The current datafile is validated immediately, and an error is returned if it has any validation issues.
+    /// The empty placeholder of [Client::from_sdk_key_in_background] is not validated, but the downloaded datafile is.
+    /// Any later update of the datafile (by polling, watching a file or [Client::update_datafile]) is ignored
+    /// if the new datafile has any validation issues.
+    ///
+    /// ```
+    /// use optimizely::Client;
+    ///
+    /// let client = Client::from_local_datafile("../datafiles/sandbox.json")?
+    ///     .with_strict_validation()?
+    ///     .initialize();
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_strict_validation(mut self) -> Result<UninitializedClient, ClientError> {
+        // Validate the current datafile, unless it is a placeholder until the download completes
+        if !self.has_placeholder_datafile() {
+            self.datafile
+                .ensure_valid()
+                .map_err(ClientError::from_datafile_report)?;
+        }
+
+        // Store option
+        self.strict_validation = true;
+
+        // Return self, so can chain other functions
+        Ok(self)
+    }
+
+    /// Use flag overrides that take precedence over the datafile, and record all decisions
+    ///
+    /// See [FlagOverrides] for more details.
+    #[cfg(feature = "testing")]
+    pub fn with_flag_overrides(mut self, flag_overrides: &FlagOverrides) -> UninitializedClient {
+        // Store a handle to the shared overrides
+        self.flag_overrides = Some(flag_overrides.clone());
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Whether the datafile is the empty placeholder that is used until the download in the background completes
+    fn has_placeholder_datafile(&self) -> bool {
+        #[cfg(feature = "online")]
+        return self.placeholder_datafile;
+        #[cfg(not(feature = "online"))]
+        return false;
+    }
+
     /// Initialize the client
     pub fn initialize(self) -> Client {
         Client::from(self)
@@ -1,13 +1,13 @@
 // External imports
 use murmur3::murmur3_32 as murmur3_hash;
-#[cfg(feature = "online")]
 use std::collections::HashMap;
 
 // Imports from crate
-use crate::datafile::{Experiment, FeatureFlag};
-#[cfg(feature = "online")]
-use crate::Conversion;
-use crate::{AttributeValue, DecideOptions, Decision, UserAttribute, UserAttributeMap};
+use crate::datafile::{Datafile, Experiment, FeatureFlag};
+use crate::event_api::{EventContext, LogEvent, LogEventKind};
+use crate::{
+    AttributeValue, Conversion, DecideOptions, Decision, EventTags, RuleType, UserAttribute, UserAttributeMap,
+};
 
 // Imports from super
 use super::{Client, DatafileReadGuard};
@@ -49,6 +49,8 @@ pub struct UserContext<'a> {
     client: &'a Client,
     user_id: &'a str,
     user_attributes: UserAttributeMap,
+    session_id: Option<String>,
+    client_ip: Option<String>,
 }
 
 impl<'a> UserContext<'a> {
@@ -58,6 +60,8 @@ impl<'a> UserContext<'a> {
             client,
             user_id,
             user_attributes: UserAttributeMap::default(),
+            session_id: None,
+            client_ip: None,
         }
     }
 
@@ -92,38 +96,63 @@ impl UserContext<'_> {
         self.user_attributes.values().collect()
     }
 
-    #[cfg(feature = "online")]
+    /// Set the session of the user, which is sent with every event of this user context
+    pub fn set_session_id(&mut self, session_id: impl Into<String>) {
+        self.session_id = Some(session_id.into());
+    }
+
+    /// Getter for `session_id` field
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+
+    /// Set the IP address of the user, which the Event API uses for geolocation unless IP anonymization is enabled
+    pub fn set_client_ip(&mut self, client_ip: impl Into<String>) {
+        self.client_ip = Some(client_ip.into());
+    }
+
+    /// Getter for `client_ip` field
+    pub fn client_ip(&self) -> Option<&str> {
+        self.client_ip.as_deref()
+    }
+
     /// Track a conversion event (without properties and tags) for this user
     pub fn track_event(&self, event_key: &str) {
         let properties = HashMap::default();
-        let tags = HashMap::default();
+        let tags = EventTags::default();
         self.track_event_with_properties_and_tags(event_key, properties, tags)
     }
 
-    #[cfg(feature = "online")]
     /// Track a conversion event with properties (but without tags) for this user
     pub fn track_event_with_properties(&self, event_key: &str, properties: HashMap<String, String>) {
-        let tags = HashMap::default();
+        let tags = EventTags::default();
         self.track_event_with_properties_and_tags(event_key, properties, tags)
     }
 
-    #[cfg(feature = "online")]
     /// Track a conversion event with properties and tags for this user
+    ///
+    /// Tags can be [EventTags] with typed values, or a `HashMap<String, String>` with text values.
     pub fn track_event_with_properties_and_tags(
-        &self, event_key: &str, properties: HashMap<String, String>, tags: HashMap<String, String>,
+        &self, event_key: &str, properties: HashMap<String, String>, tags: impl Into<EventTags>,
     ) {
-        // Find the event key in the datafile
-        if let Some(event) = self.client.datafile().event(event_key) {
-            log::debug!("Logging conversion event");
+        // Find the event key in the datafile, and release the read lock before dispatching
+        let (event_id, (context, endpoint_url)) = {
+            let datafile = self.client.datafile();
+            match datafile.event(event_key) {
+                // Use the context of the same datafile, which might be replaced once the lock is released
+                Some(event) => (event.id().to_owned(), self.event_destination(&datafile)),
+                None => return,
+            }
+        };
 
-            // Create conversion to send to dispatcher
-            let conversion = Conversion::new(event_key, event.id(), properties, tags);
+        log::debug!("Logging conversion event");
 
-            // Ignore result of the send_decision function
-            self.client
-                .event_dispatcher()
-                .send_conversion_event(self, conversion);
-        }
+        // Create conversion to send to dispatcher
+        let conversion = Conversion::new(event_key, &event_id, properties, tags.into());
+
+        // Create an event that owns all data, so the dispatcher does not borrow from the client
+        let event = LogEvent::new(self, context, endpoint_url, LogEventKind::Conversion(conversion));
+        self.client.event_dispatcher().dispatch_event(event);
     }
 
     /// Decide which variation to show to a user
@@ -134,38 +163,66 @@ impl UserContext<'_> {
 
     /// Decide which variation to show to a user
     pub fn decide_with_options(&self, flag_key: &str, options: &DecideOptions) -> Decision {
-        // Acquire datafile read lock
-        let datafile = self.client.datafile();
+        // Only send decision events if the disable_decision_event option is false
+        let mut send_decision = !options.disable_decision_event;
 
-        // Retrieve Flag
-        let flag = match datafile.flag(flag_key) {
-            Some(flag) => flag,
-            None => {
-                // When flag key cannot be found, return the off variation
-                // CONSIDERATION: Could have used Result<Decision, E> but this is how other Optimizely SDKs work
-                return Decision::off(flag_key);
+        // Make the decision in a separate scope, so the read lock is released before dispatching
+        let (decision, (context, endpoint_url)) = {
+            // Acquire datafile read lock
+            let datafile = self.client.datafile();
+
+            // Overrides take precedence over the datafile, also for flags that are not in the datafile
+            #[cfg(feature = "testing")]
+            if let Some(flag_overrides) = &self.client.flag_overrides {
+                if let Some(decision) = flag_overrides.decide(&datafile, flag_key, self.user_id) {
+                    flag_overrides.record(self.user_id, decision.clone(), true);
+                    return decision;
+                }
             }
-        };
 
-        // Only send decision events if the disable_decision_event option is false
-        let mut send_decision = !options.disable_decision_event;
+            // Retrieve Flag
+            let flag = match datafile.flag(flag_key) {
+                Some(flag) => flag,
+                None => {
+                    // When flag key cannot be found, return the off variation
+                    // CONSIDERATION: Could have used Result<Decision, E> but this is how other Optimizely SDKs work
+                    let decision = Decision::off(flag_key);
+                    #[cfg(feature = "testing")]
+                    if let Some(flag_overrides) = &self.client.flag_overrides {
+                        flag_overrides.record(self.user_id, decision.clone(), false);
+                    }
+                    return decision;
+                }
+            };
 
-        // Get the selected variation for the given flag
-        let decision = self
-            .decide_for_flag(&datafile, flag, &mut send_decision)
-            .unwrap_or_else(|| Decision::off(flag_key));
+            // Get the selected variation for the given flag
+            let decision = self
+                .decide_for_flag(&datafile, flag, &mut send_decision)
+                .unwrap_or_else(|| Decision::off_for_flag(flag));
+
+            // Use the context of the datafile that made the decision, which might be replaced once the lock is released
+            (decision, self.event_destination(&datafile))
+        };
+
+        #[cfg(feature = "testing")]
+        if let Some(flag_overrides) = &self.client.flag_overrides {
+            flag_overrides.record(self.user_id, decision.clone(), false);
+        }
 
-        #[cfg(feature = "online")]
         if send_decision {
-            self.client
-                .event_dispatcher()
-                .send_decision_event(self, decision.clone());
+            let event = LogEvent::new(self, context, endpoint_url, LogEventKind::Decision(decision.clone()));
+            self.client.event_dispatcher().dispatch_event(event);
         }
 
         // Return
         decision
     }
 
+    /// Context and Event API endpoint of the events that are produced using the given datafile
+    fn event_destination(&self, datafile: &Datafile) -> (EventContext, String) {
+        (EventContext::new(datafile), self.client.event_endpoint_url_for(datafile))
+    }
+
     fn decide_for_flag(
         &self, datafile: &DatafileReadGuard<'_>, flag: &FeatureFlag, send_decision: &mut bool,
     ) -> Option<Decision> {
@@ -175,18 +232,16 @@ impl UserContext<'_> {
             .iter()
             .filter_map(|experiment_id| datafile.experiment(experiment_id))
             .find(|experiment| self.is_in_target_audience(datafile, experiment))
-            .and_then(|experiment| self.decide_for_experiment(flag, experiment));
+            .and_then(|experiment| self.decide_for_experiment(flag, experiment, RuleType::FeatureTest));
 
         match decision {
             Some(_) => {
-                // Send out a decision event for an A/B Test
-                *send_decision &= true;
-
+                // Send out a decision event for an A/B Test, unless the caller disabled it
                 decision
             }
             None => {
-                // Do not send any decision for a Rollout (Targeted Delivery)
-                *send_decision = false;
+                // Only send decisions for a Rollout (Targeted Delivery) or the "off" variation if the project wants to
+                *send_decision &= datafile.send_flag_decisions();
 
                 // No direct experiment found, let's look at the Rollout
                 let rollout = datafile.rollout(flag.rollout_id())?;
@@ -196,12 +251,14 @@ impl UserContext<'_> {
                     .experiments()
                     .iter()
                     .find(|experiment| self.is_in_target_audience(datafile, experiment))
-                    .and_then(|experiment| self.decide_for_experiment(flag, experiment))
+                    .and_then(|experiment| self.decide_for_experiment(flag, experiment, RuleType::Rollout))
             }
         }
     }
 
-    fn decide_for_experiment(&self, flag: &FeatureFlag, experiment: &Experiment) -> Option<Decision> {
+    fn decide_for_experiment(
+        &self, flag: &FeatureFlag, experiment: &Experiment, rule_type: RuleType,
+    ) -> Option<Decision> {
         // Use references for the ids
         let user_id = self.user_id();
         let experiment_id = experiment.id();
@@ -212,7 +269,8 @@ impl UserContext<'_> {
         // To hash the bucket key it needs to be converted to an array of `u8` bytes
         // Use Murmur3 (32-bit) with seed
         let mut bytes = bucketing_key.as_bytes();
-        let hash_value = murmur3_hash(&mut bytes, HASH_SEED).unwrap();
+        // Reading from a byte slice cannot fail, but there is no decision rather than a panic if it does
+        let hash_value = murmur3_hash(&mut bytes, HASH_SEED).ok()?;
 
         // Bring the hash into a range of 0 to 10_000
         let bucket_value = ((hash_value as f64) / MAX_HASH_VALUE * MAX_RANGE_VALUE) as u64;
@@ -224,7 +282,7 @@ impl UserContext<'_> {
             // Map it to a Variation struct
             .and_then(|variation_id| experiment.variation(variation_id))
             // Combine it with the experiment
-            .map(|variation| Decision::from(flag, experiment, variation))
+            .map(|variation| Decision::from(flag, experiment, variation, rule_type))
     }
 
     fn is_in_target_audience(&self, datafile: &DatafileReadGuard<'_>, experiment: &Experiment) -> bool {
@@ -0,0 +1,296 @@
+//! Read-only view of the datafile, similar to `getOptimizelyConfig` in the official SDKs
+//!
+//! The config is a snapshot of the datafile at the moment it was created.
+//! It does not change when the client receives a new datafile, so get a new config whenever needed.
+//!
+//! ```
+//! use optimizely::Client;
+//!
+//! let client = Client::from_local_datafile("../datafiles/sandbox.json")?.initialize();
+//! let config = client.optimizely_config();
+//!
+//! // List the rules of a flag
+//! let flag = config.feature("a_b_test").ok_or("Flag not found")?;
+//! for rule in flag.experiment_rules().iter().chain(flag.delivery_rules()) {
+//!     println!("{}: {}", rule.key(), rule.audiences());
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+// External imports
+use std::collections::HashMap;
+
+// Imports from crate
+use crate::datafile::{Datafile, Experiment, FeatureFlag};
+
+// Relative imports of sub modules
+pub use attribute::OptimizelyAttribute;
+pub use audience::OptimizelyAudience;
+pub use event::OptimizelyEvent;
+pub use experiment::OptimizelyExperiment;
+pub use feature::OptimizelyFeature;
+pub use variable::OptimizelyVariable;
+pub use variation::OptimizelyVariation;
+
+mod attribute;
+mod audience;
+mod event;
+mod experiment;
+mod feature;
+mod variable;
+mod variation;
+
+/// Read-only view of all flags, experiments, events, attributes and audiences within a datafile
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizelyConfig {
+    revision: u32,
+    sdk_key: String,
+    environment_key: String,
+    features_map: HashMap<String, OptimizelyFeature>,
+    experiments_map: HashMap<String, OptimizelyExperiment>,
+    events_map: HashMap<String, OptimizelyEvent>,
+    attributes_map: HashMap<String, OptimizelyAttribute>,
+    audiences: HashMap<String, OptimizelyAudience>,
+}
+
+impl OptimizelyConfig {
+    /// Getter for `revision` field
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+
+    /// Getter for `sdk_key` field
+    pub fn sdk_key(&self) -> &str {
+        &self.sdk_key
+    }
+
+    /// Getter for `environment_key` field
+    pub fn environment_key(&self) -> &str {
+        &self.environment_key
+    }
+
+    /// Feature flags by key
+    pub fn features_map(&self) -> &HashMap<String, OptimizelyFeature> {
+        &self.features_map
+    }
+
+    /// Feature flags by ID
+    pub fn features_by_id(&self) -> HashMap<&str, &OptimizelyFeature> {
+        by_id(self.features_map.values(), OptimizelyFeature::id)
+    }
+
+    /// Get the feature flag with the given key
+    pub fn feature(&self, flag_key: &str) -> Option<&OptimizelyFeature> {
+        self.features_map.get(flag_key)
+    }
+
+    /// Experiments by key, which does not include the delivery rules of flags
+    pub fn experiments_map(&self) -> &HashMap<String, OptimizelyExperiment> {
+        &self.experiments_map
+    }
+
+    /// Experiments by ID, which does not include the delivery rules of flags
+    pub fn experiments_by_id(&self) -> HashMap<&str, &OptimizelyExperiment> {
+        by_id(self.experiments_map.values(), OptimizelyExperiment::id)
+    }
+
+    /// Get the experiment with the given key
+    pub fn experiment(&self, experiment_key: &str) -> Option<&OptimizelyExperiment> {
+        self.experiments_map.get(experiment_key)
+    }
+
+    /// Get the experiment rule or delivery rule with the given key, from any flag
+    pub fn rule(&self, rule_key: &str) -> Option<&OptimizelyExperiment> {
+        self.features_map
+            .values()
+            .flat_map(|feature| {
+                feature
+                    .experiment_rules()
+                    .iter()
+                    .chain(feature.delivery_rules())
+            })
+            .find(|rule| rule.key() == rule_key)
+    }
+
+    /// Events by key
+    pub fn events_map(&self) -> &HashMap<String, OptimizelyEvent> {
+        &self.events_map
+    }
+
+    /// Events by ID
+    pub fn events_by_id(&self) -> HashMap<&str, &OptimizelyEvent> {
+        by_id(self.events_map.values(), OptimizelyEvent::id)
+    }
+
+    /// Get the event with the given key
+    pub fn event(&self, event_key: &str) -> Option<&OptimizelyEvent> {
+        self.events_map.get(event_key)
+    }
+
+    /// Attributes by key
+    pub fn attributes_map(&self) -> &HashMap<String, OptimizelyAttribute> {
+        &self.attributes_map
+    }
+
+    /// Attributes by ID
+    pub fn attributes_by_id(&self) -> HashMap<&str, &OptimizelyAttribute> {
+        by_id(self.attributes_map.values(), OptimizelyAttribute::id)
+    }
+
+    /// Get the attribute with the given key
+    pub fn attribute(&self, attribute_key: &str) -> Option<&OptimizelyAttribute> {
+        self.attributes_map.get(attribute_key)
+    }
+
+    /// Audiences by ID, since audiences do not have a key
+    pub fn audiences(&self) -> &HashMap<String, OptimizelyAudience> {
+        &self.audiences
+    }
+
+    /// Get the audience with the given ID
+    pub fn audience(&self, audience_id: &str) -> Option<&OptimizelyAudience> {
+        self.audiences.get(audience_id)
+    }
+}
+
+impl From<&Datafile> for OptimizelyConfig {
+    fn from(datafile: &Datafile) -> Self {
+        let audiences = datafile
+            .audiences()
+            .map(|audience| {
+                let conditions = audience.condition().to_string();
+                let audience = OptimizelyAudience::new(audience.id(), audience.name(), conditions);
+                (audience.id().to_owned(), audience)
+            })
+            .collect::<HashMap<_, _>>();
+
+        // Experiments are converted within the context of their flag, so variations include the variables of the flag
+        let mut experiments_map = HashMap::new();
+        let mut features_map = HashMap::new();
+        for flag in datafile.flags() {
+            let convert = |experiment: &Experiment| convert_experiment(experiment, Some(flag), &audiences);
+
+            let experiment_rules = flag
+                .experiments_ids()
+                .iter()
+                .filter_map(|experiment_id| datafile.experiment(experiment_id))
+                .map(convert)
+                .collect::<Vec<_>>();
+
+            let delivery_rules = datafile
+                .rollout(flag.rollout_id())
+                .map(|rollout| rollout.experiments().iter().map(convert).collect())
+                .unwrap_or_default();
+
+            let variables_map = flag
+                .variables()
+                .iter()
+                .map(|variable| {
+                    let value = variable.default_value();
+                    let variable =
+                        OptimizelyVariable::new(variable.id(), variable.key(), variable.variable_type(), value);
+                    (variable.key().to_owned(), variable)
+                })
+                .collect();
+
+            for experiment in &experiment_rules {
+                experiments_map.insert(experiment.key().to_owned(), experiment.clone());
+            }
+
+            let feature =
+                OptimizelyFeature::new(flag.id(), flag.key(), experiment_rules, delivery_rules, variables_map);
+            features_map.insert(flag.key().to_owned(), feature);
+        }
+
+        // Experiments that do not belong to any flag
+        for experiment in datafile.experiments() {
+            if !experiments_map.contains_key(experiment.key()) {
+                let experiment = convert_experiment(experiment, None, &audiences);
+                experiments_map.insert(experiment.key().to_owned(), experiment);
+            }
+        }
+
+        let events_map = datafile
+            .events()
+            .map(|event| {
+                let event = OptimizelyEvent::new(event.id(), event.key(), event.experiment_ids());
+                (event.key().to_owned(), event)
+            })
+            .collect();
+
+        let attributes_map = datafile
+            .attributes()
+            .map(|attribute| {
+                let attribute = OptimizelyAttribute::new(attribute.id(), attribute.key());
+                (attribute.key().to_owned(), attribute)
+            })
+            .collect();
+
+        OptimizelyConfig {
+            revision: datafile.revision(),
+            sdk_key: datafile.sdk_key().into(),
+            environment_key: datafile.environment_key().into(),
+            features_map,
+            experiments_map,
+            events_map,
+            attributes_map,
+            audiences,
+        }
+    }
+}
+
+/// Convert an experiment or rule, including the variables of the flag it belongs to
+fn convert_experiment(
+    experiment: &Experiment, flag: Option<&FeatureFlag>, audiences: &HashMap<String, OptimizelyAudience>,
+) -> OptimizelyExperiment {
+    let variations_map = experiment
+        .variations()
+        .map(|variation| {
+            // Variables only take the value of the variation if the feature is enabled
+            let variables_map = flag
+                .map(|flag| flag.variables())
+                .unwrap_or_default()
+                .iter()
+                .map(|variable| {
+                    let value = match variation.is_feature_enabled() {
+                        true => variation.variable_value(variable.id()),
+                        false => None,
+                    };
+                    let value = value.unwrap_or(variable.default_value());
+                    let variable =
+                        OptimizelyVariable::new(variable.id(), variable.key(), variable.variable_type(), value);
+                    (variable.key().to_owned(), variable)
+                })
+                .collect();
+
+            let variation = OptimizelyVariation::new(
+                variation.id(),
+                variation.key(),
+                variation.is_feature_enabled(),
+                variables_map,
+            );
+            (variation.key().to_owned(), variation)
+        })
+        .collect();
+
+    // A user needs to match any of the audiences
+    let audience_names = experiment
+        .audience_ids()
+        .iter()
+        .map(|audience_id| match audiences.get(audience_id) {
+            Some(audience) => format!("\"{}\"", audience.name()),
+            None => format!("\"{audience_id}\""),
+        })
+        .collect::<Vec<_>>()
+        .join(" OR ");
+
+    OptimizelyExperiment::new(experiment.id(), experiment.key(), audience_names, variations_map)
+}
+
+/// Build a map by ID from the values of a map by key
+fn by_id<'a, T>(values: impl Iterator<Item = &'a T>, id: fn(&T) -> &str) -> HashMap<&'a str, &'a T>
+where
+    T: 'a,
+{
+    values.map(|value| (id(value), value)).collect()
+}
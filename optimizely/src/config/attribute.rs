@@ -0,0 +1,25 @@
+/// An attribute that can be used in audience conditions
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizelyAttribute {
+    id: String,
+    key: String,
+}
+
+impl OptimizelyAttribute {
+    pub(crate) fn new(id: &str, key: &str) -> OptimizelyAttribute {
+        OptimizelyAttribute {
+            id: id.into(),
+            key: key.into(),
+        }
+    }
+
+    /// Getter for `id` field
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Getter for `key` field
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
@@ -0,0 +1,34 @@
+/// An audience with its conditions
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizelyAudience {
+    id: String,
+    name: String,
+    conditions: String,
+}
+
+impl OptimizelyAudience {
+    pub(crate) fn new(id: &str, name: &str, conditions: String) -> OptimizelyAudience {
+        OptimizelyAudience {
+            id: id.into(),
+            name: name.into(),
+            conditions,
+        }
+    }
+
+    /// Getter for `id` field
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Getter for `name` field
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The conditions of the audience in the same JSON format as the datafile
+    ///
+    /// For example, `["and",["or",{"type":"custom_attribute","name":"appVersion","match":"semver_ge","value":"1.0.0"}]]`
+    pub fn conditions(&self) -> &str {
+        &self.conditions
+    }
+}
@@ -0,0 +1,32 @@
+/// An event that can be tracked
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizelyEvent {
+    id: String,
+    key: String,
+    experiment_ids: Vec<String>,
+}
+
+impl OptimizelyEvent {
+    pub(crate) fn new(id: &str, key: &str, experiment_ids: &[String]) -> OptimizelyEvent {
+        OptimizelyEvent {
+            id: id.into(),
+            key: key.into(),
+            experiment_ids: experiment_ids.to_vec(),
+        }
+    }
+
+    /// Getter for `id` field
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Getter for `key` field
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// IDs of the experiments that use this event as metric
+    pub fn experiment_ids(&self) -> &[String] {
+        &self.experiment_ids
+    }
+}
@@ -0,0 +1,50 @@
+// External imports
+use std::collections::HashMap;
+
+// Imports from super
+use super::OptimizelyVariation;
+
+/// An experiment or a rule of a feature flag
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizelyExperiment {
+    id: String,
+    key: String,
+    audiences: String,
+    variations_map: HashMap<String, OptimizelyVariation>,
+}
+
+impl OptimizelyExperiment {
+    pub(crate) fn new(
+        id: &str, key: &str, audiences: String, variations_map: HashMap<String, OptimizelyVariation>,
+    ) -> OptimizelyExperiment {
+        OptimizelyExperiment {
+            id: id.into(),
+            key: key.into(),
+            audiences,
+            variations_map,
+        }
+    }
+
+    /// Getter for `id` field
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Getter for `key` field
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The audiences of the experiment as readable string
+    ///
+    /// For example, `"Version 1 and higher" OR "More than 1 products in cart"`.
+    /// An empty string means that the experiment targets everyone.
+    pub fn audiences(&self) -> &str {
+        &self.audiences
+    }
+
+    /// Variations by key
+    pub fn variations_map(&self) -> &HashMap<String, OptimizelyVariation> {
+        &self.variations_map
+    }
+}
@@ -0,0 +1,57 @@
+// External imports
+use std::collections::HashMap;
+
+// Imports from super
+use super::{OptimizelyExperiment, OptimizelyVariable};
+
+/// A feature flag with its rules and variables
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizelyFeature {
+    id: String,
+    key: String,
+    experiment_rules: Vec<OptimizelyExperiment>,
+    delivery_rules: Vec<OptimizelyExperiment>,
+    variables_map: HashMap<String, OptimizelyVariable>,
+}
+
+impl OptimizelyFeature {
+    pub(crate) fn new(
+        id: &str, key: &str, experiment_rules: Vec<OptimizelyExperiment>, delivery_rules: Vec<OptimizelyExperiment>,
+        variables_map: HashMap<String, OptimizelyVariable>,
+    ) -> OptimizelyFeature {
+        OptimizelyFeature {
+            id: id.into(),
+            key: key.into(),
+            experiment_rules,
+            delivery_rules,
+            variables_map,
+        }
+    }
+
+    /// Getter for `id` field
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Getter for `key` field
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// A/B tests of the flag, in the order in which they are evaluated
+    pub fn experiment_rules(&self) -> &[OptimizelyExperiment] {
+        &self.experiment_rules
+    }
+
+    /// Targeted deliveries of the flag, in the order in which they are evaluated
+    ///
+    /// The last rule is the "Everyone Else" rule.
+    pub fn delivery_rules(&self) -> &[OptimizelyExperiment] {
+        &self.delivery_rules
+    }
+
+    /// Variables by key, with their default values
+    pub fn variables_map(&self) -> &HashMap<String, OptimizelyVariable> {
+        &self.variables_map
+    }
+}
@@ -0,0 +1,39 @@
+/// A variable of a feature flag with its value
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizelyVariable {
+    id: String,
+    key: String,
+    variable_type: String,
+    value: String,
+}
+
+impl OptimizelyVariable {
+    pub(crate) fn new(id: &str, key: &str, variable_type: &str, value: &str) -> OptimizelyVariable {
+        OptimizelyVariable {
+            id: id.into(),
+            key: key.into(),
+            variable_type: variable_type.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Getter for `id` field
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Getter for `key` field
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Type of the variable, which is either "string", "integer", "double", "boolean" or "json"
+    pub fn variable_type(&self) -> &str {
+        &self.variable_type
+    }
+
+    /// The value of the variable, encoded as string like in the datafile
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
@@ -0,0 +1,47 @@
+// External imports
+use std::collections::HashMap;
+
+// Imports from super
+use super::OptimizelyVariable;
+
+/// A variation of an experiment or delivery rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizelyVariation {
+    id: String,
+    key: String,
+    feature_enabled: bool,
+    variables_map: HashMap<String, OptimizelyVariable>,
+}
+
+impl OptimizelyVariation {
+    pub(crate) fn new(
+        id: &str, key: &str, feature_enabled: bool, variables_map: HashMap<String, OptimizelyVariable>,
+    ) -> OptimizelyVariation {
+        OptimizelyVariation {
+            id: id.into(),
+            key: key.into(),
+            feature_enabled,
+            variables_map,
+        }
+    }
+
+    /// Getter for `id` field
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Getter for `key` field
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Getter for `feature_enabled` field
+    pub fn feature_enabled(&self) -> bool {
+        self.feature_enabled
+    }
+
+    /// Variables of the flag by key, with the values of this variation
+    pub fn variables_map(&self) -> &HashMap<String, OptimizelyVariable> {
+        &self.variables_map
+    }
+}
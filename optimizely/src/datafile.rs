@@ -1,36 +1,58 @@
 //! Parsing the Optimizely datafile
 use std::fs::File;
 use std::io::Read;
+use std::path::Path;
 
-use crate::error::DatafileError;
+use crate::error::{DatafileError, ParseLocation};
 use error_stack::{Report, Result, ResultExt};
+use serde::{Serialize, Serializer};
+use serde_json::error::Category;
 use std::ops::Deref;
 
 // Relative imports of sub modules
 pub(crate) use attribute::{Attribute, AttributeMap};
 use audience::AudienceMap;
-use environment::Environment;
 #[cfg(feature = "online")]
+pub use cache::DatafileCache;
+use environment::Environment;
 pub(crate) use event::{Event, EventMap};
 pub(crate) use experiment::{Experiment, ExperimentMap};
 pub(crate) use feature_flag::{FeatureFlag, FeatureFlagMap};
+pub use region::Region;
 use revision::Revision;
 use rollout::RolloutMap;
 use traffic_allocation::TrafficAllocation;
+pub use validation::ValidationIssue;
+pub(crate) use variable::{Variable, VariationVariable};
 pub(crate) use variation::{Variation, VariationMap};
 
 mod attribute;
 mod audience;
-mod environment;
 #[cfg(feature = "online")]
+mod cache;
+mod environment;
 mod event;
 mod experiment;
 mod feature_flag;
+mod region;
 mod revision;
 mod rollout;
 mod traffic_allocation;
+mod validation;
+mod variable;
 mod variation;
 
+/// Base URL of the Optimizely CDN, which serves datafiles at `/datafiles/{sdk_key}.json`
+#[cfg(feature = "online")]
+pub const DEFAULT_CDN_URL: &str = "https://cdn.optimizely.com";
+
+/// Content of a datafile that was downloaded from the CDN
+#[cfg(feature = "online")]
+pub(crate) struct DatafileResponse {
+    pub(crate) content: String,
+    pub(crate) etag: Option<String>,
+}
+
 /// The datafile contains all the feature flags, experiments, events and other configuration from an Optimizely account.
 ///
 /// This configuration is stored in JSON format.
@@ -49,25 +71,123 @@ impl Datafile {
     /// Download the datafile from the CDN using an SDK key
     #[cfg(feature = "online")]
     pub fn from_sdk_key(sdk_key: &str) -> Result<Datafile, DatafileError> {
+        Datafile::from_cdn(DEFAULT_CDN_URL, sdk_key)
+    }
+
+    /// Download the datafile using an SDK key from a different CDN, such as a proxy or a local test server
+    ///
+    /// The datafile is requested from `{cdn_url}/datafiles/{sdk_key}.json`.
+    #[cfg(feature = "online")]
+    pub fn from_cdn(cdn_url: &str, sdk_key: &str) -> Result<Datafile, DatafileError> {
+        // Get response body
+        let content = Datafile::download(cdn_url, sdk_key)?;
+
+        // Use response to build Client
+        Datafile::from_string(content)
+    }
+
+    /// Download the datafile from the CDN, and fall back to the cached datafile if that fails
+    ///
+    /// Every successfully downloaded datafile is stored in the cache.
+    #[cfg(feature = "online")]
+    pub fn from_sdk_key_with_cache(sdk_key: &str, cache: &DatafileCache) -> Result<Datafile, DatafileError> {
+        Datafile::from_cdn_with_cache(DEFAULT_CDN_URL, sdk_key, cache)
+    }
+
+    /// Download the datafile from a different CDN, and fall back to the cached datafile if that fails
+    ///
+    /// See [Datafile::from_cdn] and [Datafile::from_sdk_key_with_cache].
+    #[cfg(feature = "online")]
+    pub fn from_cdn_with_cache(cdn_url: &str, sdk_key: &str, cache: &DatafileCache) -> Result<Datafile, DatafileError> {
+        // Download and parse the latest datafile
+        let result = Datafile::download(cdn_url, sdk_key).and_then(|content| {
+            let datafile = Datafile::from_string(&content)?;
+            Ok((datafile, content))
+        });
+
+        match result {
+            Ok((datafile, content)) => {
+                // Store as last known good datafile
+                cache.store_or_log(sdk_key, &content);
+                Ok(datafile)
+            }
+            Err(mut report) => {
+                log::warn!("Failed to fetch datafile, falling back to cached datafile");
+                log::debug!("\n{report:?}");
+
+                match cache.load(sdk_key) {
+                    Ok(datafile) => {
+                        log::warn!("Using cached datafile with revision {}", datafile.revision());
+                        Ok(datafile)
+                    }
+                    Err(cache_report) => {
+                        // Neither the CDN nor the cache has a datafile, so report both
+                        report.extend_one(cache_report);
+                        Err(report)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Download the content of the datafile from the CDN
+    #[cfg(feature = "online")]
+    pub(crate) fn download(cdn_url: &str, sdk_key: &str) -> Result<String, DatafileError> {
+        // Without an ETag, the CDN should always respond with the datafile
+        match Datafile::download_if_modified(cdn_url, sdk_key, None)? {
+            Some(response) => Ok(response.content),
+            None => Err(Report::new(DatafileError::FailedResponse).attach_printable("Unexpected 304 Not Modified")),
+        }
+    }
+
+    /// Download the content of the datafile from the CDN, unless it still has the given ETag
+    ///
+    /// Returns `None` if the CDN responds with 304 Not Modified.
+    #[cfg(feature = "online")]
+    pub(crate) fn download_if_modified(
+        cdn_url: &str, sdk_key: &str, etag: Option<&str>,
+    ) -> Result<Option<DatafileResponse>, DatafileError> {
         // Construct URL
-        let url = format!("https://cdn.optimizely.com/datafiles/{}.json", sdk_key);
+        let url = format!("{}/datafiles/{}.json", cdn_url.trim_end_matches('/'), sdk_key);
 
         // Make GET request
-        let response = ureq::get(&url)
-            .call()
-            .change_context(DatafileError::FailedRequest)?;
+        let mut request = ureq::get(&url);
+        if let Some(etag) = etag {
+            request = request.set("If-None-Match", etag);
+        }
+        let response = match request.call() {
+            Ok(response) => response,
+            // Sending the same request again would have the same result, except for timeouts and rate limits
+            Err(ureq::Error::Status(status, _)) if (400..500).contains(&status) && ![408, 429].contains(&status) => {
+                return Err(Report::new(DatafileError::RejectedRequest)
+                    .attach_printable(format!("Status code: {status}"))
+                    .attach_printable(format!("URL: {url}")));
+            }
+            Err(error) => {
+                return Err(Report::new(error)
+                    .change_context(DatafileError::FailedRequest)
+                    .attach_printable(format!("URL: {url}")));
+            }
+        };
+
+        if response.status() == 304 {
+            return Ok(None);
+        }
 
         // Get response body
+        let etag = response.header("ETag").map(String::from);
         let content = response
             .into_string()
             .change_context(DatafileError::FailedResponse)?;
 
-        // Use response to build Client
-        Datafile::from_string(content)
+        Ok(Some(DatafileResponse { content, etag }))
     }
 
     /// Read the datafile from the local filesystem
-    pub fn from_local_datafile(file_path: &str) -> Result<Datafile, DatafileError> {
+    pub fn from_local_datafile<P>(file_path: P) -> Result<Datafile, DatafileError>
+    where
+        P: AsRef<Path>,
+    {
         // Read content from local path
         let mut content = String::new();
 
@@ -89,6 +209,83 @@ impl Datafile {
     {
         Datafile::try_from(content.as_ref())
     }
+
+    /// Find all problems within the datafile that would lead to unexpected decisions
+    ///
+    /// This includes references to flags, experiments, rollouts, audiences or variations that do not exist,
+    /// traffic ranges that are unsorted, overlapping or beyond 10000, and unsupported datafile versions.
+    /// An empty list means that no problems were found.
+    ///
+    /// ```
+    /// use optimizely::datafile::Datafile;
+    ///
+    /// let datafile = Datafile::from_local_datafile("../datafiles/sandbox.json")?;
+    /// assert!(datafile.validate().is_empty());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        self.0.validate()
+    }
+
+    /// Return an error that lists all problems, if [Datafile::validate] finds any problems
+    pub fn ensure_valid(&self) -> Result<(), DatafileError> {
+        let issues = self.validate();
+        if issues.is_empty() {
+            return Ok(());
+        }
+
+        // Attach every issue to the report
+        let report = issues
+            .into_iter()
+            .fold(Report::new(DatafileError::FailedValidation), |report, issue| report.attach_printable(issue));
+
+        Err(report)
+    }
+
+    /// Serialize into a JSON document that can be used by any Optimizely SDK
+    ///
+    /// Only the properties that are used by this SDK are included.
+    /// Legacy audiences and groups are emitted as empty lists, since all audiences are typed audiences.
+    ///
+    /// ```
+    /// use optimizely::datafile::Datafile;
+    ///
+    /// let datafile = Datafile::from_local_datafile("../datafiles/sandbox.json")?;
+    /// let json = datafile.to_json()?;
+    /// assert_eq!(Datafile::from_string(json)?.revision(), datafile.revision());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_json(&self) -> Result<String, DatafileError> {
+        serde_json::to_string(self).change_context(DatafileError::FailedSerialize)
+    }
+
+    /// Create a datafile that only contains the given flags
+    ///
+    /// Only the experiments, rollouts, audiences, attributes and events that are referenced by these flags are kept.
+    /// This can be used to send a datafile to client-side SDKs, without exposing any server-side flags.
+    ///
+    /// ```
+    /// use optimizely::datafile::Datafile;
+    ///
+    /// let datafile = Datafile::from_local_datafile("../datafiles/sandbox.json")?;
+    /// let json = datafile.subset(["a_b_test"]).to_json()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn subset<I, S>(&self, flag_keys: I) -> Datafile
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let flag_keys = flag_keys.into_iter().collect::<Vec<_>>();
+        let flag_keys = flag_keys.iter().map(AsRef::as_ref).collect();
+        Datafile(self.0.subset(&flag_keys))
+    }
+
+    /// Placeholder datafile without any flags, which is used until the actual datafile is available
+    #[cfg(feature = "online")]
+    pub(crate) fn empty(sdk_key: &str) -> Datafile {
+        Datafile(Environment::empty(sdk_key))
+    }
 }
 
 impl TryFrom<&str> for Datafile {
@@ -96,13 +293,62 @@ impl TryFrom<&str> for Datafile {
 
     /// Construct a new Datafile from a string containing a JSON document
     fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
-        // Parse the JSON content via Serde into Rust structs
-        let environment = serde_json::from_str(value).change_context(DatafileError::InvalidJson)?;
+        // Parse the JSON content via Serde into Rust structs, while keeping track of the path within the document
+        let mut deserializer = serde_json::Deserializer::from_str(value);
+        let environment = serde_path_to_error::deserialize(&mut deserializer).map_err(|error| {
+            let path = error.path().to_string();
+            parse_report(error.into_inner(), path)
+        })?;
+
+        // Make sure there is no trailing content after the JSON document
+        deserializer
+            .end()
+            .map_err(|error| parse_report(error, String::from(".")))?;
 
         Ok(Datafile(environment))
     }
 }
 
+/// Turn a JSON error into a report that includes the location of the error
+fn parse_report(error: serde_json::Error, path: String) -> Report<DatafileError> {
+    let location = ParseLocation::new(&error, path);
+
+    // Distinguish between invalid JSON syntax and JSON that does not match the datafile
+    let datafile_error = match error.classify() {
+        Category::Data => DatafileError::InvalidSchema,
+        Category::Syntax | Category::Eof | Category::Io => DatafileError::InvalidJson,
+    };
+
+    Report::new(error)
+        .change_context(datafile_error)
+        .attach_printable(location)
+}
+
+impl Serialize for Datafile {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Other SDKs expect these lists to be present, even though this SDK does not use them
+        #[derive(Serialize)]
+        struct Document<'a> {
+            #[serde(flatten)]
+            environment: &'a Environment,
+            audiences: [(); 0],
+            groups: [(); 0],
+            variables: [(); 0],
+        }
+
+        let document = Document {
+            environment: &self.0,
+            audiences: [],
+            groups: [],
+            variables: [],
+        };
+        document.serialize(serializer)
+    }
+}
+
 impl Deref for Datafile {
     type Target = Environment;
 
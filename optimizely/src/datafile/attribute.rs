@@ -1,8 +1,8 @@
 // External imports
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub(crate) struct Attribute {
     id: String,
     key: String,
@@ -20,7 +20,7 @@ impl Attribute {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default, Clone)]
 pub(crate) struct AttributeMap(HashMap<String, Attribute>);
 
 impl<'de> Deserialize<'de> for AttributeMap {
@@ -28,12 +28,33 @@ impl<'de> Deserialize<'de> for AttributeMap {
     where
         D: Deserializer<'de>,
     {
-        let mut map = HashMap::new();
-        for attribute in Vec::<Attribute>::deserialize(deserializer)? {
-            map.insert(attribute.key.clone(), attribute);
-        }
+        let attributes = Vec::<Attribute>::deserialize(deserializer)?;
+        Ok(attributes.into_iter().collect())
+    }
+}
 
-        Ok(Self(map))
+impl Serialize for AttributeMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Sort by ID, so the output does not depend on the order of the HashMap
+        let mut attributes = self.0.values().collect::<Vec<_>>();
+        attributes.sort_by_key(|attribute| &attribute.id);
+        serializer.collect_seq(attributes)
+    }
+}
+
+impl FromIterator<Attribute> for AttributeMap {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Attribute>,
+    {
+        Self(
+            iter.into_iter()
+                .map(|attribute| (attribute.key.clone(), attribute))
+                .collect(),
+        )
     }
 }
 
@@ -41,4 +62,8 @@ impl AttributeMap {
     pub fn get(&self, key: &str) -> Option<&Attribute> {
         self.0.get(key)
     }
+
+    pub fn values(&self) -> impl Iterator<Item = &Attribute> {
+        self.0.values()
+    }
 }
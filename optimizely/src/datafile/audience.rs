@@ -1,5 +1,5 @@
 // External imports
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 
 use condition::Condition;
@@ -7,24 +7,34 @@ use condition::Condition;
 mod condition;
 mod match_type;
 mod operator;
+mod semantic_version;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub(crate) struct Audience {
     id: String,
-    #[allow(dead_code)]
     name: String,
     #[serde(rename = "conditions")]
     condition: Condition,
 }
 
 impl Audience {
+    /// Getter for `id` field
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Getter for `name` field
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     /// Return the condition of this audience
     pub fn condition(&self) -> &Condition {
         &self.condition
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default, Clone)]
 pub struct AudienceMap(HashMap<String, Audience>);
 
 impl<'de> Deserialize<'de> for AudienceMap {
@@ -32,18 +42,42 @@ impl<'de> Deserialize<'de> for AudienceMap {
     where
         D: Deserializer<'de>,
     {
-        let mut map = HashMap::new();
-        for audience in Vec::<Audience>::deserialize(deserializer)? {
-            map.insert(audience.id.clone(), audience);
-        }
+        let audiences = Vec::<Audience>::deserialize(deserializer)?;
+        Ok(audiences.into_iter().collect())
+    }
+}
 
-        Ok(Self(map))
+impl Serialize for AudienceMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Sort by ID, so the output does not depend on the order of the HashMap
+        let mut audiences = self.0.values().collect::<Vec<_>>();
+        audiences.sort_by_key(|audience| &audience.id);
+        serializer.collect_seq(audiences)
+    }
+}
+
+impl FromIterator<Audience> for AudienceMap {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Audience>,
+    {
+        Self(
+            iter.into_iter()
+                .map(|audience| (audience.id.clone(), audience))
+                .collect(),
+        )
     }
 }
 
 impl AudienceMap {
-    #[allow(dead_code)]
     pub fn get(&self, key: &str) -> Option<&Audience> {
         self.0.get(key)
     }
+
+    pub fn values(&self) -> impl Iterator<Item = &Audience> {
+        self.0.values()
+    }
 }
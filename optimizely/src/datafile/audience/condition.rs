@@ -1,26 +1,31 @@
 use super::match_type::MatchType;
-use super::operator::{NumericOperator, SemVerOperator, StringOperator};
+use super::operator::{NumericOperator, StringOperator};
+use super::semantic_version;
 use crate::{AttributeValue, UserAttributeMap};
-use semver::Version;
-use serde::de::{Error, MapAccess, SeqAccess, Visitor};
-use serde::{Deserialize, Deserializer};
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{DeserializeSeed, Error, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
 use std::fmt;
 
-#[derive(Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-// A simplified version of a condition to simplify parsing
-enum Field {
-    #[serde(rename = "match")]
-    MatchType,
-    #[serde(rename = "name")]
-    AttributeName,
-    Value,
-    Type,
-}
+/// The only condition type that can be evaluated, other types (like `third_party_dimension`) are unknown
+const CUSTOM_ATTRIBUTE_TYPE: &str = "custom_attribute";
+
+/// Maximum nesting of sequences, so a malicious datafile cannot cause a stack overflow
+pub(crate) const MAX_CONDITION_DEPTH: usize = 32;
+
+/// Numbers beyond ±(2^53 - 1) cannot be compared reliably, so the other Optimizely SDKs treat them as unknown
+const MAX_SAFE_INTEGER: u64 = 9_007_199_254_740_991;
 
 type AttributeName = String;
 
-#[derive(Debug, PartialEq)]
+/// A condition of an audience
+///
+/// Conditions are evaluated using three-valued logic: a condition can be true, false or unknown (`None`).
+/// A condition is unknown when the user does not have the attribute, the attribute has an unexpected type,
+/// or the condition itself has a type or match type that is not supported.
+#[derive(Debug, PartialEq, Clone)]
 pub(crate) enum Condition {
     AndSequence(Vec<Condition>),
     OrSequence(Vec<Condition>),
@@ -47,332 +52,460 @@ pub(crate) enum Condition {
     Exists {
         attribute_name: AttributeName,
     },
+    /// A condition that cannot be evaluated, which is kept as is
+    Unknown(Value),
 }
 
 impl Condition {
     /// Whether the user attributes match the condition or not
+    ///
+    /// An unknown result does not match.
     pub fn does_match(&self, user_attributes: &UserAttributeMap) -> bool {
+        self.evaluate(user_attributes).unwrap_or(false)
+    }
+
+    /// Evaluate the condition to true, false or unknown (`None`)
+    pub fn evaluate(&self, user_attributes: &UserAttributeMap) -> Option<bool> {
         match self {
             Condition::AndSequence(sequence) => {
-                // Combine sequence with AND
-                sequence
-                    .iter()
-                    .all(|condition| condition.does_match(user_attributes))
+                // Combine sequence with AND, where false takes precedence over unknown
+                let mut is_unknown = false;
+                for condition in sequence {
+                    match condition.evaluate(user_attributes) {
+                        Some(false) => return Some(false),
+                        Some(true) => {}
+                        None => is_unknown = true,
+                    }
+                }
+                if is_unknown {
+                    None
+                } else {
+                    Some(true)
+                }
             }
             Condition::OrSequence(sequence) => {
-                // Combine sequence with OR
-                sequence
-                    .iter()
-                    .any(|condition| condition.does_match(user_attributes))
+                // Combine sequence with OR, where true takes precedence over unknown
+                let mut is_unknown = false;
+                for condition in sequence {
+                    match condition.evaluate(user_attributes) {
+                        Some(true) => return Some(true),
+                        Some(false) => {}
+                        None => is_unknown = true,
+                    }
+                }
+                if is_unknown {
+                    None
+                } else {
+                    Some(false)
+                }
             }
             Condition::Negation(condition) => {
-                // Negate the result of condition within
-                !condition.does_match(user_attributes)
+                // Negate the result of condition within, but unknown stays unknown
+                condition.evaluate(user_attributes).map(|result| !result)
             }
             Condition::Exists { attribute_name } => {
-                // Verify that attribute does exist
-                user_attributes.get(attribute_name).is_some()
+                // Verify that attribute does exist, which is never unknown
+                Some(user_value(user_attributes, attribute_name).is_some())
             }
             Condition::BooleanComparison {
                 attribute_name,
                 desired_value,
             } => {
-                // Retrieve value
-                user_attributes
-                    .get(attribute_name)
-                    .map(|user_attribute| {
-                        // Instead of parsing a string to bool, we'll just match cases
-                        match user_attribute.value() {
-                            // User has attribute set to true, so the condition is true if the desired value is true
-                            AttributeValue::Boolean(true) => *desired_value,
-                            // User has attribute set to false, so the condition is true if the desired value is false
-                            AttributeValue::Boolean(false) => !desired_value,
-                            // Not a valid bool, so does not match
-                            _ => false,
-                        }
-                    })
-                    .unwrap_or(false)
+                // Instead of parsing a string to bool, we'll just match cases
+                match user_value(user_attributes, attribute_name)? {
+                    AttributeValue::Boolean(value) => Some(value == desired_value),
+                    // Not a valid bool, so unknown
+                    _ => None,
+                }
             }
             Condition::StringComparison {
                 attribute_name,
                 operator,
                 desired_value,
             } => {
-                // Retrieve value
-                user_attributes
-                    .get(attribute_name)
-                    .map(|user_attribute| {
-                        let user_attribute_value = match user_attribute.value() {
-                            AttributeValue::String(value) => value,
-                            _ => {
-                                // Cannot perform StringComparison on a non String value
-                                return false;
-                            }
-                        };
-
-                        // Apply string operator
-                        match operator {
-                            StringOperator::Equal => desired_value == user_attribute_value,
-                            StringOperator::Contains => user_attribute_value.contains(desired_value),
-                            StringOperator::SemVer(sem_ver_operator) => {
-                                let user_attribute_value = match Version::parse(user_attribute_value) {
-                                    Ok(version) => version,
-                                    Err(_) => {
-                                        // Unable to parse String as version number
-                                        return false;
-                                    }
-                                };
-                                let desired_value = match Version::parse(desired_value) {
-                                    Ok(version) => version,
-                                    Err(_) => {
-                                        // Unable to parse String as version number
-                                        return false;
-                                    }
-                                };
-                                // Apply semantic version operator
-                                match sem_ver_operator {
-                                    SemVerOperator::Equal => user_attribute_value == desired_value,
-                                    SemVerOperator::LessThan => user_attribute_value < desired_value,
-                                    SemVerOperator::LessThanOrEqual => user_attribute_value <= desired_value,
-                                    SemVerOperator::GreaterThan => user_attribute_value > desired_value,
-                                    SemVerOperator::GreaterThanOrEqual => user_attribute_value >= desired_value,
-                                }
-                            }
-                        }
-                    })
-                    .unwrap_or(false)
+                let user_attribute_value = match user_value(user_attributes, attribute_name)? {
+                    AttributeValue::String(value) => value,
+                    // Cannot perform StringComparison on a non String value
+                    _ => return None,
+                };
+
+                // Apply string operator
+                match operator {
+                    StringOperator::Equal => Some(desired_value == user_attribute_value),
+                    StringOperator::Contains => Some(user_attribute_value.contains(desired_value)),
+                    StringOperator::SemVer(sem_ver_operator) => {
+                        // Unable to parse String as version number, so unknown
+                        let ordering = semantic_version::compare(user_attribute_value, desired_value)?;
+
+                        // Apply semantic version operator
+                        Some(sem_ver_operator.apply(ordering))
+                    }
+                }
             }
             Condition::IntegerComparison {
                 attribute_name,
                 operator,
                 desired_value,
             } => {
-                // Retrieve value
-                user_attributes
-                    .get(attribute_name)
-                    .map(|user_attribute| {
-                        let user_attribute_value = match user_attribute.value() {
-                            AttributeValue::Integer(value) => value,
-                            _ => {
-                                // Cannot perform IntegerComparison on a non Integer value
-                                return false;
-                            }
-                        };
-                        // Apply operator
-                        match operator {
-                            NumericOperator::Equal => user_attribute_value == desired_value,
-                            NumericOperator::LessThan => user_attribute_value < desired_value,
-                            NumericOperator::LessThanOrEqual => user_attribute_value <= desired_value,
-                            NumericOperator::GreaterThan => user_attribute_value > desired_value,
-                            NumericOperator::GreaterThanOrEqual => user_attribute_value >= desired_value,
-                        }
-                    })
-                    .unwrap_or(false)
+                // Desired values above MAX_SAFE_INTEGER are parsed as unknown, so the cast is exact
+                let user_attribute_value = numeric_value(user_value(user_attributes, attribute_name)?)?;
+                Some(operator.apply(user_attribute_value, *desired_value as f64))
             }
             Condition::DecimalComparison {
                 attribute_name,
                 operator,
                 desired_value,
             } => {
-                // Retrieve value
-                user_attributes
-                    .get(attribute_name)
-                    .map(|user_attribute| {
-                        let user_attribute_value = match user_attribute.value() {
-                            AttributeValue::Decimal(value) => value,
-                            _ => {
-                                // Cannot perform DecimalComparison on a non Decimal value
-                                return false;
-                            }
-                        };
-                        // Apply operator
-                        match operator {
-                            NumericOperator::Equal => user_attribute_value == desired_value,
-                            NumericOperator::LessThan => user_attribute_value < desired_value,
-                            NumericOperator::LessThanOrEqual => user_attribute_value <= desired_value,
-                            NumericOperator::GreaterThan => user_attribute_value > desired_value,
-                            NumericOperator::GreaterThanOrEqual => user_attribute_value >= desired_value,
-                        }
-                    })
-                    .unwrap_or(false)
+                let user_attribute_value = numeric_value(user_value(user_attributes, attribute_name)?)?;
+                Some(operator.apply(user_attribute_value, *desired_value))
             }
+            Condition::Unknown(_) => None,
+        }
+    }
+
+    /// Names of all attributes that are used within the condition
+    pub fn attribute_names(&self) -> Vec<&str> {
+        match self {
+            Condition::AndSequence(sequence) | Condition::OrSequence(sequence) => sequence
+                .iter()
+                .flat_map(Condition::attribute_names)
+                .collect(),
+            Condition::Negation(condition) => condition.attribute_names(),
+            Condition::IntegerComparison { attribute_name, .. }
+            | Condition::DecimalComparison { attribute_name, .. }
+            | Condition::StringComparison { attribute_name, .. }
+            | Condition::BooleanComparison { attribute_name, .. }
+            | Condition::Exists { attribute_name } => Vec::from([attribute_name.as_str()]),
+            Condition::Unknown(value) => value
+                .get("name")
+                .and_then(Value::as_str)
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Construct a condition from a single leaf, or return None if the leaf cannot be evaluated
+    fn from_leaf(leaf: &Map<String, Value>) -> Option<Condition> {
+        // Only custom attributes can be evaluated
+        if leaf.get("type")?.as_str()? != CUSTOM_ATTRIBUTE_TYPE {
+            return None;
+        }
+
+        let attribute_name = leaf.get("name")?.as_str()?.to_owned();
+
+        // A missing match type is the same as an exact match
+        let match_type = match leaf.get("match") {
+            None | Some(Value::Null) => MatchType::Exact,
+            Some(value) => MatchType::try_from(value.as_str()?).ok()?,
+        };
+
+        // Value is optional. It is not needed for exists
+        let value = match leaf.get("value") {
+            None | Some(Value::Null) => AttributeValue::Null,
+            Some(Value::Bool(value)) => AttributeValue::Boolean(*value),
+            Some(Value::String(value)) => AttributeValue::String(value.clone()),
+            Some(Value::Number(number)) => match number.as_u64() {
+                Some(value) => AttributeValue::Integer(value),
+                None => AttributeValue::Decimal(number.as_f64()?),
+            },
+            Some(_) => return None,
+        };
+
+        // Only accept valid combinations of match type and value type
+        let condition = match (match_type, value) {
+            // Checking whether an attribute exists, regardless of the value
+            (MatchType::Exists, _) => Condition::Exists { attribute_name },
+            // Comparing an attribute to a boolean value
+            (MatchType::Exact, AttributeValue::Boolean(desired_value)) => Condition::BooleanComparison {
+                attribute_name,
+                desired_value,
+            },
+            // Comparing an attribute to a numeric value
+            (match_type, AttributeValue::Integer(desired_value)) => {
+                if desired_value > MAX_SAFE_INTEGER {
+                    return None;
+                }
+
+                Condition::IntegerComparison {
+                    operator: NumericOperator::try_from(match_type).ok()?,
+                    attribute_name,
+                    desired_value,
+                }
+            }
+            // Comparing an attribute to a numeric value
+            (match_type, AttributeValue::Decimal(desired_value)) => Condition::DecimalComparison {
+                operator: NumericOperator::try_from(match_type).ok()?,
+                attribute_name,
+                desired_value,
+            },
+            // Comparing an attribute to a string value
+            (match_type, AttributeValue::String(desired_value)) => Condition::StringComparison {
+                operator: StringOperator::try_from(match_type).ok()?,
+                attribute_name,
+                desired_value,
+            },
+            // Any other combination cannot be evaluated
+            _ => return None,
+        };
+
+        Some(condition)
+    }
+}
+
+/// Get the value of an attribute, where a missing attribute and a null value are treated the same
+fn user_value<'a>(user_attributes: &'a UserAttributeMap, attribute_name: &str) -> Option<&'a AttributeValue> {
+    match user_attributes.get(attribute_name)?.value() {
+        AttributeValue::Null => None,
+        value => Some(value),
+    }
+}
+
+/// Get an attribute value as number, or None if it is not a number that can be compared reliably
+fn numeric_value(value: &AttributeValue) -> Option<f64> {
+    match value {
+        AttributeValue::Integer(value) if *value <= MAX_SAFE_INTEGER => Some(*value as f64),
+        AttributeValue::Decimal(value) if value.abs() <= MAX_SAFE_INTEGER as f64 => Some(*value),
+        _ => None,
+    }
+}
+
+impl Serialize for Condition {
+    /// Serialize into the same JSON structure as the datafile
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Sequences start with their operator
+        let (operator, conditions) = match self {
+            Condition::AndSequence(conditions) => ("and", conditions.iter().collect::<Vec<_>>()),
+            Condition::OrSequence(conditions) => ("or", conditions.iter().collect()),
+            Condition::Negation(condition) => ("not", Vec::from([condition.as_ref()])),
+            Condition::Unknown(value) => return value.serialize(serializer),
+            leaf => return serialize_leaf(leaf, serializer),
+        };
+
+        let mut seq = serializer.serialize_seq(Some(conditions.len() + 1))?;
+        seq.serialize_element(operator)?;
+        for condition in conditions {
+            seq.serialize_element(condition)?;
         }
+        seq.end()
     }
 }
 
+/// Serialize a single comparison as a map with type, name, match and value
+fn serialize_leaf<S>(leaf: &Condition, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let (attribute_name, match_type, value) = match leaf {
+        Condition::IntegerComparison {
+            attribute_name,
+            operator,
+            desired_value,
+        } => (attribute_name, operator.match_type(), Value::from(*desired_value)),
+        Condition::DecimalComparison {
+            attribute_name,
+            operator,
+            desired_value,
+        } => (attribute_name, operator.match_type(), Value::from(*desired_value)),
+        Condition::StringComparison {
+            attribute_name,
+            operator,
+            desired_value,
+        } => (attribute_name, operator.match_type(), Value::from(desired_value.as_str())),
+        Condition::BooleanComparison {
+            attribute_name,
+            desired_value,
+        } => (attribute_name, "exact", Value::from(*desired_value)),
+        Condition::Exists { attribute_name } => (attribute_name, "exists", Value::Null),
+        _ => return Err(serde::ser::Error::custom("expected a single comparison")),
+    };
+
+    let mut map = serializer.serialize_map(Some(4))?;
+    map.serialize_entry("type", CUSTOM_ATTRIBUTE_TYPE)?;
+    map.serialize_entry("name", attribute_name)?;
+    map.serialize_entry("match", match_type)?;
+    map.serialize_entry("value", &value)?;
+    map.end()
+}
+
+impl fmt::Display for Condition {
+    /// Display as compact JSON, like the conditions in the datafile
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| fmt::Error)?;
+        f.write_str(&json)
+    }
+}
+
+/// Logical operators that can be the first element of a sequence
+enum LogicalOperator {
+    And,
+    Or,
+    Not,
+}
+
+/// An element of a sequence, which is either an operator or a condition
+enum SequenceItem {
+    Operator(String),
+    Condition(Condition),
+    Unknown(Value),
+}
+
 // Advanced serde Deserialize
-struct ConditionVisitor;
-impl<'de> Visitor<'de> for ConditionVisitor {
-    type Value = Condition;
+struct SequenceItemVisitor {
+    depth: usize,
+}
+
+impl<'de> Visitor<'de> for SequenceItemVisitor {
+    type Value = SequenceItem;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("a sequence or map")
+        formatter.write_str("an operator, a sequence or a map")
     }
 
-    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
     where
-        A: SeqAccess<'de>,
+        E: Error,
     {
-        let operator = seq
-            .next_element::<String>()?
-            .ok_or_else(|| Error::custom("expected at least one element"))?;
+        Ok(SequenceItem::Operator(value.to_owned()))
+    }
 
-        let mut conditions = Vec::new();
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(SequenceItem::Unknown(Value::from(value)))
+    }
 
-        while let Some(condition) = seq.next_element::<Condition>()? {
-            conditions.push(condition);
-        }
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(SequenceItem::Unknown(Value::from(value)))
+    }
 
-        let condition = match operator.as_str() {
-            "and" => Condition::AndSequence(conditions),
-            "or" => Condition::OrSequence(conditions),
-            "not" => {
-                if conditions.len() > 1 {
-                    return Err(Error::custom("too many conditions found within not statement"));
-                }
-                let condition = match conditions.pop() {
-                    Some(condition) => condition,
-                    None => {
-                        return Err(Error::custom("no condition found within not statement"));
-                    }
-                };
-                Condition::Negation(Box::new(condition))
-            }
-            _ => {
-                return Err(Error::custom(r#"expected either "and" or "or""#));
-            }
-        };
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(SequenceItem::Unknown(Value::from(value)))
+    }
 
-        Ok(condition)
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(SequenceItem::Unknown(Value::from(value)))
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(SequenceItem::Unknown(Value::Null))
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let visitor = ConditionVisitor { depth: self.depth };
+        visitor.visit_seq(seq).map(SequenceItem::Condition)
     }
 
-    fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error>
+    fn visit_map<V>(self, map: V) -> Result<Self::Value, V::Error>
     where
         V: MapAccess<'de>,
     {
-        // Start with all variables set to none
-        let mut match_type = Option::None;
-        let mut attribute_name = Option::None;
-        let mut value = Option::None;
-
-        // Iterate over all keys
-        while let Some(key) = map.next_key::<Field>()? {
-            match key {
-                Field::MatchType => {
-                    if match_type.is_some() {
-                        return Err(Error::duplicate_field("match"));
-                    }
-                    match_type = Some(map.next_value::<MatchType>()?);
-                }
-                Field::AttributeName => {
-                    if attribute_name.is_some() {
-                        return Err(Error::duplicate_field("name"));
-                    }
-                    attribute_name = Some(map.next_value::<AttributeName>()?);
-                }
-                Field::Value => {
-                    if value.is_some() {
-                        return Err(Error::duplicate_field("value"));
-                    }
-                    value = Some(map.next_value::<AttributeValue>()?);
-                }
-                Field::Type => {
-                    // Skip type field as it is always "custom_attribute"
-                    let _type = map.next_value::<String>()?;
-                    assert_eq!(_type, "custom_attribute");
-                }
-            }
-        }
+        let visitor = ConditionVisitor { depth: self.depth };
+        visitor.visit_map(map).map(SequenceItem::Condition)
+    }
+}
 
-        // Verify that match type and attribute name have been set
-        let match_type = match_type.ok_or_else(|| Error::missing_field("match"))?;
-        let attribute_name = attribute_name.ok_or_else(|| Error::missing_field("name"))?;
+impl<'de> DeserializeSeed<'de> for SequenceItemVisitor {
+    type Value = SequenceItem;
 
-        // Value is optional. It is not needed for exists
-        let value = value.unwrap_or(AttributeValue::Null);
+    fn deserialize<D>(self, deserializer: D) -> Result<SequenceItem, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
 
-        // Only accept valid combinations of match type and value type
-        let condition = match value {
-            // Checking whether an attribute exists
-            AttributeValue::Null => {
-                // Only one valid operator
-                if match_type != MatchType::Exists {
-                    return Err(Error::custom("invalid operator for empty type"));
-                }
+// Advanced serde Deserialize
+struct ConditionVisitor {
+    // Number of sequences that contain this condition
+    depth: usize,
+}
 
-                Condition::Exists { attribute_name }
-            }
-            // Comparing an attribute to a boolean value
-            AttributeValue::Boolean(desired_value) => {
-                // Only one valid operator
-                if match_type != MatchType::Exact {
-                    return Err(Error::custom("invalid operator for boolean"));
-                }
+impl<'de> Visitor<'de> for ConditionVisitor {
+    type Value = Condition;
 
-                Condition::BooleanComparison {
-                    attribute_name,
-                    desired_value,
-                }
-            }
-            // Comparing an attribute to a numeric value
-            AttributeValue::Integer(desired_value) => {
-                let operator = match match_type {
-                    MatchType::Exact => NumericOperator::Equal,
-                    MatchType::LessThan => NumericOperator::LessThan,
-                    MatchType::LessThanOrEqual => NumericOperator::LessThanOrEqual,
-                    MatchType::GreaterThan => NumericOperator::GreaterThan,
-                    MatchType::GreaterThanOrEqual => NumericOperator::GreaterThanOrEqual,
-                    _ => return Err(Error::custom("invalid operator for number")),
-                };
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence or map")
+    }
 
-                Condition::IntegerComparison {
-                    operator,
-                    attribute_name,
-                    desired_value,
-                }
-            }
-            // Comparing an attribute to a numeric value
-            AttributeValue::Decimal(desired_value) => {
-                let operator = match match_type {
-                    MatchType::Exact => NumericOperator::Equal,
-                    MatchType::LessThan => NumericOperator::LessThan,
-                    MatchType::LessThanOrEqual => NumericOperator::LessThanOrEqual,
-                    MatchType::GreaterThan => NumericOperator::GreaterThan,
-                    MatchType::GreaterThanOrEqual => NumericOperator::GreaterThanOrEqual,
-                    _ => return Err(Error::custom("invalid operator for number")),
-                };
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        // Reject deeply nested sequences, instead of recursing any further
+        if self.depth >= MAX_CONDITION_DEPTH {
+            let message = format!("audience condition exceeds maximum depth of {MAX_CONDITION_DEPTH}");
+            return Err(A::Error::custom(message));
+        }
 
-                Condition::DecimalComparison {
-                    operator,
-                    attribute_name,
-                    desired_value,
-                }
+        // A sequence without a leading operator is combined with OR
+        let mut operator = LogicalOperator::Or;
+        let mut conditions = Vec::new();
+        let mut is_first = true;
+
+        let depth = self.depth + 1;
+        while let Some(item) = seq.next_element_seed(SequenceItemVisitor { depth })? {
+            match item {
+                SequenceItem::Operator(text) if is_first && text == "and" => operator = LogicalOperator::And,
+                SequenceItem::Operator(text) if is_first && text == "or" => operator = LogicalOperator::Or,
+                SequenceItem::Operator(text) if is_first && text == "not" => operator = LogicalOperator::Not,
+                SequenceItem::Operator(text) => conditions.push(Condition::Unknown(Value::String(text))),
+                SequenceItem::Condition(condition) => conditions.push(condition),
+                SequenceItem::Unknown(value) => conditions.push(Condition::Unknown(value)),
             }
-            // Comparing an attribute to a string value
-            AttributeValue::String(desired_value) => {
-                let operator = match match_type {
-                    MatchType::Exact => StringOperator::Equal,
-                    MatchType::Substring => StringOperator::Contains,
-                    MatchType::SemVerEqual => StringOperator::SemVer(SemVerOperator::Equal),
-                    MatchType::SemVerLessThan => StringOperator::SemVer(SemVerOperator::LessThan),
-                    MatchType::SemVerLessThanOrEqual => StringOperator::SemVer(SemVerOperator::LessThanOrEqual),
-                    MatchType::SemVerGreaterThan => StringOperator::SemVer(SemVerOperator::GreaterThan),
-                    MatchType::SemVerGreaterThanOrEqual => StringOperator::SemVer(SemVerOperator::GreaterThanOrEqual),
-                    _ => {
-                        return Err(Error::custom("invalid operator for string"));
-                    }
-                };
+            is_first = false;
+        }
 
-                Condition::StringComparison {
-                    operator,
-                    attribute_name,
-                    desired_value,
-                }
+        let condition = match operator {
+            LogicalOperator::And => Condition::AndSequence(conditions),
+            LogicalOperator::Or => Condition::OrSequence(conditions),
+            LogicalOperator::Not => {
+                // Only the first condition is negated, and an empty negation is unknown
+                let condition = conditions
+                    .into_iter()
+                    .next()
+                    .unwrap_or(Condition::Unknown(Value::Null));
+                Condition::Negation(Box::new(condition))
             }
         };
 
         Ok(condition)
     }
+
+    fn visit_map<V>(self, map: V) -> Result<Self::Value, V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        // Collect all fields, so unknown conditions can be kept as is
+        let leaf = Map::<String, Value>::deserialize(MapAccessDeserializer::new(map))?;
+
+        let condition = Condition::from_leaf(&leaf).unwrap_or_else(|| {
+            let leaf = Value::Object(leaf);
+            log::warn!("Audience condition {leaf} is not supported and will evaluate to unknown");
+            Condition::Unknown(leaf)
+        });
+
+        Ok(condition)
+    }
 }
 
 impl<'de> Deserialize<'de> for Condition {
@@ -380,13 +513,15 @@ impl<'de> Deserialize<'de> for Condition {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_any(ConditionVisitor)
+        deserializer.deserialize_any(ConditionVisitor { depth: 0 })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::datafile::audience::operator::SemVerOperator;
+    use crate::UserAttribute;
     use std::error::Error;
 
     #[test]
@@ -433,4 +568,329 @@ mod tests {
 
         Ok(())
     }
+
+    /// Build user attributes from a JSON object
+    fn user_attributes(json: &str) -> Result<UserAttributeMap, Box<dyn Error>> {
+        let mut user_attributes = UserAttributeMap::default();
+        for (key, value) in serde_json::from_str::<Map<String, Value>>(json)? {
+            let attribute = serde_json::from_value(serde_json::json!({"id": key, "key": key}))?;
+            let value = serde_json::from_value::<AttributeValue>(value)?;
+            user_attributes.insert(key, UserAttribute::from_attribute_and_value(&attribute, value));
+        }
+        Ok(user_attributes)
+    }
+
+    /// Evaluate a JSON encoded condition against JSON encoded user attributes
+    fn evaluate(condition: &str, attributes: &str) -> Result<Option<bool>, Box<dyn Error>> {
+        let condition = serde_json::from_str::<Condition>(condition)?;
+        Ok(condition.evaluate(&user_attributes(attributes)?))
+    }
+
+    #[test]
+    fn unknown_leaves() -> Result<(), Box<dyn Error>> {
+        // Unknown condition type
+        let json = r#"{"match":"exact","name":"browser","type":"third_party_dimension","value":"safari"}"#;
+        assert!(matches!(serde_json::from_str::<Condition>(json)?, Condition::Unknown(_)));
+        assert_eq!(evaluate(json, r#"{"browser":"safari"}"#)?, None);
+
+        // Unknown match type
+        let json = r#"{"match":"regex","name":"browser","type":"custom_attribute","value":"saf.*"}"#;
+        assert_eq!(evaluate(json, r#"{"browser":"safari"}"#)?, None);
+
+        // Invalid combination of match type and value
+        let json = r#"{"match":"gt","name":"browser","type":"custom_attribute","value":"safari"}"#;
+        assert_eq!(evaluate(json, r#"{"browser":"safari"}"#)?, None);
+
+        // Missing match type is an exact match
+        let json = r#"{"name":"browser","type":"custom_attribute","value":"safari"}"#;
+        assert_eq!(evaluate(json, r#"{"browser":"safari"}"#)?, Some(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_user_attributes() -> Result<(), Box<dyn Error>> {
+        let json = r#"{"match":"gt","name":"age","type":"custom_attribute","value":18}"#;
+
+        // Missing, null or a different type is unknown
+        assert_eq!(evaluate(json, r#"{}"#)?, None);
+        assert_eq!(evaluate(json, r#"{"age":null}"#)?, None);
+        assert_eq!(evaluate(json, r#"{"age":"twenty"}"#)?, None);
+
+        // Integers and decimals can be compared
+        assert_eq!(evaluate(json, r#"{"age":20}"#)?, Some(true));
+        assert_eq!(evaluate(json, r#"{"age":17.5}"#)?, Some(false));
+
+        // Numbers beyond 2^53 cannot be compared reliably
+        assert_eq!(evaluate(json, r#"{"age":9007199254740993}"#)?, None);
+
+        // Exists is never unknown
+        let json = r#"{"match":"exists","name":"age","type":"custom_attribute"}"#;
+        assert_eq!(evaluate(json, r#"{}"#)?, Some(false));
+        assert_eq!(evaluate(json, r#"{"age":null}"#)?, Some(false));
+        assert_eq!(evaluate(json, r#"{"age":"twenty"}"#)?, Some(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn tri_state_logic() -> Result<(), Box<dyn Error>> {
+        let known = r#"{"match":"exact","name":"browser","type":"custom_attribute","value":"safari"}"#;
+        let unknown = r#"{"match":"exact","name":"missing","type":"custom_attribute","value":"safari"}"#;
+        let attributes = r#"{"browser":"chrome"}"#;
+
+        // False takes precedence over unknown in an AND-sequence
+        assert_eq!(evaluate(&format!(r#"["and",{known},{unknown}]"#), attributes)?, Some(false));
+        // Unknown takes precedence over false in an OR-sequence
+        assert_eq!(evaluate(&format!(r#"["or",{known},{unknown}]"#), attributes)?, None);
+        // Negation of unknown is unknown
+        assert_eq!(evaluate(&format!(r#"["not",{unknown}]"#), attributes)?, None);
+        assert_eq!(evaluate(&format!(r#"["not",{known}]"#), attributes)?, Some(true));
+        // A sequence without operator is an OR-sequence
+        assert_eq!(evaluate(&format!(r#"[{known},{unknown}]"#), r#"{"browser":"safari"}"#)?, Some(true));
+        // Empty sequences
+        assert_eq!(evaluate(r#"["and"]"#, attributes)?, Some(true));
+        assert_eq!(evaluate(r#"["or"]"#, attributes)?, Some(false));
+        assert_eq!(evaluate(r#"["not"]"#, attributes)?, None);
+
+        Ok(())
+    }
+
+    /// Leaf condition on the `attribute` attribute, using the given match type and JSON encoded value
+    fn leaf(match_type: &str, value: &str) -> String {
+        format!(r#"{{"match":"{match_type}","name":"attribute","type":"custom_attribute","value":{value}}}"#)
+    }
+
+    /// Evaluate a leaf condition against a JSON encoded value of the `attribute` attribute
+    fn evaluate_leaf(match_type: &str, desired: &str, user: &str) -> Result<Option<bool>, Box<dyn Error>> {
+        evaluate(&leaf(match_type, desired), &format!(r#"{{"attribute":{user}}}"#))
+    }
+
+    #[test]
+    fn exact_match() -> Result<(), Box<dyn Error>> {
+        // String, integer, decimal and boolean values
+        assert_eq!(evaluate_leaf("exact", r#""firefox""#, r#""firefox""#)?, Some(true));
+        assert_eq!(evaluate_leaf("exact", r#""firefox""#, r#""chrome""#)?, Some(false));
+        assert_eq!(evaluate_leaf("exact", "9000", "9000")?, Some(true));
+        assert_eq!(evaluate_leaf("exact", "9000", "9000.0")?, Some(true));
+        assert_eq!(evaluate_leaf("exact", "9000", "900")?, Some(false));
+        assert_eq!(evaluate_leaf("exact", "9000.5", "9000.5")?, Some(true));
+        assert_eq!(evaluate_leaf("exact", "false", "false")?, Some(true));
+        assert_eq!(evaluate_leaf("exact", "false", "true")?, Some(false));
+
+        // Mismatched types are unknown
+        assert_eq!(evaluate_leaf("exact", r#""firefox""#, "false")?, None);
+        assert_eq!(evaluate_leaf("exact", r#""9000""#, "9000")?, None);
+        assert_eq!(evaluate_leaf("exact", "9000", r#""9000""#)?, None);
+        assert_eq!(evaluate_leaf("exact", "false", r#""false""#)?, None);
+        assert_eq!(evaluate_leaf("exact", "false", "0")?, None);
+
+        // Null and missing values are unknown
+        for desired in [r#""firefox""#, "9000", "false"] {
+            assert_eq!(evaluate_leaf("exact", desired, "null")?, None);
+            assert_eq!(evaluate(&leaf("exact", desired), "{}")?, None);
+        }
+
+        // Null and out of range desired values are unknown
+        assert_eq!(evaluate_leaf("exact", "null", r#""firefox""#)?, None);
+        assert_eq!(evaluate_leaf("exact", "9007199254740993", "9007199254740993")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn exists_match() -> Result<(), Box<dyn Error>> {
+        let json = r#"{"match":"exists","name":"attribute","type":"custom_attribute"}"#;
+
+        assert_eq!(evaluate(json, "{}")?, Some(false));
+        assert_eq!(evaluate(json, r#"{"attribute":null}"#)?, Some(false));
+        assert_eq!(evaluate(json, r#"{"attribute":""}"#)?, Some(true));
+        assert_eq!(evaluate(json, r#"{"attribute":"firefox"}"#)?, Some(true));
+        assert_eq!(evaluate(json, r#"{"attribute":10}"#)?, Some(true));
+        assert_eq!(evaluate(json, r#"{"attribute":10.5}"#)?, Some(true));
+        assert_eq!(evaluate(json, r#"{"attribute":false}"#)?, Some(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn substring_match() -> Result<(), Box<dyn Error>> {
+        let desired = r#""chrome""#;
+
+        assert_eq!(evaluate_leaf("substring", desired, r#""chrome""#)?, Some(true));
+        assert_eq!(evaluate_leaf("substring", desired, r#""chrome vs firefox""#)?, Some(true));
+        assert_eq!(evaluate_leaf("substring", desired, r#""firefox""#)?, Some(false));
+
+        // Non-string, null and missing values are unknown
+        assert_eq!(evaluate_leaf("substring", desired, "10")?, None);
+        assert_eq!(evaluate_leaf("substring", desired, "false")?, None);
+        assert_eq!(evaluate_leaf("substring", desired, "null")?, None);
+        assert_eq!(evaluate(&leaf("substring", desired), "{}")?, None);
+
+        // Non-string desired value is unknown
+        assert_eq!(evaluate_leaf("substring", "10", r#""10""#)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn numeric_matches() -> Result<(), Box<dyn Error>> {
+        // Match type with the results for a user value below, equal to and above the desired value
+        let fixtures = [
+            ("gt", [false, false, true]),
+            ("ge", [false, true, true]),
+            ("lt", [true, false, false]),
+            ("le", [true, true, false]),
+        ];
+
+        for (match_type, [below, equal, above]) in fixtures {
+            for desired in ["10", "10.0"] {
+                assert_eq!(evaluate_leaf(match_type, desired, "9")?, Some(below), "{match_type} {desired}");
+                assert_eq!(evaluate_leaf(match_type, desired, "9.99")?, Some(below), "{match_type} {desired}");
+                assert_eq!(evaluate_leaf(match_type, desired, "10")?, Some(equal), "{match_type} {desired}");
+                assert_eq!(evaluate_leaf(match_type, desired, "10.0")?, Some(equal), "{match_type} {desired}");
+                assert_eq!(evaluate_leaf(match_type, desired, "10.01")?, Some(above), "{match_type} {desired}");
+                assert_eq!(evaluate_leaf(match_type, desired, "11")?, Some(above), "{match_type} {desired}");
+
+                // Non-numeric, null and missing values are unknown
+                assert_eq!(evaluate_leaf(match_type, desired, r#""10""#)?, None);
+                assert_eq!(evaluate_leaf(match_type, desired, "false")?, None);
+                assert_eq!(evaluate_leaf(match_type, desired, "null")?, None);
+                assert_eq!(evaluate(&leaf(match_type, desired), "{}")?, None);
+
+                // Values beyond 2^53 are unknown
+                assert_eq!(evaluate_leaf(match_type, desired, "9007199254740993")?, None);
+                assert_eq!(evaluate_leaf(match_type, desired, "-9007199254740993")?, None);
+            }
+
+            // Non-numeric and null desired values are unknown
+            assert_eq!(evaluate_leaf(match_type, r#""10""#, "10")?, None);
+            assert_eq!(evaluate_leaf(match_type, "null", "10")?, None);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn semantic_versions() -> Result<(), Box<dyn Error>> {
+        // Pairs of desired and user version, where the user version is equal to the desired version
+        let equal = [
+            ("2.0", "2.0.0"),
+            ("2.0", "2.0.1"),
+            ("2.0", "2.0"),
+            ("2", "2.9.9"),
+            ("2.0.0", "2.0.0"),
+            ("3.7.1-prerelease", "3.7.1-prerelease"),
+            ("3.7.1+build", "3.7.1+build"),
+            ("3.7.1", "3.7.1+build"),
+            ("3.7.1-prerelease+build", "3.7.1-prerelease+build"),
+        ];
+        // Pairs of desired and user version, where the user version is greater than the desired version
+        let greater = [
+            ("2.0.0", "2.0.1"),
+            ("2.0.0", "2.1"),
+            ("2.0.0", "3.0"),
+            ("2.0", "2.1"),
+            ("2", "3"),
+            ("2.0.0-beta", "2.0.0"),
+            ("2.0.0-beta", "2.0.0-release"),
+            ("3.7.0-prerelease", "3.7.0-prerelease.2"),
+            ("3.7.0+build", "3.7.0"),
+            ("3.7.0", "3.7.1-beta"),
+        ];
+        // Pairs of desired and user version, where the user version is less than the desired version
+        let less = [
+            ("2.0.1", "2.0.0"),
+            ("2.1", "2.0.9"),
+            ("3.0", "2.9.9"),
+            ("2.0.0", "1.9"),
+            ("2.0.0", "2.0"),
+            ("2.0.0", "2.0.0-beta"),
+            ("2.0.0-release", "2.0.0-beta"),
+            ("3.7.0-prerelease.2", "3.7.0-prerelease.1"),
+            ("3.7.1", "3.7.0+build"),
+        ];
+
+        for (desired, user) in equal {
+            let (desired, user) = (format!(r#""{desired}""#), format!(r#""{user}""#));
+            assert_eq!(evaluate_leaf("semver_eq", &desired, &user)?, Some(true), "{user} = {desired}");
+            assert_eq!(evaluate_leaf("semver_ge", &desired, &user)?, Some(true), "{user} = {desired}");
+            assert_eq!(evaluate_leaf("semver_le", &desired, &user)?, Some(true), "{user} = {desired}");
+            assert_eq!(evaluate_leaf("semver_gt", &desired, &user)?, Some(false), "{user} = {desired}");
+            assert_eq!(evaluate_leaf("semver_lt", &desired, &user)?, Some(false), "{user} = {desired}");
+        }
+        for (desired, user) in greater {
+            let (desired, user) = (format!(r#""{desired}""#), format!(r#""{user}""#));
+            assert_eq!(evaluate_leaf("semver_eq", &desired, &user)?, Some(false), "{user} > {desired}");
+            assert_eq!(evaluate_leaf("semver_ge", &desired, &user)?, Some(true), "{user} > {desired}");
+            assert_eq!(evaluate_leaf("semver_le", &desired, &user)?, Some(false), "{user} > {desired}");
+            assert_eq!(evaluate_leaf("semver_gt", &desired, &user)?, Some(true), "{user} > {desired}");
+            assert_eq!(evaluate_leaf("semver_lt", &desired, &user)?, Some(false), "{user} > {desired}");
+        }
+        for (desired, user) in less {
+            let (desired, user) = (format!(r#""{desired}""#), format!(r#""{user}""#));
+            assert_eq!(evaluate_leaf("semver_eq", &desired, &user)?, Some(false), "{user} < {desired}");
+            assert_eq!(evaluate_leaf("semver_ge", &desired, &user)?, Some(false), "{user} < {desired}");
+            assert_eq!(evaluate_leaf("semver_le", &desired, &user)?, Some(true), "{user} < {desired}");
+            assert_eq!(evaluate_leaf("semver_gt", &desired, &user)?, Some(false), "{user} < {desired}");
+            assert_eq!(evaluate_leaf("semver_lt", &desired, &user)?, Some(true), "{user} < {desired}");
+        }
+
+        // Invalid version numbers are unknown
+        let invalid = [
+            "-",
+            ".",
+            "..",
+            "+",
+            "+test",
+            " ",
+            "2 .0. 0",
+            "2.",
+            ".2.2",
+            "3.7.2.2",
+            "3.x",
+            ",",
+            "+build-prerelease",
+            "2..0",
+            "latest",
+            "",
+        ];
+        for version in invalid {
+            let version = format!(r#""{version}""#);
+            assert_eq!(evaluate_leaf("semver_eq", r#""2.0.0""#, &version)?, None, "{version}");
+            assert_eq!(evaluate_leaf("semver_eq", &version, r#""2.0.0""#)?, None, "{version}");
+        }
+
+        // Non-string, null and missing values are unknown
+        assert_eq!(evaluate_leaf("semver_eq", r#""2.0.0""#, "2")?, None);
+        assert_eq!(evaluate_leaf("semver_eq", r#""2.0.0""#, "2.0")?, None);
+        assert_eq!(evaluate_leaf("semver_eq", r#""2.0.0""#, "true")?, None);
+        assert_eq!(evaluate_leaf("semver_eq", r#""2.0.0""#, "null")?, None);
+        assert_eq!(evaluate(&leaf("semver_eq", r#""2.0.0""#), "{}")?, None);
+        assert_eq!(evaluate_leaf("semver_eq", "2", r#""2""#)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn maximum_depth() {
+        // Nesting up to the maximum depth is allowed
+        let json = format!("{}{}", "[".repeat(MAX_CONDITION_DEPTH), "]".repeat(MAX_CONDITION_DEPTH));
+        assert!(serde_json::from_str::<Condition>(&json).is_ok());
+
+        // Any deeper nesting is rejected
+        let json = format!("{}{}", "[".repeat(MAX_CONDITION_DEPTH + 1), "]".repeat(MAX_CONDITION_DEPTH + 1));
+        assert!(serde_json::from_str::<Condition>(&json).is_err());
+    }
+
+    #[test]
+    fn serialize() -> Result<(), Box<dyn Error>> {
+        let json = r#"["and",["or",{"type":"custom_attribute","name":"appVersion","match":"semver_ge","value":"1.0.0"}],["not",{"type":"custom_attribute","name":"isLoggedIn","match":"exists","value":null}],{"name":"browser","type":"third_party_dimension"}]"#;
+
+        // Serializing a parsed condition results in the same JSON
+        let condition = serde_json::from_str::<Condition>(json)?;
+        assert_eq!(condition.to_string(), json);
+
+        Ok(())
+    }
 }
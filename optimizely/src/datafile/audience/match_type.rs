@@ -1,27 +1,40 @@
-use serde::Deserialize;
-
-#[derive(Deserialize, Debug, PartialEq)]
-#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq)]
 pub(crate) enum MatchType {
     Exists,
     Exact,
     Substring,
-    #[serde(rename = "lt")]
     LessThan,
-    #[serde(rename = "le")]
     LessThanOrEqual,
-    #[serde(rename = "gt")]
     GreaterThan,
-    #[serde(rename = "ge")]
     GreaterThanOrEqual,
-    #[serde(rename = "semver_eq")]
     SemVerEqual,
-    #[serde(rename = "semver_lt")]
     SemVerLessThan,
-    #[serde(rename = "semver_le")]
     SemVerLessThanOrEqual,
-    #[serde(rename = "semver_gt")]
     SemVerGreaterThan,
-    #[serde(rename = "semver_ge")]
     SemVerGreaterThanOrEqual,
 }
+
+impl TryFrom<&str> for MatchType {
+    type Error = ();
+
+    /// Parse the match type of a condition, or fail for match types that are not supported
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let match_type = match value {
+            "exists" => MatchType::Exists,
+            "exact" => MatchType::Exact,
+            "substring" => MatchType::Substring,
+            "lt" => MatchType::LessThan,
+            "le" => MatchType::LessThanOrEqual,
+            "gt" => MatchType::GreaterThan,
+            "ge" => MatchType::GreaterThanOrEqual,
+            "semver_eq" => MatchType::SemVerEqual,
+            "semver_lt" => MatchType::SemVerLessThan,
+            "semver_le" => MatchType::SemVerLessThanOrEqual,
+            "semver_gt" => MatchType::SemVerGreaterThan,
+            "semver_ge" => MatchType::SemVerGreaterThanOrEqual,
+            _ => return Err(()),
+        };
+
+        Ok(match_type)
+    }
+}
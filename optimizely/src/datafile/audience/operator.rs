@@ -1,6 +1,10 @@
-use serde::Deserialize;
+// External imports
+use std::cmp::Ordering;
 
-#[derive(Deserialize, Debug, PartialEq)]
+// Imports from super
+use super::match_type::MatchType;
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum NumericOperator {
     Equal,
     LessThan,
@@ -9,14 +13,89 @@ pub enum NumericOperator {
     GreaterThanOrEqual,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+impl NumericOperator {
+    /// Compare the value of a user attribute to the desired value
+    pub fn apply(&self, user_value: f64, desired_value: f64) -> bool {
+        match self {
+            NumericOperator::Equal => user_value == desired_value,
+            NumericOperator::LessThan => user_value < desired_value,
+            NumericOperator::LessThanOrEqual => user_value <= desired_value,
+            NumericOperator::GreaterThan => user_value > desired_value,
+            NumericOperator::GreaterThanOrEqual => user_value >= desired_value,
+        }
+    }
+
+    /// The match type as used in the datafile
+    pub fn match_type(&self) -> &'static str {
+        match self {
+            NumericOperator::Equal => "exact",
+            NumericOperator::LessThan => "lt",
+            NumericOperator::LessThanOrEqual => "le",
+            NumericOperator::GreaterThan => "gt",
+            NumericOperator::GreaterThanOrEqual => "ge",
+        }
+    }
+}
+
+impl TryFrom<MatchType> for NumericOperator {
+    type Error = ();
+
+    fn try_from(match_type: MatchType) -> Result<Self, Self::Error> {
+        let operator = match match_type {
+            MatchType::Exact => NumericOperator::Equal,
+            MatchType::LessThan => NumericOperator::LessThan,
+            MatchType::LessThanOrEqual => NumericOperator::LessThanOrEqual,
+            MatchType::GreaterThan => NumericOperator::GreaterThan,
+            MatchType::GreaterThanOrEqual => NumericOperator::GreaterThanOrEqual,
+            _ => return Err(()),
+        };
+
+        Ok(operator)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum StringOperator {
     Equal,
     Contains,
     SemVer(SemVerOperator),
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+impl StringOperator {
+    /// The match type as used in the datafile
+    pub fn match_type(&self) -> &'static str {
+        match self {
+            StringOperator::Equal => "exact",
+            StringOperator::Contains => "substring",
+            StringOperator::SemVer(SemVerOperator::Equal) => "semver_eq",
+            StringOperator::SemVer(SemVerOperator::LessThan) => "semver_lt",
+            StringOperator::SemVer(SemVerOperator::LessThanOrEqual) => "semver_le",
+            StringOperator::SemVer(SemVerOperator::GreaterThan) => "semver_gt",
+            StringOperator::SemVer(SemVerOperator::GreaterThanOrEqual) => "semver_ge",
+        }
+    }
+}
+
+impl TryFrom<MatchType> for StringOperator {
+    type Error = ();
+
+    fn try_from(match_type: MatchType) -> Result<Self, Self::Error> {
+        let operator = match match_type {
+            MatchType::Exact => StringOperator::Equal,
+            MatchType::Substring => StringOperator::Contains,
+            MatchType::SemVerEqual => StringOperator::SemVer(SemVerOperator::Equal),
+            MatchType::SemVerLessThan => StringOperator::SemVer(SemVerOperator::LessThan),
+            MatchType::SemVerLessThanOrEqual => StringOperator::SemVer(SemVerOperator::LessThanOrEqual),
+            MatchType::SemVerGreaterThan => StringOperator::SemVer(SemVerOperator::GreaterThan),
+            MatchType::SemVerGreaterThanOrEqual => StringOperator::SemVer(SemVerOperator::GreaterThanOrEqual),
+            _ => return Err(()),
+        };
+
+        Ok(operator)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum SemVerOperator {
     Equal,
     LessThan,
@@ -24,3 +103,16 @@ pub enum SemVerOperator {
     GreaterThan,
     GreaterThanOrEqual,
 }
+
+impl SemVerOperator {
+    /// Check whether the ordering of the user version relative to the desired version satisfies the operator
+    pub fn apply(&self, ordering: Ordering) -> bool {
+        match self {
+            SemVerOperator::Equal => ordering.is_eq(),
+            SemVerOperator::LessThan => ordering.is_lt(),
+            SemVerOperator::LessThanOrEqual => ordering.is_le(),
+            SemVerOperator::GreaterThan => ordering.is_gt(),
+            SemVerOperator::GreaterThanOrEqual => ordering.is_ge(),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+// External imports
+use std::cmp::Ordering;
+
+/// Compare the version of a user to the desired version, or return None if either is invalid
+///
+/// Follows the comparison of the other Optimizely SDKs rather than strict semantic versioning, so partial versions
+/// like `2.0` are allowed and only the parts present in the desired version are compared.
+pub(crate) fn compare(user_version: &str, desired_version: &str) -> Option<Ordering> {
+    let user_parts = split(user_version)?;
+    let desired_parts = split(desired_version)?;
+
+    for (index, desired_part) in desired_parts.iter().enumerate() {
+        let Some(user_part) = user_parts.get(index) else {
+            // The user version is shorter, which is only higher if the desired version has a suffix
+            return match is_pre_release(desired_version) || is_build(desired_version) {
+                true => Some(Ordering::Greater),
+                false => Some(Ordering::Less),
+            };
+        };
+
+        if !is_number(user_part) {
+            // Suffixes are compared as strings, but a pre-release is always lower than the release itself
+            match user_part.cmp(desired_part) {
+                Ordering::Less if is_pre_release(desired_version) && !is_pre_release(user_version) => {
+                    return Some(Ordering::Greater)
+                }
+                Ordering::Greater if !is_pre_release(desired_version) && is_pre_release(user_version) => {
+                    return Some(Ordering::Less)
+                }
+                Ordering::Equal => {}
+                ordering => return Some(ordering),
+            }
+        } else if is_number(desired_part) {
+            match user_part
+                .parse::<u64>()
+                .ok()?
+                .cmp(&desired_part.parse::<u64>().ok()?)
+            {
+                Ordering::Equal => {}
+                ordering => return Some(ordering),
+            }
+        }
+        // A number is never compared to a suffix
+    }
+
+    // A pre-release is lower than the release itself
+    match is_pre_release(user_version) && !is_pre_release(desired_version) {
+        true => Some(Ordering::Less),
+        false => Some(Ordering::Equal),
+    }
+}
+
+/// Split a version into at most three numbers followed by an optional suffix, or return None if it is invalid
+fn split(version: &str) -> Option<Vec<&str>> {
+    if version.chars().any(char::is_whitespace) {
+        return None;
+    }
+
+    // Whichever of '-' and '+' comes first separates the numbers from the pre-release or build suffix
+    let (prefix, suffix) = match version.find(['-', '+']) {
+        Some(index) => (&version[..index], &version[index + 1..]),
+        None => (version, ""),
+    };
+
+    let mut parts = prefix.split('.').collect::<Vec<_>>();
+    if parts.len() > 3 || !parts.iter().all(|part| is_number(part)) {
+        return None;
+    }
+
+    if !suffix.is_empty() {
+        parts.push(suffix);
+    }
+    Some(parts)
+}
+
+/// Whether the version has a pre-release suffix, like `1.0.0-beta`
+fn is_pre_release(version: &str) -> bool {
+    version
+        .find(['-', '+'])
+        .is_some_and(|index| version[index..].starts_with('-'))
+}
+
+/// Whether the version has a build suffix, like `1.0.0+build`
+fn is_build(version: &str) -> bool {
+    version
+        .find(['-', '+'])
+        .is_some_and(|index| version[index..].starts_with('+'))
+}
+
+/// Whether the part consists of digits only
+fn is_number(part: &str) -> bool {
+    !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit())
+}
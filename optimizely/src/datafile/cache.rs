@@ -0,0 +1,98 @@
+// External imports
+use error_stack::{Result, ResultExt};
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// Imports from crate
+use crate::error::DatafileError;
+
+// Imports from super
+use super::Datafile;
+
+/// Persistent cache of the last datafile that was successfully downloaded from the CDN
+///
+/// Every datafile is stored in a separate file per SDK key within the cache directory.
+/// Files are written to a temporary file first and then renamed, so a crash never leaves a partially written datafile.
+#[derive(Debug, Clone)]
+pub struct DatafileCache {
+    directory: PathBuf,
+}
+
+impl DatafileCache {
+    /// Create a cache that stores datafiles in the given directory
+    ///
+    /// The directory is created when the first datafile is stored.
+    pub fn new(directory: impl AsRef<Path>) -> DatafileCache {
+        DatafileCache {
+            directory: directory.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Getter for `directory` field
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Path of the cached datafile for the given SDK key
+    ///
+    /// Characters that are not safe to use in a file name are percent-encoded,
+    /// so every SDK key has its own file.
+    pub fn path(&self, sdk_key: &str) -> PathBuf {
+        let mut file_name = String::with_capacity(sdk_key.len());
+        for byte in sdk_key.bytes() {
+            if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+                file_name.push(char::from(byte));
+            } else {
+                // Writing to a String cannot fail
+                let _ = write!(file_name, "%{byte:02X}");
+            }
+        }
+
+        self.directory.join(format!("{file_name}.json"))
+    }
+
+    /// Read the cached datafile for the given SDK key
+    pub fn load(&self, sdk_key: &str) -> Result<Datafile, DatafileError> {
+        let path = self.path(sdk_key);
+
+        // Read file content into String
+        let content = fs::read_to_string(&path)
+            .change_context(DatafileError::FailedFileRead)
+            .attach_printable_lazy(|| format!("Cached datafile: {}", path.display()))?;
+
+        Datafile::from_string(content)
+    }
+
+    /// Atomically replace the cached datafile for the given SDK key
+    pub fn store(&self, sdk_key: &str, content: &str) -> Result<(), DatafileError> {
+        let path = self.path(sdk_key);
+        let temporary_path = path.with_extension("json.tmp");
+
+        // Make sure the directory exists
+        fs::create_dir_all(&self.directory).change_context(DatafileError::FailedFileWrite)?;
+
+        // Write to a temporary file first and make sure it is on disk, then move it in place
+        let mut file = File::create(&temporary_path).change_context(DatafileError::FailedFileWrite)?;
+        file.write_all(content.as_bytes())
+            .and_then(|_| file.sync_all())
+            .change_context(DatafileError::FailedFileWrite)
+            .attach_printable_lazy(|| format!("Temporary datafile: {}", temporary_path.display()))?;
+        fs::rename(&temporary_path, &path)
+            .change_context(DatafileError::FailedFileWrite)
+            .attach_printable_lazy(|| format!("Cached datafile: {}", path.display()))?;
+
+        Ok(())
+    }
+
+    /// Store the datafile, and only log an error if that fails
+    ///
+    /// Failing to update the cache should never affect the client itself.
+    pub(crate) fn store_or_log(&self, sdk_key: &str, content: &str) {
+        if let Err(report) = self.store(sdk_key, content) {
+            log::error!("Failed to store datafile in cache");
+            log::error!("\n{report:?}");
+        }
+    }
+}
@@ -1,31 +1,35 @@
 // External imports
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 // Imports from super
+use super::validation::{validate_experiment, validate_variables, ValidationIssue, SUPPORTED_VERSIONS};
 use super::{
-    audience::Audience, rollout::Rollout, Attribute, AttributeMap, AudienceMap, Experiment, ExperimentMap, FeatureFlag,
-    FeatureFlagMap, Revision, RolloutMap,
+    audience::Audience, rollout::Rollout, Attribute, AttributeMap, AudienceMap, Event, EventMap, Experiment,
+    ExperimentMap, FeatureFlag, FeatureFlagMap, Region, Revision, RolloutMap,
 };
-#[cfg(feature = "online")]
-use super::{Event, EventMap};
 
 /// Each Datafile is for exactly one Environment, so most methods are implemented on Environment instead of Datafile
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Environment {
+    #[serde(default)]
+    version: String,
     account_id: String,
     project_id: String,
     environment_key: String,
     sdk_key: String,
     revision: Revision,
+    #[serde(default)]
+    region: Region,
     bot_filtering: bool,
     #[serde(rename = "anonymizeIP")]
     anonymize_ip: bool,
-    #[cfg(feature = "online")]
+    #[serde(default)]
+    send_flag_decisions: bool,
     events: EventMap,
     attributes: AttributeMap,
     #[serde(rename = "typedAudiences")]
-    #[allow(dead_code)]
     audiences: AudienceMap,
     experiments: ExperimentMap,
     rollouts: RolloutMap,
@@ -33,6 +37,20 @@ pub struct Environment {
 }
 
 impl Environment {
+    /// Construct an environment without any flags, for which every decision is "off"
+    #[cfg(feature = "online")]
+    pub(crate) fn empty(sdk_key: &str) -> Environment {
+        Environment {
+            sdk_key: sdk_key.into(),
+            ..Environment::default()
+        }
+    }
+
+    /// Getter for `version` field
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
     /// Getter for `account_id` field
     pub fn account_id(&self) -> &str {
         &self.account_id
@@ -43,7 +61,7 @@ impl Environment {
         &self.sdk_key
     }
 
-    #[allow(dead_code)]
+    /// Getter for `project_id` field
     pub fn project_id(&self) -> &str {
         &self.project_id
     }
@@ -58,16 +76,26 @@ impl Environment {
         *self.revision
     }
 
+    /// Getter for `region` field
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
     #[allow(dead_code)]
     pub fn bot_filtering(&self) -> bool {
         self.bot_filtering
     }
 
-    #[allow(dead_code)]
+    /// Getter for `anonymize_ip` field
     pub fn anonymize_ip(&self) -> bool {
         self.anonymize_ip
     }
 
+    /// Whether decision events are also sent for delivery rules and for the "off" variation
+    pub fn send_flag_decisions(&self) -> bool {
+        self.send_flag_decisions
+    }
+
     /// Get the flag with the given key
     pub(crate) fn flag(&self, flag_key: &str) -> Option<&FeatureFlag> {
         self.feature_flags.get(flag_key).or_else(|| {
@@ -93,7 +121,6 @@ impl Environment {
     }
 
     /// Get the event with the given key
-    #[cfg(feature = "online")]
     pub(crate) fn event(&self, event_key: &str) -> Option<&Event> {
         self.events.get(event_key).or_else(|| {
             log::warn!("Event key '{event_key}' does not exist in datafile");
@@ -116,4 +143,177 @@ impl Environment {
             None
         })
     }
+
+    /// Iterate over all flags
+    pub(crate) fn flags(&self) -> impl Iterator<Item = &FeatureFlag> {
+        self.feature_flags.values()
+    }
+
+    /// Iterate over all experiments, which does not include the rules of rollouts
+    pub(crate) fn experiments(&self) -> impl Iterator<Item = &Experiment> {
+        self.experiments.values()
+    }
+
+    /// Iterate over all events
+    pub(crate) fn events(&self) -> impl Iterator<Item = &Event> {
+        self.events.values()
+    }
+
+    /// Iterate over all attributes
+    pub(crate) fn attributes(&self) -> impl Iterator<Item = &Attribute> {
+        self.attributes.values()
+    }
+
+    /// Iterate over all audiences
+    pub(crate) fn audiences(&self) -> impl Iterator<Item = &Audience> {
+        self.audiences.values()
+    }
+
+    /// Copy of this environment with only the given flags, and the experiments, rollouts, audiences,
+    /// attributes and events they reference
+    pub(crate) fn subset(&self, flag_keys: &HashSet<&str>) -> Environment {
+        let feature_flags = self
+            .feature_flags
+            .values()
+            .filter(|flag| flag_keys.contains(flag.key()))
+            .cloned()
+            .collect::<FeatureFlagMap>();
+
+        let experiments = feature_flags
+            .values()
+            .flat_map(|flag| flag.experiments_ids())
+            .filter_map(|experiment_id| self.experiments.get(experiment_id))
+            .cloned()
+            .collect::<ExperimentMap>();
+
+        let rollouts = feature_flags
+            .values()
+            .filter_map(|flag| self.rollouts.get(flag.rollout_id()))
+            .cloned()
+            .collect::<RolloutMap>();
+
+        // Audiences that are used by any experiment or rollout rule
+        let rules = rollouts
+            .values()
+            .flat_map(|rollout| rollout.experiments().iter());
+        let audience_ids = experiments
+            .values()
+            .chain(rules)
+            .flat_map(|experiment| experiment.referenced_audience_ids())
+            .collect::<HashSet<_>>();
+        let audiences = self
+            .audiences
+            .values()
+            .filter(|audience| audience_ids.contains(audience.id()))
+            .cloned()
+            .collect::<AudienceMap>();
+
+        // Attributes that are used by any audience
+        let attribute_keys = audiences
+            .values()
+            .flat_map(|audience| audience.condition().attribute_names())
+            .collect::<HashSet<_>>();
+        let attributes = self
+            .attributes
+            .values()
+            .filter(|attribute| attribute_keys.contains(attribute.key()))
+            .cloned()
+            .collect::<AttributeMap>();
+
+        // Events that are used as metric by any experiment
+        let events = self
+            .events
+            .values()
+            .filter(|event| {
+                event
+                    .experiment_ids()
+                    .iter()
+                    .any(|experiment_id| experiments.get(experiment_id).is_some())
+            })
+            .cloned()
+            .collect::<EventMap>();
+
+        Environment {
+            version: self.version.clone(),
+            account_id: self.account_id.clone(),
+            project_id: self.project_id.clone(),
+            environment_key: self.environment_key.clone(),
+            sdk_key: self.sdk_key.clone(),
+            revision: self.revision.clone(),
+            region: self.region,
+            bot_filtering: self.bot_filtering,
+            anonymize_ip: self.anonymize_ip,
+            send_flag_decisions: self.send_flag_decisions,
+            events,
+            attributes,
+            audiences,
+            experiments,
+            rollouts,
+            feature_flags,
+        }
+    }
+
+    /// Find all cross-reference problems within the datafile
+    pub(crate) fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        // Only version 4 datafiles contain feature flags
+        if !SUPPORTED_VERSIONS.contains(&self.version.as_str()) {
+            issues.push(ValidationIssue::UnsupportedVersion {
+                version: self.version.clone(),
+            });
+        }
+
+        // Every flag should reference existing experiments and an existing rollout
+        for flag in self.feature_flags.values() {
+            for experiment_id in flag.experiments_ids() {
+                if self.experiments.get(experiment_id).is_none() {
+                    issues.push(ValidationIssue::MissingExperiment {
+                        flag_key: flag.key().into(),
+                        experiment_id: experiment_id.clone(),
+                    });
+                }
+            }
+
+            if self.rollouts.get(flag.rollout_id()).is_none() {
+                issues.push(ValidationIssue::MissingRollout {
+                    flag_key: flag.key().into(),
+                    rollout_id: flag.rollout_id().into(),
+                });
+            }
+
+            // Every variation of the flag should only set variables of the flag
+            let variable_exists = |variable_id: &str| {
+                flag.variables()
+                    .iter()
+                    .any(|variable| variable.id() == variable_id)
+            };
+            let experiments = flag
+                .experiments_ids()
+                .iter()
+                .filter_map(|experiment_id| self.experiments.get(experiment_id));
+            let rules = self
+                .rollouts
+                .get(flag.rollout_id())
+                .into_iter()
+                .flat_map(|rollout| rollout.experiments().iter());
+            for experiment in experiments.chain(rules) {
+                validate_variables(experiment, variable_exists, &mut issues);
+            }
+        }
+
+        // Every experiment and rollout rule should reference existing audiences and variations
+        let audience_exists = |audience_id: &str| self.audiences.get(audience_id).is_some();
+        let rules = self
+            .rollouts
+            .values()
+            .flat_map(|rollout| rollout.experiments().iter());
+        for experiment in self.experiments.values().chain(rules) {
+            validate_experiment(experiment, audience_exists, &mut issues);
+        }
+
+        // Sort the issues, since the maps are not ordered
+        issues.sort();
+        issues
+    }
 }
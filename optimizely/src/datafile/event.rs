@@ -1,11 +1,13 @@
 // External imports
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub(crate) struct Event {
     id: String,
     key: String,
+    #[serde(default, rename = "experimentIds")]
+    experiment_ids: Vec<String>,
 }
 
 impl Event {
@@ -15,13 +17,17 @@ impl Event {
     }
 
     /// Getter for `key` field
-    #[allow(dead_code)]
     pub fn key(&self) -> &str {
         &self.key
     }
+
+    /// Getter for `experiment_ids` field
+    pub fn experiment_ids(&self) -> &[String] {
+        &self.experiment_ids
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default, Clone)]
 pub(crate) struct EventMap(HashMap<String, Event>);
 
 impl<'de> Deserialize<'de> for EventMap {
@@ -29,17 +35,43 @@ impl<'de> Deserialize<'de> for EventMap {
     where
         D: Deserializer<'de>,
     {
-        let mut map = HashMap::new();
-        for event in Vec::<Event>::deserialize(deserializer)? {
-            map.insert(event.key.clone(), event);
-        }
+        let events = Vec::<Event>::deserialize(deserializer)?;
+        Ok(events.into_iter().collect())
+    }
+}
 
-        Ok(Self(map))
+impl Serialize for EventMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Sort by ID, so the output does not depend on the order of the HashMap
+        let mut events = self.0.values().collect::<Vec<_>>();
+        events.sort_by_key(|event| &event.id);
+        serializer.collect_seq(events)
+    }
+}
+
+impl FromIterator<Event> for EventMap {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Event>,
+    {
+        Self(
+            iter.into_iter()
+                .map(|event| (event.key.clone(), event))
+                .collect(),
+        )
     }
 }
 
 impl EventMap {
+    #[allow(dead_code)]
     pub fn get(&self, key: &str) -> Option<&Event> {
         self.0.get(key)
     }
+
+    pub fn values(&self) -> impl Iterator<Item = &Event> {
+        self.0.values()
+    }
 }
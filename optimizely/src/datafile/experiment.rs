@@ -1,20 +1,33 @@
 // External imports
-use serde::{Deserialize, Deserializer};
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 
 // Imports from super
 use super::{TrafficAllocation, Variation, VariationMap};
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Experiment {
     #[serde()]
     id: String,
+    key: String,
+    #[serde(default = "default_status")]
+    status: String,
     #[serde(rename = "layerId")]
     campaign_id: String,
     traffic_allocation: TrafficAllocation,
     audience_ids: Vec<String>, // TODO: use audienceConditions instead of audienceIds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    audience_conditions: Option<Value>,
     variations: VariationMap,
+    #[serde(default)]
+    forced_variations: HashMap<String, String>,
+}
+
+/// Experiments without status are running
+fn default_status() -> String {
+    String::from("Running")
 }
 
 impl Experiment {
@@ -22,6 +35,10 @@ impl Experiment {
         &self.id
     }
 
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
     pub fn campaign_id(&self) -> &str {
         &self.campaign_id
     }
@@ -34,12 +51,39 @@ impl Experiment {
         self.variations.get(variation_id)
     }
 
+    pub fn variations(&self) -> impl Iterator<Item = &Variation> {
+        self.variations.values()
+    }
+
     pub fn audience_ids(&self) -> &[String] {
         &self.audience_ids
     }
+
+    /// IDs of all audiences that are referenced by either the audience IDs or the audience conditions
+    pub fn referenced_audience_ids(&self) -> HashSet<&str> {
+        let mut audience_ids = self
+            .audience_ids
+            .iter()
+            .map(String::as_str)
+            .collect::<HashSet<_>>();
+
+        // Audience conditions are nested sequences of operators and audience IDs
+        let mut stack = self.audience_conditions.iter().collect::<Vec<_>>();
+        while let Some(value) = stack.pop() {
+            match value {
+                Value::Array(values) => stack.extend(values),
+                Value::String(text) if !matches!(text.as_str(), "and" | "or" | "not") => {
+                    audience_ids.insert(text);
+                }
+                _ => {}
+            }
+        }
+
+        audience_ids
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default, Clone)]
 pub struct ExperimentMap(HashMap<String, Experiment>);
 
 impl<'de> Deserialize<'de> for ExperimentMap {
@@ -47,12 +91,33 @@ impl<'de> Deserialize<'de> for ExperimentMap {
     where
         D: Deserializer<'de>,
     {
-        let mut map = HashMap::new();
-        for experiment in Vec::<Experiment>::deserialize(deserializer)? {
-            map.insert(experiment.id.clone(), experiment);
-        }
+        let experiments = Vec::<Experiment>::deserialize(deserializer)?;
+        Ok(experiments.into_iter().collect())
+    }
+}
 
-        Ok(Self(map))
+impl Serialize for ExperimentMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Sort by ID, so the output does not depend on the order of the HashMap
+        let mut experiments = self.0.values().collect::<Vec<_>>();
+        experiments.sort_by_key(|experiment| &experiment.id);
+        serializer.collect_seq(experiments)
+    }
+}
+
+impl FromIterator<Experiment> for ExperimentMap {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Experiment>,
+    {
+        Self(
+            iter.into_iter()
+                .map(|experiment| (experiment.id.clone(), experiment))
+                .collect(),
+        )
     }
 }
 
@@ -60,4 +125,8 @@ impl ExperimentMap {
     pub fn get(&self, id: &str) -> Option<&Experiment> {
         self.0.get(id)
     }
+
+    pub fn values(&self) -> impl Iterator<Item = &Experiment> {
+        self.0.values()
+    }
 }
@@ -1,19 +1,28 @@
 // External imports
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 
+// Imports from super
+use super::Variable;
+
 /// Optimizely feature flag.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct FeatureFlag {
+    id: String,
     key: String,
     rollout_id: String,
     experiment_ids: Vec<String>,
-    // TODO: variables
+    #[serde(default)]
+    variables: Vec<Variable>,
 }
 
 impl FeatureFlag {
-    #[allow(dead_code)]
+    /// Getter for `id` field
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
     pub fn key(&self) -> &str {
         &self.key
     }
@@ -25,9 +34,14 @@ impl FeatureFlag {
     pub fn experiments_ids(&self) -> &[String] {
         &self.experiment_ids
     }
+
+    /// Getter for `variables` field
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default, Clone)]
 pub(crate) struct FeatureFlagMap(HashMap<String, FeatureFlag>);
 
 impl<'de> Deserialize<'de> for FeatureFlagMap {
@@ -35,12 +49,33 @@ impl<'de> Deserialize<'de> for FeatureFlagMap {
     where
         D: Deserializer<'de>,
     {
-        let mut map = HashMap::new();
-        for flag in Vec::<FeatureFlag>::deserialize(deserializer)? {
-            map.insert(flag.key.clone(), flag);
-        }
+        let flags = Vec::<FeatureFlag>::deserialize(deserializer)?;
+        Ok(flags.into_iter().collect())
+    }
+}
+
+impl Serialize for FeatureFlagMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Sort by ID, so the output does not depend on the order of the HashMap
+        let mut flags = self.0.values().collect::<Vec<_>>();
+        flags.sort_by_key(|flag| &flag.id);
+        serializer.collect_seq(flags)
+    }
+}
 
-        Ok(Self(map))
+impl FromIterator<FeatureFlag> for FeatureFlagMap {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = FeatureFlag>,
+    {
+        Self(
+            iter.into_iter()
+                .map(|flag| (flag.key.clone(), flag))
+                .collect(),
+        )
     }
 }
 
@@ -48,4 +83,8 @@ impl FeatureFlagMap {
     pub fn get(&self, key: &str) -> Option<&FeatureFlag> {
         self.0.get(key)
     }
+
+    pub fn values(&self) -> impl Iterator<Item = &FeatureFlag> {
+        self.0.values()
+    }
 }
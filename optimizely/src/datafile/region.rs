@@ -0,0 +1,65 @@
+// External imports
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+
+// Imports from crate
+use crate::event_api::{DEFAULT_EVENT_API_URL, EU_EVENT_API_URL};
+
+/// Data residency region of the project, which decides where events are sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Region {
+    /// United States, which is used when the datafile does not contain a region
+    #[default]
+    US,
+    /// European Union
+    EU,
+}
+
+impl Region {
+    /// Base URL of the Event API in this region
+    pub fn event_api_url(&self) -> &'static str {
+        match self {
+            Region::US => DEFAULT_EVENT_API_URL,
+            Region::EU => EU_EVENT_API_URL,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Region {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let region = String::deserialize(deserializer)?;
+
+        // Newer regions should not make older SDKs reject the datafile
+        let region = match region.as_str() {
+            "US" => Region::US,
+            "EU" => Region::EU,
+            _ => {
+                log::warn!("Unknown region '{region}' in datafile, using US");
+                Region::US
+            }
+        };
+
+        Ok(region)
+    }
+}
+
+impl Serialize for Region {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl Display for Region {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Region::US => write!(f, "US"),
+            Region::EU => write!(f, "EU"),
+        }
+    }
+}
@@ -1,9 +1,9 @@
 // External imports
 use serde::de::Error;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::ops::Deref;
 
-#[derive(Debug)]
+#[derive(Debug, Default, Clone)]
 pub struct Revision(u32);
 
 impl<'de> Deserialize<'de> for Revision {
@@ -26,6 +26,16 @@ impl<'de> Deserialize<'de> for Revision {
     }
 }
 
+impl Serialize for Revision {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Store the revision as String type again
+        serializer.serialize_str(&self.0.to_string())
+    }
+}
+
 impl Deref for Revision {
     type Target = u32;
 
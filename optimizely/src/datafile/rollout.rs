@@ -1,11 +1,11 @@
 // External imports
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 
 // Imports from super
 use super::Experiment;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub(crate) struct Rollout {
     id: String,
     experiments: Vec<Experiment>,
@@ -22,7 +22,7 @@ impl Rollout {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default, Clone)]
 pub(crate) struct RolloutMap(HashMap<String, Rollout>);
 
 impl<'de> Deserialize<'de> for RolloutMap {
@@ -30,12 +30,33 @@ impl<'de> Deserialize<'de> for RolloutMap {
     where
         D: Deserializer<'de>,
     {
-        let mut map = HashMap::new();
-        for rollout in Vec::<Rollout>::deserialize(deserializer)? {
-            map.insert(rollout.id.clone(), rollout);
-        }
+        let rollouts = Vec::<Rollout>::deserialize(deserializer)?;
+        Ok(rollouts.into_iter().collect())
+    }
+}
 
-        Ok(Self(map))
+impl Serialize for RolloutMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Sort by ID, so the output does not depend on the order of the HashMap
+        let mut rollouts = self.0.values().collect::<Vec<_>>();
+        rollouts.sort_by_key(|rollout| &rollout.id);
+        serializer.collect_seq(rollouts)
+    }
+}
+
+impl FromIterator<Rollout> for RolloutMap {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Rollout>,
+    {
+        Self(
+            iter.into_iter()
+                .map(|rollout| (rollout.id.clone(), rollout))
+                .collect(),
+        )
     }
 }
 
@@ -43,4 +64,8 @@ impl RolloutMap {
     pub fn get(&self, id: &str) -> Option<&Rollout> {
         self.0.get(id)
     }
+
+    pub fn values(&self) -> impl Iterator<Item = &Rollout> {
+        self.0.values()
+    }
 }
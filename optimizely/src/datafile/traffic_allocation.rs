@@ -1,26 +1,48 @@
 // External imports
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::BTreeMap;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-struct Range {
+pub struct Range {
     #[serde(rename = "entityId")]
     variation_id: String,
     end_of_range: u64,
 }
 
-#[derive(Debug)]
-pub struct TrafficAllocation(BTreeMap<u64, String>);
+impl Range {
+    /// Getter for `variation_id` field
+    pub fn variation_id(&self) -> &str {
+        &self.variation_id
+    }
+
+    /// Getter for `end_of_range` field
+    pub fn end_of_range(&self) -> u64 {
+        self.end_of_range
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrafficAllocation {
+    // Lookup tree from end of range to variation ID
+    tree: BTreeMap<u64, String>,
+    // Ranges in the order of the datafile, used for validation
+    ranges: Vec<Range>,
+}
 
 impl TrafficAllocation {
     pub fn variation(&self, bucket_value: u64) -> Option<&str> {
         // Use BTreeMap::range to find the variation in O(log(n))
-        self.0
+        self.tree
             .range(bucket_value..)
             .next()
             .map(|(_, variation)| variation.as_ref())
     }
+
+    /// Get the ranges in the order of the datafile
+    pub fn ranges(&self) -> &[Range] {
+        &self.ranges
+    }
 }
 
 impl<'de> Deserialize<'de> for TrafficAllocation {
@@ -28,12 +50,24 @@ impl<'de> Deserialize<'de> for TrafficAllocation {
     where
         D: Deserializer<'de>,
     {
+        let ranges = Vec::<Range>::deserialize(deserializer)?;
+
         let mut tree = BTreeMap::new();
-        for range in Vec::<Range>::deserialize(deserializer)? {
-            tree.insert(range.end_of_range, range.variation_id);
+        for range in &ranges {
+            tree.insert(range.end_of_range, range.variation_id.clone());
         }
 
-        Ok(Self(tree))
+        Ok(Self { tree, ranges })
+    }
+}
+
+impl Serialize for TrafficAllocation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Keep the order of the datafile
+        self.ranges.serialize(serializer)
     }
 }
 
@@ -44,12 +78,15 @@ mod tests {
     #[test]
     fn variation() {
         let traffic_allocation = {
-            let mut ranges = BTreeMap::<u64, String>::new();
-            ranges.insert(3_333, String::from("A"));
-            ranges.insert(6_666, String::from("B"));
-            ranges.insert(10_000, String::from("C"));
+            let mut tree = BTreeMap::<u64, String>::new();
+            tree.insert(3_333, String::from("A"));
+            tree.insert(6_666, String::from("B"));
+            tree.insert(10_000, String::from("C"));
 
-            TrafficAllocation(ranges)
+            TrafficAllocation {
+                tree,
+                ranges: Vec::new(),
+            }
         };
 
         assert_eq!(traffic_allocation.variation(0), Some("A"));
@@ -0,0 +1,172 @@
+// External imports
+use thiserror::Error;
+
+// Imports from super
+use super::Experiment;
+
+/// Datafile versions that can be parsed by this SDK
+pub(crate) const SUPPORTED_VERSIONS: [&str; 1] = ["4"];
+
+/// Range values are between 0 and 10_000 (inclusive)
+const MAX_END_OF_RANGE: u64 = 10_000;
+
+/// A problem within a datafile that is syntactically valid, but would lead to unexpected decisions
+///
+/// See [Datafile::validate](super::Datafile::validate).
+#[derive(Error, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum ValidationIssue {
+    /// The datafile version is not supported
+    #[error("Datafile version '{version}' is not supported")]
+    UnsupportedVersion {
+        /// Version of the datafile
+        version: String,
+    },
+    /// A flag references a rollout that does not exist
+    #[error("Flag '{flag_key}' references rollout '{rollout_id}' which does not exist")]
+    MissingRollout {
+        /// Key of the flag
+        flag_key: String,
+        /// ID of the missing rollout
+        rollout_id: String,
+    },
+    /// A flag references an experiment that does not exist
+    #[error("Flag '{flag_key}' references experiment '{experiment_id}' which does not exist")]
+    MissingExperiment {
+        /// Key of the flag
+        flag_key: String,
+        /// ID of the missing experiment
+        experiment_id: String,
+    },
+    /// An experiment references an audience that does not exist
+    #[error("Experiment '{experiment_id}' references audience '{audience_id}' which does not exist")]
+    MissingAudience {
+        /// ID of the experiment
+        experiment_id: String,
+        /// ID of the missing audience
+        audience_id: String,
+    },
+    /// A traffic allocation of an experiment points to a variation that does not exist
+    #[error(
+        "Traffic allocation of experiment '{experiment_id}' references variation '{variation_id}' which does not exist"
+    )]
+    MissingVariation {
+        /// ID of the experiment
+        experiment_id: String,
+        /// ID of the missing variation
+        variation_id: String,
+    },
+    /// A variation sets the value of a variable that its flag does not have
+    #[error(
+        "Variation '{variation_id}' of experiment '{experiment_id}' sets variable '{variable_id}' which does not exist"
+    )]
+    MissingVariable {
+        /// ID of the experiment
+        experiment_id: String,
+        /// ID of the variation
+        variation_id: String,
+        /// ID of the missing variable
+        variable_id: String,
+    },
+    /// A traffic range ends before the previous range
+    #[error("Traffic allocation of experiment '{experiment_id}' is not sorted at end of range {end_of_range}")]
+    UnsortedTrafficRange {
+        /// ID of the experiment
+        experiment_id: String,
+        /// End of the range that is out of order
+        end_of_range: u64,
+    },
+    /// A traffic range ends at the same value as the previous range
+    #[error(
+        "Traffic allocation of experiment '{experiment_id}' has overlapping ranges at end of range {end_of_range}"
+    )]
+    OverlappingTrafficRange {
+        /// ID of the experiment
+        experiment_id: String,
+        /// End of the range that overlaps
+        end_of_range: u64,
+    },
+    /// A traffic range ends beyond the maximum bucket value
+    #[error("Traffic allocation of experiment '{experiment_id}' has end of range {end_of_range} beyond 10000")]
+    TrafficRangeOutOfBounds {
+        /// ID of the experiment
+        experiment_id: String,
+        /// End of the range that is out of bounds
+        end_of_range: u64,
+    },
+}
+
+/// Validate that the variations of a single experiment only set variables of its flag
+pub(crate) fn validate_variables<F>(experiment: &Experiment, variable_exists: F, issues: &mut Vec<ValidationIssue>)
+where
+    F: Fn(&str) -> bool,
+{
+    for variation in experiment.variations() {
+        for variable_id in variation.variable_ids() {
+            if !variable_exists(variable_id) {
+                issues.push(ValidationIssue::MissingVariable {
+                    experiment_id: experiment.id().into(),
+                    variation_id: variation.id().into(),
+                    variable_id: variable_id.into(),
+                });
+            }
+        }
+    }
+}
+
+/// Validate the traffic allocation and audiences of a single experiment
+pub(crate) fn validate_experiment<F>(experiment: &Experiment, audience_exists: F, issues: &mut Vec<ValidationIssue>)
+where
+    F: Fn(&str) -> bool,
+{
+    let experiment_id = experiment.id();
+
+    // Every audience should exist
+    for audience_id in experiment.audience_ids() {
+        if !audience_exists(audience_id) {
+            issues.push(ValidationIssue::MissingAudience {
+                experiment_id: experiment_id.into(),
+                audience_id: audience_id.into(),
+            });
+        }
+    }
+
+    // Every range should point to an existing variation, and the ranges should be ascending and within bounds
+    let mut previous_end_of_range = None;
+    for range in experiment.traffic_allocation().ranges() {
+        let end_of_range = range.end_of_range();
+
+        // An empty variation ID is used for traffic that is not allocated to any variation
+        if !range.variation_id().is_empty() && experiment.variation(range.variation_id()).is_none() {
+            issues.push(ValidationIssue::MissingVariation {
+                experiment_id: experiment_id.into(),
+                variation_id: range.variation_id().into(),
+            });
+        }
+
+        if end_of_range > MAX_END_OF_RANGE {
+            issues.push(ValidationIssue::TrafficRangeOutOfBounds {
+                experiment_id: experiment_id.into(),
+                end_of_range,
+            });
+        }
+
+        match previous_end_of_range {
+            Some(previous) if end_of_range < previous => {
+                issues.push(ValidationIssue::UnsortedTrafficRange {
+                    experiment_id: experiment_id.into(),
+                    end_of_range,
+                });
+            }
+            Some(previous) if end_of_range == previous => {
+                issues.push(ValidationIssue::OverlappingTrafficRange {
+                    experiment_id: experiment_id.into(),
+                    end_of_range,
+                });
+            }
+            _ => {}
+        }
+
+        previous_end_of_range = Some(end_of_range);
+    }
+}
@@ -0,0 +1,60 @@
+// External imports
+use serde::{Deserialize, Serialize};
+
+/// A variable of a feature flag, with its default value
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Variable {
+    id: String,
+    key: String,
+    #[serde(rename = "type")]
+    variable_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sub_type: Option<String>,
+    default_value: String,
+}
+
+impl Variable {
+    /// Getter for `id` field
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Getter for `key` field
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Type of the variable, which is either "string", "integer", "double", "boolean" or "json"
+    pub fn variable_type(&self) -> &str {
+        // JSON variables are stored as strings with a sub type
+        match self.sub_type.as_deref() {
+            Some("json") => "json",
+            _ => &self.variable_type,
+        }
+    }
+
+    /// Getter for `default_value` field
+    pub fn default_value(&self) -> &str {
+        &self.default_value
+    }
+}
+
+/// The value of a variable within a variation
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub(crate) struct VariationVariable {
+    id: String,
+    value: String,
+}
+
+impl VariationVariable {
+    /// Getter for `id` field
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Getter for `value` field
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
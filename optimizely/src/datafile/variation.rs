@@ -1,7 +1,10 @@
 // External imports
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 
+// Imports from super
+use super::VariationVariable;
+
 /// A single variation like "off", "on" or other user-created variations.
 ///
 /// A variation has the properties `id`, `key`, and `is_feature_enabled`.
@@ -9,12 +12,14 @@ use std::collections::HashMap;
 /// The `key` is a human-readable value.
 /// The value of `is_feature_enabled` is `false` for the "off" variation.
 /// All other variations will have `is_feature_enabled` is `true`.
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Variation {
     id: String,
     key: String,
     feature_enabled: bool,
+    #[serde(default)]
+    variables: Vec<VariationVariable>,
 }
 
 impl Variation {
@@ -32,9 +37,22 @@ impl Variation {
     pub fn is_feature_enabled(&self) -> bool {
         self.feature_enabled
     }
+
+    /// Get the value of a variable within this variation, if it differs from the default value
+    pub fn variable_value(&self, variable_id: &str) -> Option<&str> {
+        self.variables
+            .iter()
+            .find(|variable| variable.id() == variable_id)
+            .map(|variable| variable.value())
+    }
+
+    /// IDs of the variables for which this variation has a value
+    pub(crate) fn variable_ids(&self) -> impl Iterator<Item = &str> {
+        self.variables.iter().map(|variable| variable.id())
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct VariationMap(HashMap<String, Variation>);
 
 impl<'de> Deserialize<'de> for VariationMap {
@@ -42,12 +60,33 @@ impl<'de> Deserialize<'de> for VariationMap {
     where
         D: Deserializer<'de>,
     {
-        let mut map = HashMap::new();
-        for variation in Vec::<Variation>::deserialize(deserializer)? {
-            map.insert(variation.id.clone(), variation);
-        }
+        let variations = Vec::<Variation>::deserialize(deserializer)?;
+        Ok(variations.into_iter().collect())
+    }
+}
+
+impl Serialize for VariationMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Sort by ID, so the output does not depend on the order of the HashMap
+        let mut variations = self.0.values().collect::<Vec<_>>();
+        variations.sort_by_key(|variation| &variation.id);
+        serializer.collect_seq(variations)
+    }
+}
 
-        Ok(Self(map))
+impl FromIterator<Variation> for VariationMap {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Variation>,
+    {
+        Self(
+            iter.into_iter()
+                .map(|variation| (variation.id.clone(), variation))
+                .collect(),
+        )
     }
 }
 
@@ -55,4 +94,8 @@ impl VariationMap {
     pub fn get(&self, id: &str) -> Option<&Variation> {
         self.0.get(id)
     }
+
+    pub fn values(&self) -> impl Iterator<Item = &Variation> {
+        self.0.values()
+    }
 }
@@ -3,7 +3,9 @@
 pub use client_error::ClientError;
 pub use datafile_error::DatafileError;
 pub use event_api_error::EventApiError;
+pub use parse_location::ParseLocation;
 
 mod client_error;
 mod datafile_error;
 mod event_api_error;
+mod parse_location;
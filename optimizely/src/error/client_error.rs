@@ -1,10 +1,54 @@
 // External imports
+use error_stack::Report;
 use thiserror::Error;
 
+// Imports from super
+use super::DatafileError;
+
 /// Representation of client initialization errors.
 #[derive(Error, Debug, PartialEq)]
 pub enum ClientError {
-    /// Invalid Datafile
-    #[error("Invalid Datafile")]
-    InvalidDatafile,
+    /// Failed to download the datafile from the CDN
+    #[error("Failed to download datafile")]
+    FailedDownload,
+    /// Failed to read the datafile from the local filesystem
+    #[error("Failed to read datafile")]
+    FailedFileRead,
+    /// Failed to write the datafile to the local filesystem, such as the datafile cache
+    #[error("Failed to write datafile")]
+    FailedFileWrite,
+    /// Datafile is not valid JSON
+    #[error("Datafile is not valid JSON")]
+    InvalidJson,
+    /// Failed to serialize the datafile to JSON, such as for the datafile cache
+    #[error("Failed to serialize datafile to JSON")]
+    FailedSerialize,
+    /// Datafile is valid JSON, but does not match the datafile schema
+    #[error("Datafile does not match the schema")]
+    InvalidSchema,
+    /// Datafile contains references that do not exist or invalid traffic allocations
+    #[error("Datafile failed validation")]
+    FailedValidation,
+    /// Datafile does not have a newer revision than the current datafile
+    #[error("Datafile does not have a newer revision")]
+    OutdatedDatafile,
+}
+
+impl ClientError {
+    /// Change the context of a datafile error report into the matching client error
+    pub(crate) fn from_datafile_report(report: Report<DatafileError>) -> Report<ClientError> {
+        let client_error = match report.current_context() {
+            DatafileError::FailedRequest | DatafileError::RejectedRequest | DatafileError::FailedResponse => {
+                ClientError::FailedDownload
+            }
+            DatafileError::FailedFileOpen | DatafileError::FailedFileRead => ClientError::FailedFileRead,
+            DatafileError::FailedFileWrite => ClientError::FailedFileWrite,
+            DatafileError::InvalidJson => ClientError::InvalidJson,
+            DatafileError::FailedSerialize => ClientError::FailedSerialize,
+            DatafileError::InvalidSchema => ClientError::InvalidSchema,
+            DatafileError::FailedValidation => ClientError::FailedValidation,
+        };
+
+        report.change_context(client_error)
+    }
 }
@@ -7,9 +7,21 @@ pub enum DatafileError {
     /// Failed to parse JSON
     #[error("JSON can not be parsed")]
     InvalidJson,
+    /// JSON is valid, but does not match the structure of a datafile
+    #[error("JSON does not match the datafile schema")]
+    InvalidSchema,
+    /// Datafile contains references that do not exist or invalid traffic allocations
+    #[error("Datafile failed validation")]
+    FailedValidation,
+    /// Failed to serialize datafile to JSON
+    #[error("Failed to serialize datafile to JSON")]
+    FailedSerialize,
     /// Failed to make request to cdn.optimizely.com
     #[error("Failed to make request to cdn.optimizely.com")]
     FailedRequest,
+    /// The CDN rejected the request, for example because the SDK key does not exist
+    #[error("cdn.optimizely.com rejected the request")]
+    RejectedRequest,
     /// Failed to decode response from cdn.optimizely.com
     #[error("Failed to decode response from cdn.optimizely.com")]
     FailedResponse,
@@ -19,10 +31,7 @@ pub enum DatafileError {
     /// Failed to read from local datafile
     #[error("Failed to read from local datafile")]
     FailedFileRead,
-}
-
-impl serde::de::Error for DatafileError {
-    fn custom<T: std::fmt::Display>(_msg: T) -> Self {
-        DatafileError::InvalidJson
-    }
+    /// Failed to write to local datafile
+    #[error("Failed to write to local datafile")]
+    FailedFileWrite,
 }
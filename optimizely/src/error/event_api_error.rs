@@ -1,13 +1,28 @@
 // External imports
 use thiserror::Error;
 
-/// This type represents all possible errors that can occur when communicating with Event API
+/// This type represents all possible errors that can occur when communicating with Event API or writing events to a file
 #[derive(Error, Debug, PartialEq)]
 pub enum EventApiError {
     #[doc(hidden)]
     #[error("Failed to send request to Event API")]
     FailedRequest,
     #[doc(hidden)]
+    #[error("Event API rejected the request")]
+    RejectedRequest,
+    #[doc(hidden)]
+    #[error("Event API failed to handle the request")]
+    FailedResponse,
+    #[doc(hidden)]
     #[error("Failed to serialize payload to JSON")]
     FailedSerialize,
+    #[doc(hidden)]
+    #[error("Failed to write to durable event queue")]
+    FailedQueueWrite,
+    #[doc(hidden)]
+    #[error("Failed to read from durable event queue")]
+    FailedQueueRead,
+    #[doc(hidden)]
+    #[error("Failed to write events to file")]
+    FailedFileWrite,
 }
@@ -0,0 +1,62 @@
+// External imports
+use std::fmt;
+
+/// Location within the datafile where parsing failed
+///
+/// This is attached to the error report of a datafile that could not be parsed,
+/// and can be retrieved with `report.downcast_ref::<ParseLocation>()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseLocation {
+    message: String,
+    line: usize,
+    column: usize,
+    path: String,
+}
+
+impl ParseLocation {
+    /// Create a location from a JSON error and the path to the value that failed
+    pub(crate) fn new(error: &serde_json::Error, path: String) -> ParseLocation {
+        let line = error.line();
+        let column = error.column();
+
+        // The message of serde_json ends with the location, which is stored separately
+        let message = error.to_string();
+        let suffix = format!(" at line {line} column {column}");
+        let message = message.strip_suffix(&suffix).unwrap_or(&message).to_owned();
+
+        ParseLocation {
+            message,
+            line,
+            column,
+            path,
+        }
+    }
+
+    /// Getter for `message` field
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Getter for `line` field
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Getter for `column` field
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Getter for `path` field
+    ///
+    /// For example, `experiments[3].trafficAllocation[0].endOfRange`
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl fmt::Display for ParseLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at line {} column {} (path: {})", self.message, self.line, self.column, self.path)
+    }
+}
@@ -1,7 +1,22 @@
-//! Event logging to Optimizely Event API
+//! Event logging to Optimizely Event API or to local files
 
 // Relative imports of sub modules
 pub use dispatcher::*;
+pub use event_context::EventContext;
+pub use log_event::{LogEvent, LogEventKind};
+#[cfg(feature = "online")]
+pub use request::{DispatchOutcome, RetryPolicy};
 
 mod dispatcher;
+mod event_context;
+mod log_event;
+#[cfg(any(feature = "online", feature = "testing"))]
+pub(crate) mod payload;
+#[cfg(feature = "online")]
 pub(crate) mod request;
+
+/// Base URL of the Optimizely Event API, which receives events at `/v1/events`
+pub const DEFAULT_EVENT_API_URL: &str = "https://logx.optimizely.com";
+
+/// Base URL of the Optimizely Event API for projects in the EU region
+pub const EU_EVENT_API_URL: &str = "https://eu.logx.optimizely.com";
@@ -1,7 +1,19 @@
-pub use batched_event_dispatcher::BatchedEventDispatcher;
+#[cfg(feature = "online")]
+pub use batched_event_dispatcher::{BackpressurePolicy, BatchedEventDispatcher, BatchedEventDispatcherBuilder};
+#[cfg(not(feature = "online"))]
+pub(crate) use discard_event_dispatcher::DiscardEventDispatcher;
 pub use event_dispatcher::EventDispatcher;
+pub use file_event_dispatcher::{EventFormat, FileEventDispatcher};
+#[cfg(feature = "online")]
 pub use simple_event_dispatcher::SimpleEventDispatcher;
 
+#[cfg(feature = "online")]
 mod batched_event_dispatcher;
+#[cfg(not(feature = "online"))]
+mod discard_event_dispatcher;
+#[cfg(feature = "online")]
+mod durable_queue;
 mod event_dispatcher;
+mod file_event_dispatcher;
+#[cfg(feature = "online")]
 mod simple_event_dispatcher;
@@ -1,93 +1,245 @@
+use super::durable_queue::{DurableQueue, QueuedEvent};
 use super::EventDispatcher;
-use crate::event_api::request::{Request, Visitor};
-use crate::{client::UserContext, datafile::Datafile};
-use crate::{Conversion, Decision};
-use std::sync::mpsc;
+use crate::datafile::Datafile;
+use crate::event_api::payload::Payload;
+use crate::event_api::request::{DispatchOutcome, Request};
+use crate::event_api::LogEvent;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 
-// Structure used to send message between threads
-struct ThreadMessage {
-    visitor: Visitor,
-    event: EventEnum,
-}
-enum EventEnum {
-    Conversion(Conversion),
-    Decision(Decision),
-}
+// Relative imports of sub modules
+pub use backpressure_policy::BackpressurePolicy;
+use bounded_queue::{BoundedQueue, Received};
+pub use builder::BatchedEventDispatcherBuilder;
 
-// Upper limit to number of events in a batch
-const DEFAULT_BATCH_THRESHOLD: usize = 10;
+mod backpressure_policy;
+mod bounded_queue;
+mod builder;
 
 /// Implementation of the EventDispatcher trait that collects multiple events before sending them
 ///
-/// TODO: add example usage in SDK
+/// By default a batch is sent once it contains 10 visitors or 30 seconds after its first event,
+/// use [BatchedEventDispatcher::builder] to change these limits.
 ///
 /// Inspiration from [Spawn threads and join in destructor](https://users.rust-lang.org/t/spawn-threads-and-join-in-destructor/1613/9)
 pub struct BatchedEventDispatcher {
     receiver_thread: Option<thread::JoinHandle<()>>,
-    transmitter_channel: Option<mpsc::Sender<ThreadMessage>>,
+    queue: Arc<BoundedQueue<QueuedEvent>>,
+    durable_queue: Option<Arc<Mutex<DurableQueue>>>,
+    last_outcome: Arc<Mutex<Option<DispatchOutcome>>>,
+}
+
+/// State of the receiver thread
+struct Worker {
+    request: Request,
+    durable_queue: Option<Arc<Mutex<DurableQueue>>>,
+    // Segments of the durable queue that contain the events of the current batch
+    batch_segments: Vec<u64>,
+    last_outcome: Arc<Mutex<Option<DispatchOutcome>>>,
+    batch_size: usize,
+    flush_interval: Duration,
+    max_payload_bytes: usize,
+    // Estimated size of the current payload and the time at which its first event was added
+    payload_bytes: usize,
+    batch_started: Option<Instant>,
 }
 
 impl BatchedEventDispatcher {
-    /// Constructor for a new batched event dispatcher
+    /// Constructor for a new batched event dispatcher with the default limits
     pub fn new(datafile: &Datafile) -> Self {
+        BatchedEventDispatcher::builder().build(datafile)
+    }
+
+    /// Constructor for a batched event dispatcher that stores events on disk until they are sent
+    ///
+    /// Every event is appended to a segment file in the given directory before it is dispatched,
+    /// and the segment is removed once the Event API acknowledged all of its events.
+    /// Events that could not be sent are sent again once the Event API accepts the next batch.
+    /// Segments that are left behind by a crash or by a failing Event API are sent again on the next start.
+    /// Events that are dropped because the queue is full are removed from the durable queue as well.
+    /// Resent events have the same UUIDs, so the Event API can de-duplicate them.
+    ///
+    /// ```
+    /// use optimizely::{event_api::BatchedEventDispatcher, Client};
+    /// # let directory = std::env::temp_dir().join("optimizely-doc-event-queue");
+    ///
+    /// let client = Client::from_local_datafile("../datafiles/sandbox.json")?
+    ///     .with_event_dispatcher(|datafile| BatchedEventDispatcher::with_durable_queue(datafile, &directory))
+    ///     .initialize();
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_durable_queue<P>(datafile: &Datafile, directory: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        BatchedEventDispatcher::builder()
+            .with_durable_queue(directory.as_ref())
+            .build(datafile)
+    }
+
+    /// Create a builder to configure the limits of the batches and the queue
+    pub fn builder() -> BatchedEventDispatcherBuilder {
+        BatchedEventDispatcherBuilder::default()
+    }
+
+    fn spawn(datafile: &Datafile, builder: BatchedEventDispatcherBuilder) -> Self {
         // Create the request buffer using the datafile
         let mut request = Request::new(datafile);
+        request.set_retry_policy(builder.retry_policy);
+
+        // Queue between the client threads and the receiver thread
+        let queue = Arc::new(BoundedQueue::new(builder.queue_capacity, builder.backpressure_policy));
 
-        // Create sender and receiver for thread
-        let (transmitter_channel, receiver_channel) = mpsc::channel::<ThreadMessage>();
+        // Outcome of the last batch, shared with the receiver thread
+        let last_outcome = Arc::new(Mutex::new(None));
+
+        // Durable queue that is appended to by the client threads, and the segments that were left by a previous run
+        let (durable_queue, segments) = match builder.queue_directory.as_deref().map(DurableQueue::open) {
+            Some(Ok((durable_queue, segments))) => (Some(Arc::new(Mutex::new(durable_queue))), segments),
+            Some(Err(report)) => {
+                log::error!("Failed to open durable event queue, keeping events in memory only");
+                log::error!("\n{report:?}");
+                (None, Vec::new())
+            }
+            None => (None, Vec::new()),
+        };
+
+        let mut worker = Worker {
+            request,
+            durable_queue: durable_queue.clone(),
+            batch_segments: Vec::new(),
+            last_outcome: last_outcome.clone(),
+            batch_size: builder.batch_size,
+            flush_interval: builder.flush_interval,
+            max_payload_bytes: builder.max_payload_bytes,
+            payload_bytes: 0,
+            batch_started: None,
+        };
 
         // Receiver logic in separate thread
-        let receiver_thread = thread::spawn(move || {
-            // Keep receiving new messages from the main thread
-            for message in receiver_channel.iter() {
-                // Deconstruct the message
-                let ThreadMessage { visitor, event } = message;
-
-                // the corresponding event to the payload
-                match event {
-                    EventEnum::Conversion(conversion) => {
-                        request.add_conversion_event(visitor, conversion);
-                    }
-                    EventEnum::Decision(decision) => {
-                        request.add_decision_event(visitor, decision);
+        let receiver_thread = {
+            let queue = queue.clone();
+            thread::spawn(move || {
+                // Send the events that were left behind by a previous run, before handling new events
+                worker.replay(segments);
+
+                // Keep receiving new messages from the main thread, until the dispatcher is dropped
+                loop {
+                    match queue.recv_timeout(worker.time_until_flush()) {
+                        Received::Item(event) => worker.handle(event),
+                        Received::Flush(done) => {
+                            log::debug!("Received explicit flush");
+                            worker.flush();
+                            // The caller might have stopped waiting already
+                            let _ = done.send(());
+                        }
+                        Received::Timeout => {
+                            log::debug!("Reached flush interval");
+                            worker.flush();
+                        }
+                        Received::Closed => break,
                     }
                 }
 
-                // Send request if reached the batch threshold
-                if request.buffer_size() >= DEFAULT_BATCH_THRESHOLD {
-                    log::debug!("Reached DEFAULT_BATCH_THRESHOLD");
-
-                    // Sending request
-                    request.send();
-                }
-            }
-        });
+                // Send the last batch once the dispatcher is dropped
+                worker.flush();
+            })
+        };
 
         BatchedEventDispatcher {
             receiver_thread: Some(receiver_thread),
-            transmitter_channel: Some(transmitter_channel),
+            queue,
+            durable_queue,
+            last_outcome,
         }
     }
+
+    /// Get the outcome of the last batch that was sent to the Event API
+    pub fn last_outcome(&self) -> Option<DispatchOutcome> {
+        self.last_outcome
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Get the number of events that were dropped because the queue was full
+    pub fn dropped_events(&self) -> u64 {
+        self.queue.dropped()
+    }
 }
 
 impl EventDispatcher for BatchedEventDispatcher {
-    fn send_conversion_event(&self, user_context: &UserContext, conversion: Conversion) {
-        self.transmit(user_context, EventEnum::Conversion(conversion))
+    fn dispatch_event(&self, event: LogEvent) {
+        // Build message, using the context and endpoint at the time of the event
+        let context = event.context().clone();
+        let client_ip = event.client_ip().map(String::from);
+        let endpoint_url = event.endpoint_url().to_owned();
+        let visitor = match Payload::event_visitor(event) {
+            Ok(visitor) => visitor,
+            Err(report) => {
+                log::error!("Skipping event that cannot be serialized");
+                log::error!("\n{report:?}");
+                return;
+            }
+        };
+        let mut message = QueuedEvent {
+            context,
+            client_ip,
+            endpoint_url,
+            visitor,
+            segment: None,
+        };
+
+        // Store the event on disk before it is queued, so it is not lost if the process stops
+        if let Some(durable_queue) = &self.durable_queue {
+            match durable_queue
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .append(&message)
+            {
+                Ok(segment) => message.segment = Some(segment),
+                Err(report) => {
+                    log::error!("Failed to store event in durable queue");
+                    log::error!("\n{report:?}");
+                }
+            }
+        }
+
+        // Send message to thread, which might block or drop an event if the queue is full
+        let Some(dropped) = self.queue.send(message) else {
+            return;
+        };
+
+        // The dropped event will never be sent, so it should not be replayed either
+        if let (Some(durable_queue), Some(segment)) = (&self.durable_queue, dropped.segment) {
+            let result = durable_queue
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .acknowledge(&[segment]);
+            if let Err(report) = result {
+                log::error!("Failed to remove dropped event from durable queue");
+                log::error!("\n{report:?}");
+            }
+        }
     }
 
-    fn send_decision_event(&self, user_context: &UserContext, decision: Decision) {
-        self.transmit(user_context, EventEnum::Decision(decision))
+    /// Send every event that was dispatched before, including retries of the batch
+    ///
+    /// Returns `false` if the batch was not sent before the timeout, it is still sent in the background.
+    fn flush(&self, timeout: Duration) -> bool {
+        let (done, receiver) = mpsc::channel();
+        self.queue.send_flush(done);
+
+        receiver.recv_timeout(timeout).is_ok()
     }
 }
 
 impl Drop for BatchedEventDispatcher {
     fn drop(&mut self) {
-        // Take the transmitter channel and replace it with None
-        if let Some(channel) = self.transmitter_channel.take() {
-            // Drop the transmitter channel first, so the receiver channel in the thread will stop receiving messages
-            drop(channel);
-        }
+        // Close the queue first, so the thread will stop receiving messages after the remaining ones
+        self.queue.close();
 
         // Take the receiver thread and replace it with None
         if let Some(thread) = self.receiver_thread.take() {
@@ -102,27 +254,184 @@ impl Drop for BatchedEventDispatcher {
     }
 }
 
-impl BatchedEventDispatcher {
-    fn transmit(&self, user_context: &UserContext, event: EventEnum) {
-        // Create a String so the value can be owned by the other thread.
-        let visitor = Visitor::from(user_context);
-
-        // Build message
-        let message = ThreadMessage { visitor, event };
-
-        // Send message to thread
-        match &self.transmitter_channel {
-            Some(channel) => match channel.send(message) {
-                Ok(_) => {
-                    log::debug!("Successfully sent message to thread");
-                }
-                Err(_) => {
-                    log::error!("Failed to send message to thread");
+impl Worker {
+    /// Replay every segment of the durable queue that was left by a previous run
+    fn replay(&mut self, segments: Vec<PathBuf>) {
+        for segment in segments {
+            // Keep the segment for the next start if the Event API is not reachable
+            if self.replay_segment(&segment) {
+                if let Err(report) = DurableQueue::remove_segment(&segment) {
+                    log::error!("Failed to remove replayed events\n{report:?}");
                 }
-            },
-            None => {
-                log::error!("Transmitter already dropped");
             }
         }
     }
+
+    /// Replay every segment of the durable queue with events that could not be sent during this run
+    fn replay_failed(&mut self) {
+        let Some(durable_queue) = self.durable_queue.clone() else {
+            return;
+        };
+
+        let failed = durable_queue
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take_failed();
+        for (sequence, segment) in failed {
+            let acknowledged = self.replay_segment(&segment);
+
+            let mut durable_queue = durable_queue.lock().unwrap_or_else(|e| e.into_inner());
+            if !acknowledged {
+                // Try again after the next batch that is acknowledged
+                durable_queue.fail(&[sequence]);
+            } else if let Err(report) = durable_queue.remove(sequence) {
+                log::error!("Failed to remove replayed events\n{report:?}");
+            }
+        }
+    }
+
+    /// Send every event of a segment, and return whether all of them were acknowledged
+    fn replay_segment(&mut self, segment: &Path) -> bool {
+        let events = match DurableQueue::read_segment(segment) {
+            Ok(events) => events,
+            Err(report) => {
+                log::error!("Failed to replay events\n{report:?}");
+                return false;
+            }
+        };
+
+        log::info!("Replaying {} events from {}", events.len(), segment.display());
+        let mut acknowledged = true;
+        for event in events {
+            let size = Worker::visitor_bytes(&event);
+            if self.needs_new_batch(&event, size) {
+                acknowledged &= Worker::is_acknowledged(&self.send());
+            }
+            self.add(event, size);
+            if self.is_full() {
+                acknowledged &= Worker::is_acknowledged(&self.send());
+            }
+        }
+        if self.request.buffer_size() > 0 {
+            acknowledged &= Worker::is_acknowledged(&self.send());
+        }
+
+        acknowledged
+    }
+
+    /// Add a single event, and send the batch once it is full
+    fn handle(&mut self, event: QueuedEvent) {
+        // A batch has a single context, IP address and endpoint and a maximum size, so start a new batch if needed
+        let size = Worker::visitor_bytes(&event);
+        if self.needs_new_batch(&event, size) {
+            self.flush();
+        }
+
+        self.add(event, size);
+
+        // Send request if reached the batch size, or if events keep arriving after the flush interval
+        if self.is_full() || self.time_until_flush() == Some(Duration::ZERO) {
+            self.flush();
+        }
+    }
+
+    /// Whether the event has to be sent in a different batch than the current one
+    fn needs_new_batch(&self, event: &QueuedEvent, size: usize) -> bool {
+        if self.request.buffer_size() == 0 {
+            return false;
+        }
+
+        self.request.context() != &event.context
+            || self.request.client_ip() != event.client_ip.as_deref()
+            || self.request.endpoint_url() != event.endpoint_url
+            || self.payload_bytes + size > self.max_payload_bytes
+    }
+
+    fn is_full(&self) -> bool {
+        self.request.buffer_size() >= self.batch_size
+    }
+
+    /// Time until the current batch has to be sent, or `None` if there is nothing to send
+    fn time_until_flush(&self) -> Option<Duration> {
+        self.batch_started
+            .map(|started| self.flush_interval.saturating_sub(started.elapsed()))
+    }
+
+    /// Add an event to the current batch, using the context and endpoint at the time of the event
+    fn add(&mut self, event: QueuedEvent, size: usize) {
+        self.request.update_context(&event.context);
+        self.request.update_client_ip(event.client_ip.as_deref());
+        self.request.update_endpoint(&event.endpoint_url);
+
+        if self.request.buffer_size() == 0 {
+            // Every payload contains the context and information about the SDK
+            self.payload_bytes = Payload::new(event.context.clone(), event.client_ip.clone())
+                .to_string()
+                .map_or(0, |envelope| envelope.len());
+            self.batch_started = Some(Instant::now());
+        }
+
+        if self.payload_bytes + size > self.max_payload_bytes {
+            log::warn!("Sending visitor of {size} bytes, which exceeds the maximum payload size on its own");
+        }
+
+        self.batch_segments.extend(event.segment);
+        self.request.add_visitor(event.visitor);
+        self.payload_bytes += size;
+    }
+
+    /// Serialized size of a visitor, including the separator in the list of visitors
+    fn visitor_bytes(event: &QueuedEvent) -> usize {
+        serde_json::to_vec(&event.visitor).map_or(0, |visitor| visitor.len()) + 1
+    }
+
+    /// Send the current batch and remove it from the durable queue if it was acknowledged
+    fn flush(&mut self) {
+        if self.request.buffer_size() == 0 {
+            return;
+        }
+
+        let segments = std::mem::take(&mut self.batch_segments);
+        let outcome = self.send();
+        let acknowledged = Worker::is_acknowledged(&outcome);
+
+        if let Some(durable_queue) = &self.durable_queue {
+            let mut durable_queue = durable_queue.lock().unwrap_or_else(|e| e.into_inner());
+            let result = if acknowledged {
+                durable_queue.acknowledge(&segments)
+            } else {
+                log::warn!("Keeping unsent events in durable queue, until the Event API accepts events again");
+                durable_queue.fail(&segments);
+                Ok(())
+            };
+
+            // Continue in a new segment, so segments of which all events are acknowledged can be removed
+            let result = result.and_then(|_| durable_queue.rotate());
+            if let Err(report) = result {
+                log::error!("Failed to remove sent events from durable queue");
+                log::error!("\n{report:?}");
+            }
+        }
+
+        // The Event API accepts events again, so send the events that failed before
+        if acknowledged {
+            self.replay_failed();
+        }
+    }
+
+    fn send(&mut self) -> Option<DispatchOutcome> {
+        self.payload_bytes = 0;
+        self.batch_started = None;
+
+        let outcome = self.request.send();
+        if let Some(outcome) = &outcome {
+            *self.last_outcome.lock().unwrap_or_else(|e| e.into_inner()) = Some(outcome.clone());
+        }
+        outcome
+    }
+
+    /// Whether the batch does not need to be sent again, which includes payloads that were rejected by the Event API
+    fn is_acknowledged(outcome: &Option<DispatchOutcome>) -> bool {
+        !matches!(outcome, Some(DispatchOutcome::Failed { .. }))
+    }
 }
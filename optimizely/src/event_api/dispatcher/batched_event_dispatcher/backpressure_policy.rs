@@ -0,0 +1,14 @@
+/// What a batched event dispatcher does with a new event when its queue is full
+///
+/// The queue fills up when events are created faster than they can be sent, for example while the Event API is down.
+/// Every event that is dropped is counted, see [BatchedEventDispatcher::dropped_events](super::BatchedEventDispatcher::dropped_events).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackpressurePolicy {
+    /// Drop the new event, keeping the events that are already queued
+    #[default]
+    DropNewest,
+    /// Drop the oldest queued event to make room for the new event
+    DropOldest,
+    /// Block the thread that makes the decision or tracks the event until there is room in the queue
+    Block,
+}
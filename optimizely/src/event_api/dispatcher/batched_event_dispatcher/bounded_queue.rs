@@ -0,0 +1,173 @@
+// External imports
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+
+// Imports from super
+use super::BackpressurePolicy;
+
+/// Result of waiting for the next item in the queue
+pub(super) enum Received<T> {
+    Item(T),
+    Flush(Sender<()>),
+    Timeout,
+    Closed,
+}
+
+/// Queue with a maximum number of items between the client threads and the receiver thread
+///
+/// Unlike a channel, the oldest item can be dropped by the sending side when the queue is full.
+/// Flush requests are received in order with the items, but never count towards the capacity or get dropped.
+pub(super) struct BoundedQueue<T> {
+    state: Mutex<QueueState<T>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+    policy: BackpressurePolicy,
+    dropped: AtomicU64,
+}
+
+struct QueueState<T> {
+    entries: VecDeque<Entry<T>>,
+    // Number of items in the entries, so excluding flush requests
+    items: usize,
+    closed: bool,
+}
+
+enum Entry<T> {
+    Item(T),
+    Flush(Sender<()>),
+}
+
+impl<T> BoundedQueue<T> {
+    pub(super) fn new(capacity: usize, policy: BackpressurePolicy) -> BoundedQueue<T> {
+        BoundedQueue {
+            state: Mutex::new(QueueState {
+                entries: VecDeque::new(),
+                items: 0,
+                closed: false,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity: capacity.max(1),
+            policy,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Add an item, applying the backpressure policy if the queue is full
+    ///
+    /// Returns the item that was dropped because the queue was full, which is either the new or the oldest item.
+    pub(super) fn send(&self, item: T) -> Option<T> {
+        let mut state = self.lock();
+        let mut dropped = None;
+
+        if state.items >= self.capacity {
+            match self.policy {
+                BackpressurePolicy::DropNewest => {
+                    log::warn!("Event queue is full, dropping new event");
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return Some(item);
+                }
+                BackpressurePolicy::DropOldest => {
+                    log::warn!("Event queue is full, dropping oldest event");
+                    let oldest = state
+                        .entries
+                        .iter()
+                        .position(|entry| matches!(entry, Entry::Item(_)));
+                    if let Some(Entry::Item(oldest)) = oldest.and_then(|index| state.entries.remove(index)) {
+                        state.items -= 1;
+                        dropped = Some(oldest);
+                    }
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                BackpressurePolicy::Block => {
+                    while state.items >= self.capacity && !state.closed {
+                        state = self.not_full.wait(state).unwrap_or_else(|e| e.into_inner());
+                    }
+                }
+            }
+        }
+
+        if state.closed {
+            log::error!("Event queue already closed");
+            return dropped;
+        }
+
+        state.entries.push_back(Entry::Item(item));
+        state.items += 1;
+        self.not_empty.notify_one();
+        dropped
+    }
+
+    /// Ask the receiver to flush once it received every item that is currently in the queue
+    ///
+    /// The sender is notified after the flush, or dropped if the queue is already closed.
+    pub(super) fn send_flush(&self, done: Sender<()>) {
+        let mut state = self.lock();
+        if state.closed {
+            return;
+        }
+
+        state.entries.push_back(Entry::Flush(done));
+        self.not_empty.notify_one();
+    }
+
+    /// Wait for the next item, at most for the given timeout or indefinitely with `None`
+    ///
+    /// The remaining items are still received after the queue is closed.
+    pub(super) fn recv_timeout(&self, timeout: Option<Duration>) -> Received<T> {
+        let mut state = self.lock();
+
+        loop {
+            match state.entries.pop_front() {
+                Some(Entry::Item(item)) => {
+                    state.items -= 1;
+                    self.not_full.notify_one();
+                    return Received::Item(item);
+                }
+                Some(Entry::Flush(done)) => return Received::Flush(done),
+                None => {}
+            }
+            if state.closed {
+                return Received::Closed;
+            }
+
+            state = match timeout {
+                Some(timeout) => {
+                    let (state, result) = self
+                        .not_empty
+                        .wait_timeout(state, timeout)
+                        .unwrap_or_else(|e| e.into_inner());
+                    if result.timed_out() && state.entries.is_empty() && !state.closed {
+                        return Received::Timeout;
+                    }
+                    state
+                }
+                None => self
+                    .not_empty
+                    .wait(state)
+                    .unwrap_or_else(|e| e.into_inner()),
+            };
+        }
+    }
+
+    /// Stop accepting items, and wake up every waiting thread
+    pub(super) fn close(&self) {
+        self.lock().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+
+    /// Number of items that were dropped because the queue was full
+    pub(super) fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState<T>> {
+        // A panic in a client thread should not stop the receiver thread
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
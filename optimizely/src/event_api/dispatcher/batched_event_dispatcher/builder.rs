@@ -0,0 +1,123 @@
+// External imports
+use std::path::PathBuf;
+use std::time::Duration;
+
+// Imports from crate
+use crate::datafile::Datafile;
+use crate::event_api::RetryPolicy;
+
+// Imports from super
+use super::{BackpressurePolicy, BatchedEventDispatcher};
+
+// Default limits of a batch
+const DEFAULT_BATCH_SIZE: usize = 10;
+const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+// The Event API accepts payloads of at most 3.5 MB
+const DEFAULT_MAX_PAYLOAD_BYTES: usize = 3_500_000;
+const DEFAULT_QUEUE_CAPACITY: usize = 10_000;
+
+/// Builder for a [BatchedEventDispatcher] with custom limits
+///
+/// A batch is sent once it contains `batch_size` visitors, once the oldest event in it waited for `flush_interval`,
+/// or before the payload would exceed `max_payload_bytes`.
+///
+/// ```
+/// use optimizely::event_api::{BackpressurePolicy, BatchedEventDispatcher};
+/// use optimizely::Client;
+/// use std::time::Duration;
+///
+/// let client = Client::from_local_datafile("../datafiles/sandbox.json")?
+///     .with_event_dispatcher(|datafile| {
+///         BatchedEventDispatcher::builder()
+///             .with_batch_size(50)
+///             .with_flush_interval(Duration::from_secs(5))
+///             .with_queue_capacity(1_000, BackpressurePolicy::DropOldest)
+///             .build(datafile)
+///     })
+///     .initialize();
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct BatchedEventDispatcherBuilder {
+    pub(super) batch_size: usize,
+    pub(super) flush_interval: Duration,
+    pub(super) max_payload_bytes: usize,
+    pub(super) queue_capacity: usize,
+    pub(super) backpressure_policy: BackpressurePolicy,
+    pub(super) retry_policy: RetryPolicy,
+    pub(super) queue_directory: Option<PathBuf>,
+}
+
+impl BatchedEventDispatcherBuilder {
+    /// Send a batch once it contains this many visitors
+    pub fn with_batch_size(mut self, batch_size: usize) -> BatchedEventDispatcherBuilder {
+        self.batch_size = batch_size.max(1);
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Send a batch at most this long after its first event was added, even if it is not full
+    pub fn with_flush_interval(mut self, flush_interval: Duration) -> BatchedEventDispatcherBuilder {
+        self.flush_interval = flush_interval;
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Split batches so a payload does not exceed this many bytes
+    ///
+    /// A single visitor that exceeds the limit on its own is still sent in a payload of its own.
+    pub fn with_max_payload_bytes(mut self, max_payload_bytes: usize) -> BatchedEventDispatcherBuilder {
+        self.max_payload_bytes = max_payload_bytes;
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Keep at most `capacity` events in memory that are waiting to be added to a batch
+    ///
+    /// The policy decides what happens with new events when the queue is full.
+    pub fn with_queue_capacity(mut self, capacity: usize, policy: BackpressurePolicy) -> BatchedEventDispatcherBuilder {
+        self.queue_capacity = capacity.max(1);
+        self.backpressure_policy = policy;
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Use a different retry policy for batches that fail with a server error or network error
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> BatchedEventDispatcherBuilder {
+        self.retry_policy = retry_policy;
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Store events in the given directory until they are sent, see [BatchedEventDispatcher::with_durable_queue]
+    pub fn with_durable_queue(mut self, directory: impl Into<PathBuf>) -> BatchedEventDispatcherBuilder {
+        self.queue_directory = Some(directory.into());
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Start the dispatcher
+    pub fn build(self, datafile: &Datafile) -> BatchedEventDispatcher {
+        BatchedEventDispatcher::spawn(datafile, self)
+    }
+}
+
+impl Default for BatchedEventDispatcherBuilder {
+    fn default() -> Self {
+        BatchedEventDispatcherBuilder {
+            batch_size: DEFAULT_BATCH_SIZE,
+            flush_interval: DEFAULT_FLUSH_INTERVAL,
+            max_payload_bytes: DEFAULT_MAX_PAYLOAD_BYTES,
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+            backpressure_policy: BackpressurePolicy::default(),
+            retry_policy: RetryPolicy::default(),
+            queue_directory: None,
+        }
+    }
+}
@@ -0,0 +1,16 @@
+// Imports from super
+use super::EventDispatcher;
+
+// Imports from crate
+use crate::event_api::LogEvent;
+
+/// Implementation of the EventDispatcher trait that ignores every event
+///
+/// Used by default when the SDK is built without the Event API, unless a custom event dispatcher is specified.
+pub(crate) struct DiscardEventDispatcher;
+
+impl EventDispatcher for DiscardEventDispatcher {
+    fn dispatch_event(&self, _event: LogEvent) {
+        log::debug!("Discarding event, since there is no event dispatcher");
+    }
+}
@@ -0,0 +1,236 @@
+// External imports
+use error_stack::{Result, ResultExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// Imports from crate
+use crate::error::EventApiError;
+use crate::event_api::EventContext;
+
+// Segment files are named segment-{sequence}.ndjson, so they sort in the order in which they were written
+const SEGMENT_PREFIX: &str = "segment-";
+const SEGMENT_EXTENSION: &str = "ndjson";
+
+/// Event that is stored in the durable queue until the Event API acknowledged it
+///
+/// The visitor is stored as it is sent, so a replay contains the same UUIDs and the Event API can de-duplicate.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct QueuedEvent {
+    pub(crate) context: EventContext,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) client_ip: Option<String>,
+    pub(crate) endpoint_url: String,
+    pub(crate) visitor: Value,
+    // Sequence of the segment that contains the event, which is only known after it was appended
+    #[serde(skip)]
+    pub(crate) segment: Option<u64>,
+}
+
+/// Append-only segment files that contain every event that was not acknowledged yet
+///
+/// Every event is appended as a single JSON line to the current segment before it is dispatched.
+/// Once every event of a segment is acknowledged and the queue moved on to the next segment, the segment is removed.
+/// Events that could not be sent are never acknowledged, so their segment is kept and replayed once the Event API
+/// accepts events again, or on the next start.
+pub(crate) struct DurableQueue {
+    directory: PathBuf,
+    next_sequence: u64,
+    segment: Option<(u64, File)>,
+    // Number of events per segment that were not acknowledged yet
+    pending: BTreeMap<u64, usize>,
+    // Segments with events that could not be sent, which have to be replayed
+    failed: BTreeSet<u64>,
+}
+
+impl DurableQueue {
+    /// Open the queue in the given directory, and return the segments that were left by a previous run
+    pub(crate) fn open(directory: &Path) -> Result<(DurableQueue, Vec<PathBuf>), EventApiError> {
+        fs::create_dir_all(directory)
+            .change_context(EventApiError::FailedQueueWrite)
+            .attach_printable_lazy(|| format!("Queue directory: {}", directory.display()))?;
+
+        let mut segments = fs::read_dir(directory)
+            .change_context(EventApiError::FailedQueueRead)
+            .attach_printable_lazy(|| format!("Queue directory: {}", directory.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter_map(|path| segment_sequence(&path).map(|sequence| (sequence, path)))
+            .collect::<Vec<_>>();
+        segments.sort();
+
+        let queue = DurableQueue {
+            directory: directory.to_path_buf(),
+            next_sequence: segments.last().map_or(0, |(sequence, _)| sequence + 1),
+            segment: None,
+            pending: BTreeMap::new(),
+            failed: BTreeSet::new(),
+        };
+        let segments = segments.into_iter().map(|(_, path)| path).collect();
+
+        Ok((queue, segments))
+    }
+
+    /// Append an event to the current segment, which is created if needed, and return the sequence of the segment
+    ///
+    /// The data is synced to disk before returning, so the event survives a crash once it is dispatched.
+    pub(crate) fn append(&mut self, event: &QueuedEvent) -> Result<u64, EventApiError> {
+        let mut line = serde_json::to_string(event).change_context(EventApiError::FailedSerialize)?;
+        line.push('\n');
+
+        let (sequence, file) = match &mut self.segment {
+            Some(segment) => segment,
+            None => {
+                let sequence = self.next_sequence;
+                self.next_sequence += 1;
+
+                let path = self.segment_path(sequence);
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .change_context(EventApiError::FailedQueueWrite)
+                    .attach_printable_lazy(|| format!("Segment: {}", path.display()))?;
+                self.segment.insert((sequence, file))
+            }
+        };
+        let sequence = *sequence;
+
+        // Write the line at once, so a crash leaves at most one incomplete line
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.sync_data())
+            .change_context(EventApiError::FailedQueueWrite)
+            .attach_printable_lazy(|| format!("Segment: {}", self.segment_path(sequence).display()))?;
+
+        *self.pending.entry(sequence).or_default() += 1;
+        Ok(sequence)
+    }
+
+    /// The events in the given segments were acknowledged, so remove every segment without pending events
+    pub(crate) fn acknowledge(&mut self, segments: &[u64]) -> Result<(), EventApiError> {
+        for sequence in segments {
+            if let Some(pending) = self.pending.get_mut(sequence) {
+                *pending = pending.saturating_sub(1);
+            }
+        }
+
+        self.remove_acknowledged()
+    }
+
+    /// The events in the given segments could not be sent, so replay these segments later on
+    pub(crate) fn fail(&mut self, segments: &[u64]) {
+        // Segments that were already replayed and removed do not have to be replayed again
+        let segments = segments
+            .iter()
+            .filter(|sequence| self.pending.contains_key(sequence));
+        self.failed.extend(segments);
+    }
+
+    /// Take the segments with events that could not be sent, except the segment that is still appended to
+    pub(crate) fn take_failed(&mut self) -> Vec<(u64, PathBuf)> {
+        let current = self.segment.as_ref().map(|(sequence, _)| *sequence);
+        let failed = self
+            .failed
+            .iter()
+            .copied()
+            .filter(|sequence| Some(*sequence) != current)
+            .collect::<Vec<_>>();
+
+        failed
+            .into_iter()
+            .map(|sequence| {
+                self.failed.remove(&sequence);
+                (sequence, self.segment_path(sequence))
+            })
+            .collect()
+    }
+
+    /// All events of the segment were replayed and acknowledged, so remove the segment
+    pub(crate) fn remove(&mut self, sequence: u64) -> Result<(), EventApiError> {
+        self.pending.remove(&sequence);
+        self.failed.remove(&sequence);
+        DurableQueue::remove_segment(&self.segment_path(sequence))
+    }
+
+    /// Append new events to a new segment, so the current segment can be removed once its events are acknowledged
+    ///
+    /// Segments of events that could not be sent are kept until they are replayed.
+    pub(crate) fn rotate(&mut self) -> Result<(), EventApiError> {
+        if let Some((sequence, _)) = self.segment.take() {
+            if self
+                .pending
+                .get(&sequence)
+                .is_some_and(|pending| *pending > 0)
+            {
+                log::debug!("Keeping pending events in {}", self.segment_path(sequence).display());
+            }
+        }
+
+        self.remove_acknowledged()
+    }
+
+    /// Remove every segment that is no longer appended to and of which all events were acknowledged
+    fn remove_acknowledged(&mut self) -> Result<(), EventApiError> {
+        let current = self.segment.as_ref().map(|(sequence, _)| *sequence);
+        let acknowledged = self
+            .pending
+            .iter()
+            .filter(|(sequence, pending)| **pending == 0 && Some(**sequence) != current)
+            .map(|(sequence, _)| *sequence)
+            .collect::<Vec<_>>();
+
+        for sequence in acknowledged {
+            self.pending.remove(&sequence);
+            DurableQueue::remove_segment(&self.segment_path(sequence))?;
+        }
+
+        Ok(())
+    }
+
+    fn segment_path(&self, sequence: u64) -> PathBuf {
+        self.directory
+            .join(format!("{SEGMENT_PREFIX}{sequence:020}.{SEGMENT_EXTENSION}"))
+    }
+
+    /// Read all events of a segment, skipping a line that was only partially written
+    pub(crate) fn read_segment(path: &Path) -> Result<Vec<QueuedEvent>, EventApiError> {
+        let content = fs::read_to_string(path)
+            .change_context(EventApiError::FailedQueueRead)
+            .attach_printable_lazy(|| format!("Segment: {}", path.display()))?;
+
+        let events = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(event) => Some(event),
+                Err(error) => {
+                    log::warn!("Skipping invalid event in {}: {error}", path.display());
+                    None
+                }
+            })
+            .collect();
+
+        Ok(events)
+    }
+
+    /// Remove a segment of which all events were acknowledged
+    pub(crate) fn remove_segment(path: &Path) -> Result<(), EventApiError> {
+        fs::remove_file(path)
+            .change_context(EventApiError::FailedQueueWrite)
+            .attach_printable_lazy(|| format!("Segment: {}", path.display()))
+    }
+}
+
+/// Sequence number of a segment file, or `None` if the file is not a segment
+fn segment_sequence(path: &Path) -> Option<u64> {
+    if path.extension()? != SEGMENT_EXTENSION {
+        return None;
+    }
+    path.file_stem()?
+        .to_str()?
+        .strip_prefix(SEGMENT_PREFIX)?
+        .parse()
+        .ok()
+}
@@ -1,16 +1,49 @@
 use std::any::Any;
+use std::time::Duration;
 
-// Imports from super
-use crate::{client::UserContext, Conversion, Decision};
+// Imports from crate
+use crate::event_api::LogEvent;
 
 /// Trait for sending events to Optimizely Event API
 ///
-/// It is possible to make a custom event dispatcher by implementing this trait
-/// TODO: add example again
+/// It is possible to make a custom event dispatcher by implementing this trait.
+/// Every [LogEvent] owns its data, so it can be moved to another thread or stored for later.
+///
+/// ```
+/// use optimizely::event_api::{EventDispatcher, LogEvent};
+/// use optimizely::Client;
+/// use std::sync::{mpsc, Mutex};
+///
+/// // Forward every event to a channel, so another thread can process it
+/// struct ChannelEventDispatcher(Mutex<mpsc::Sender<LogEvent>>);
+///
+/// impl EventDispatcher for ChannelEventDispatcher {
+///     fn dispatch_event(&self, event: LogEvent) {
+///         let _ = self.0.lock().unwrap().send(event);
+///     }
+/// }
+///
+/// let (sender, receiver) = mpsc::channel();
+/// let client = Client::from_local_datafile("../datafiles/sandbox.json")?
+///     .with_event_dispatcher(|_| ChannelEventDispatcher(Mutex::new(sender)))
+///     .initialize();
+///
+/// client.create_user_context("user-1").track_event("purchase");
+///
+/// let event = receiver.recv()?;
+/// assert_eq!(event.visitor_id(), "user-1");
+/// assert_eq!(event.conversion().map(|conversion| conversion.event_key()), Some("purchase"));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
 pub trait EventDispatcher: Send + Sync + Any {
-    /// Send conversion event to destination
-    fn send_conversion_event(&self, user_context: &UserContext, conversion: Conversion);
+    /// Send decision or conversion event to destination
+    fn dispatch_event(&self, event: LogEvent);
 
-    /// Send event to destination
-    fn send_decision_event(&self, user_context: &UserContext, decision: Decision);
+    /// Block until every event that was dispatched before is sent, or the timeout elapsed
+    ///
+    /// Returns whether all events were sent in time.
+    /// The default implementation does nothing, which suits dispatchers that send every event right away.
+    fn flush(&self, _timeout: Duration) -> bool {
+        true
+    }
 }
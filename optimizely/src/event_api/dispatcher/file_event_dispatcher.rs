@@ -0,0 +1,160 @@
+// External imports
+use error_stack::{Result, ResultExt};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+// Imports from super
+use super::EventDispatcher;
+
+// Imports from crate
+use crate::error::EventApiError;
+use crate::event_api::LogEvent;
+
+// Relative imports of sub modules
+pub use event_format::EventFormat;
+use event_record::EventRecord;
+use rotating_file::RotatingFile;
+
+mod event_format;
+mod event_record;
+mod rotating_file;
+
+/// Implementation of the EventDispatcher trait that writes every event as a record to stdout or a local file
+///
+/// Every decision and conversion becomes a single record with the timestamp, visitor, attributes,
+/// flag, experiment, variation, event key and tags, in the [EventFormat] of choice.
+/// Events are not sent to the Event API, so this dispatcher is also available without the `online` feature.
+///
+/// ```
+/// use optimizely::event_api::{EventFormat, FileEventDispatcher};
+/// use optimizely::Client;
+/// # let file_path = std::env::temp_dir().join("optimizely-doc-events.ndjson");
+///
+/// // Start a new file after 10 MB, and keep the 5 most recent files
+/// let dispatcher = FileEventDispatcher::file(&file_path, EventFormat::Ndjson)?
+///     .with_rotation(10_000_000, 5);
+///
+/// let client = Client::from_local_datafile("../datafiles/sandbox.json")?
+///     .with_event_dispatcher(|_| dispatcher)
+///     .initialize();
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct FileEventDispatcher {
+    format: EventFormat,
+    output: Mutex<Output>,
+}
+
+/// Destination of the records
+enum Output {
+    Stdout { header_written: bool },
+    File(RotatingFile),
+}
+
+impl FileEventDispatcher {
+    /// Constructor for a file event dispatcher that writes to stdout
+    pub fn stdout(format: EventFormat) -> FileEventDispatcher {
+        FileEventDispatcher {
+            format,
+            output: Mutex::new(Output::Stdout {
+                header_written: false,
+            }),
+        }
+    }
+
+    /// Constructor for a file event dispatcher that appends to the given file
+    ///
+    /// The file is created if it does not exist yet.
+    pub fn file<P>(path: P, format: EventFormat) -> Result<FileEventDispatcher, EventApiError>
+    where
+        P: AsRef<Path>,
+    {
+        let file = RotatingFile::open(path.as_ref())?;
+
+        Ok(FileEventDispatcher {
+            format,
+            output: Mutex::new(Output::File(file)),
+        })
+    }
+
+    /// Start a new file once the current file would exceed the maximum size in bytes
+    ///
+    /// The full file is renamed to `{path}.1`, older files are renamed to `{path}.2` and so on,
+    /// and only the given number of these rotated files are kept.
+    /// This only has effect on dispatchers created with [FileEventDispatcher::file].
+    pub fn with_rotation(self, max_bytes: u64, max_files: usize) -> FileEventDispatcher {
+        match &mut *self.output.lock().unwrap_or_else(|e| e.into_inner()) {
+            Output::File(file) => file.set_rotation(max_bytes, max_files),
+            Output::Stdout { .. } => log::warn!("Rotation is ignored, since events are written to stdout"),
+        }
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Getter for `format` field
+    pub fn format(&self) -> EventFormat {
+        self.format
+    }
+
+    fn write(&self, record: &EventRecord) -> Result<(), EventApiError> {
+        let line = self.format.encode(record)?;
+        let header = self.format.header();
+
+        // Hold the lock while writing, so records of concurrent events are not interleaved
+        let mut output = self.output.lock().unwrap_or_else(|e| e.into_inner());
+        match &mut *output {
+            Output::Stdout { header_written } => {
+                let mut stdout = io::stdout().lock();
+                if let Some(header) = header.as_deref().filter(|_| !*header_written) {
+                    stdout
+                        .write_all(header.as_bytes())
+                        .change_context(EventApiError::FailedFileWrite)?;
+                }
+                *header_written = true;
+                stdout
+                    .write_all(line.as_bytes())
+                    .change_context(EventApiError::FailedFileWrite)
+            }
+            Output::File(file) => {
+                if file.is_full(line.len()) {
+                    file.rotate()?;
+                }
+                // Every file starts with a header, so rotated files can be read on their own
+                if let Some(header) = header.as_deref().filter(|_| file.is_empty()) {
+                    file.write(header.as_bytes())?;
+                }
+                file.write(line.as_bytes())
+            }
+        }
+    }
+}
+
+impl EventDispatcher for FileEventDispatcher {
+    fn dispatch_event(&self, event: LogEvent) {
+        log::debug!("Writing event to file");
+
+        if let Err(report) = self.write(&EventRecord::from(&event)) {
+            log::error!("Unable to write event\n{report:?}");
+        }
+    }
+
+    fn flush(&self, _timeout: Duration) -> bool {
+        // Records are written right away, but may still be buffered by stdout or the operating system
+        let result = match &mut *self.output.lock().unwrap_or_else(|e| e.into_inner()) {
+            Output::Stdout { .. } => io::stdout()
+                .flush()
+                .change_context(EventApiError::FailedFileWrite),
+            Output::File(file) => file.sync(),
+        };
+
+        match result {
+            Ok(()) => true,
+            Err(report) => {
+                log::error!("Unable to flush events\n{report:?}");
+                false
+            }
+        }
+    }
+}
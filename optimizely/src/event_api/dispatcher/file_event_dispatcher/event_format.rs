@@ -0,0 +1,59 @@
+// External imports
+use error_stack::{Result, ResultExt};
+
+// Imports from crate
+use crate::error::EventApiError;
+
+// Imports from super
+use super::event_record::{EventRecord, COLUMNS};
+
+/// Format of the records that a [FileEventDispatcher](super::FileEventDispatcher) writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EventFormat {
+    /// A JSON object on every line (newline delimited JSON)
+    #[default]
+    Ndjson,
+    /// Comma separated values with a header line, where the attributes and tags are JSON objects
+    Csv,
+}
+
+impl EventFormat {
+    /// Line to write at the start of every file, if the format has one
+    pub(super) fn header(&self) -> Option<String> {
+        match self {
+            EventFormat::Ndjson => None,
+            EventFormat::Csv => Some(csv_line(COLUMNS.iter().copied())),
+        }
+    }
+
+    /// Encode a record as a single line, including the line break
+    pub(super) fn encode(&self, record: &EventRecord) -> Result<String, EventApiError> {
+        match self {
+            EventFormat::Ndjson => {
+                let json = serde_json::to_string(record).change_context(EventApiError::FailedSerialize)?;
+                Ok(format!("{json}\n"))
+            }
+            EventFormat::Csv => {
+                let fields = record
+                    .csv_fields()
+                    .change_context(EventApiError::FailedSerialize)?;
+                Ok(csv_line(fields.iter().map(String::as_str)))
+            }
+        }
+    }
+}
+
+fn csv_line<'a>(fields: impl Iterator<Item = &'a str>) -> String {
+    let mut line = fields.map(csv_field).collect::<Vec<_>>().join(",");
+    line.push('\n');
+    line
+}
+
+// Quote fields that contain a separator, quote or line break, and escape quotes by doubling them
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
@@ -0,0 +1,120 @@
+// External imports
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// Imports from crate
+use crate::event_api::{LogEvent, LogEventKind};
+use crate::TagValue;
+
+/// Names of the fields of a record, in the order of the CSV columns
+pub(super) const COLUMNS: [&str; 12] = [
+    "timestamp",
+    "event_type",
+    "visitor_id",
+    "session_id",
+    "attributes",
+    "flag_key",
+    "rule_key",
+    "experiment_id",
+    "variation_key",
+    "enabled",
+    "event_key",
+    "tags",
+];
+
+/// A single decision or conversion, as it is written to the file
+///
+/// Fields that do not apply to the type of event are null, so every record has the same fields.
+/// Attributes and tags are sorted by key, so identical events result in identical records.
+#[derive(Serialize)]
+pub(super) struct EventRecord<'a> {
+    timestamp: u128,
+    event_type: &'static str,
+    visitor_id: &'a str,
+    session_id: Option<&'a str>,
+    attributes: BTreeMap<&'a str, String>,
+    flag_key: Option<&'a str>,
+    rule_key: Option<&'a str>,
+    experiment_id: Option<&'a str>,
+    variation_key: Option<&'a str>,
+    enabled: Option<bool>,
+    event_key: Option<&'a str>,
+    tags: Option<BTreeMap<&'a str, &'a TagValue>>,
+}
+
+impl<'a> From<&'a LogEvent> for EventRecord<'a> {
+    fn from(event: &'a LogEvent) -> Self {
+        let attributes = event
+            .attributes()
+            .iter()
+            .map(|attribute| (attribute.key(), String::from(attribute.value())))
+            .collect();
+
+        let mut record = EventRecord {
+            timestamp: event.timestamp(),
+            event_type: "",
+            visitor_id: event.visitor_id(),
+            session_id: event.session_id(),
+            attributes,
+            flag_key: None,
+            rule_key: None,
+            experiment_id: None,
+            variation_key: None,
+            enabled: None,
+            event_key: None,
+            tags: None,
+        };
+
+        match event.kind() {
+            LogEventKind::Decision(decision) => {
+                record.event_type = "decision";
+                record.flag_key = Some(decision.flag_key());
+                record.rule_key = Some(decision.rule_key());
+                record.experiment_id = Some(decision.experiment_id());
+                record.variation_key = Some(decision.variation_key());
+                record.enabled = Some(decision.enabled());
+            }
+            LogEventKind::Conversion(conversion) => {
+                let tags = conversion
+                    .tags()
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value))
+                    .collect();
+
+                record.event_type = "conversion";
+                record.event_key = Some(conversion.event_key());
+                record.tags = Some(tags);
+            }
+        }
+
+        record
+    }
+}
+
+impl EventRecord<'_> {
+    /// Values of the CSV columns, where missing values are empty and the attributes and tags are JSON objects
+    pub(super) fn csv_fields(&self) -> Result<[String; 12], serde_json::Error> {
+        let text = |value: Option<&str>| value.unwrap_or_default().to_owned();
+        let tags = match &self.tags {
+            Some(tags) => serde_json::to_string(tags)?,
+            None => String::new(),
+        };
+
+        Ok([
+            self.timestamp.to_string(),
+            self.event_type.to_owned(),
+            self.visitor_id.to_owned(),
+            text(self.session_id),
+            serde_json::to_string(&self.attributes)?,
+            text(self.flag_key),
+            text(self.rule_key),
+            text(self.experiment_id),
+            text(self.variation_key),
+            self.enabled
+                .map(|enabled| enabled.to_string())
+                .unwrap_or_default(),
+            text(self.event_key),
+            tags,
+        ])
+    }
+}
@@ -0,0 +1,131 @@
+// External imports
+use error_stack::{Result, ResultExt};
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+// Imports from crate
+use crate::error::EventApiError;
+
+/// File that records are appended to, which is replaced by a new file once it reached its maximum size
+pub(super) struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: Option<u64>,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    /// Open the file for appending, and create it if it does not exist yet
+    pub(super) fn open(path: &Path) -> Result<RotatingFile, EventApiError> {
+        let file = open_file(path)?;
+
+        // Continue with the size of the existing content
+        let size = file
+            .metadata()
+            .change_context(EventApiError::FailedFileWrite)
+            .attach_printable_lazy(|| format!("Event file: {}", path.display()))?
+            .len();
+
+        Ok(RotatingFile {
+            path: path.to_path_buf(),
+            file,
+            size,
+            max_size: None,
+            max_files: 0,
+        })
+    }
+
+    pub(super) fn set_rotation(&mut self, max_size: u64, max_files: usize) {
+        self.max_size = Some(max_size);
+        self.max_files = max_files;
+    }
+
+    /// Whether nothing was written to the current file yet
+    pub(super) fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Whether a record of the given length does not fit in the current file
+    ///
+    /// A record that is larger than the maximum size is still written to an empty file.
+    pub(super) fn is_full(&self, length: usize) -> bool {
+        match self.max_size {
+            Some(max_size) => !self.is_empty() && self.size + length as u64 > max_size,
+            None => false,
+        }
+    }
+
+    pub(super) fn write(&mut self, bytes: &[u8]) -> Result<(), EventApiError> {
+        self.file
+            .write_all(bytes)
+            .change_context(EventApiError::FailedFileWrite)
+            .attach_printable_lazy(|| format!("Event file: {}", self.path.display()))?;
+        self.size += bytes.len() as u64;
+
+        Ok(())
+    }
+
+    /// Wait until the operating system wrote all records to disk
+    pub(super) fn sync(&self) -> Result<(), EventApiError> {
+        self.file
+            .sync_data()
+            .change_context(EventApiError::FailedFileWrite)
+            .attach_printable_lazy(|| format!("Event file: {}", self.path.display()))
+    }
+
+    /// Shift the rotated files by one, move the current file to `{path}.1` and start an empty file
+    pub(super) fn rotate(&mut self) -> Result<(), EventApiError> {
+        if self.max_files == 0 {
+            remove_file(&self.path)?;
+        } else {
+            // The oldest file is removed, since it would exceed the number of rotated files
+            remove_file(&self.rotated_path(self.max_files))?;
+            for index in (1..self.max_files).rev() {
+                rename_file(&self.rotated_path(index), &self.rotated_path(index + 1))?;
+            }
+            rename_file(&self.path, &self.rotated_path(1))?;
+        }
+
+        self.file = open_file(&self.path)?;
+        self.size = 0;
+
+        Ok(())
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = OsString::from(&self.path);
+        path.push(format!(".{index}"));
+        PathBuf::from(path)
+    }
+}
+
+fn open_file(path: &Path) -> Result<File, EventApiError> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .change_context(EventApiError::FailedFileWrite)
+        .attach_printable_lazy(|| format!("Event file: {}", path.display()))
+}
+
+// Files that do not exist (yet) are skipped
+fn remove_file(path: &Path) -> Result<(), EventApiError> {
+    match fs::remove_file(path) {
+        Err(error) if error.kind() != ErrorKind::NotFound => Err(error)
+            .change_context(EventApiError::FailedFileWrite)
+            .attach_printable_lazy(|| format!("Event file: {}", path.display())),
+        _ => Ok(()),
+    }
+}
+
+fn rename_file(from: &Path, to: &Path) -> Result<(), EventApiError> {
+    match fs::rename(from, to) {
+        Err(error) if error.kind() != ErrorKind::NotFound => Err(error)
+            .change_context(EventApiError::FailedFileWrite)
+            .attach_printable_lazy(|| format!("Event file: {}", from.display())),
+        _ => Ok(()),
+    }
+}
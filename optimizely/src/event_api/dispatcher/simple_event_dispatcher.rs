@@ -1,54 +1,67 @@
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
 
 // Imports from super
 use super::EventDispatcher;
 
 // Imports from crate
-use crate::event_api::request::{Request, Visitor};
-use crate::{client::UserContext, Conversion, Decision};
+use crate::event_api::request::{DispatchOutcome, Request, RetryPolicy};
+use crate::event_api::LogEvent;
 
 /// Implementation of the EventDispatcher trait that makes an HTTP request for every event
 ///
+/// The request is made on the thread that makes the decision or tracks the event, so failed requests are not retried
+/// by default. Use [BatchedEventDispatcher](super::BatchedEventDispatcher) to retry on a background thread instead.
+///
 /// TODO: add example usage in SDK
 pub struct SimpleEventDispatcher {
     request: RwLock<Request>,
+    last_outcome: Mutex<Option<DispatchOutcome>>,
 }
 
 impl SimpleEventDispatcher {
     /// Constructor for a new simple event dispatcher
     pub fn new(datafile: &crate::datafile::Datafile) -> SimpleEventDispatcher {
-        // Generate a new payload
-        let request = RwLock::new(Request::new(datafile));
+        // Generate a new payload, without blocking the caller for retries
+        let mut request = Request::new(datafile);
+        request.set_retry_policy(RetryPolicy::no_retries());
+        let request = RwLock::new(request);
 
-        SimpleEventDispatcher { request }
+        SimpleEventDispatcher {
+            request,
+            last_outcome: Mutex::default(),
+        }
     }
-}
-
-impl EventDispatcher for SimpleEventDispatcher {
-    fn send_conversion_event(&self, user_context: &UserContext, conversion: Conversion) {
-        log::debug!("Sending conversion event to Event API");
 
-        // Get mutable reference to request
-        let mut request = match self.request.try_write() {
-            Ok(request) => request,
-            Err(_) => {
-                log::error!("Unable to dispatch concurrently");
-                return;
-            }
-        };
+    /// Use a different retry policy for requests that fail with a server error or network error
+    ///
+    /// Retries block the caller, and events that are dispatched concurrently in the meantime are dropped.
+    pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> SimpleEventDispatcher {
+        if let Ok(mut request) = self.request.write() {
+            request.set_retry_policy(retry_policy);
+        }
 
-        // Create new request::Visitor
-        let visitor = Visitor::from(user_context);
+        // Return self, so can chain other functions
+        self
+    }
 
-        // Add single conversion
-        request.add_conversion_event(visitor, conversion);
+    /// Get the outcome of the last request to the Event API
+    pub fn last_outcome(&self) -> Option<DispatchOutcome> {
+        self.last_outcome
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
 
-        // Dispatch single conversion
-        request.send()
+    fn store_outcome(&self, outcome: Option<DispatchOutcome>) {
+        if outcome.is_some() {
+            *self.last_outcome.lock().unwrap_or_else(|e| e.into_inner()) = outcome;
+        }
     }
+}
 
-    fn send_decision_event(&self, user_context: &UserContext, decision: Decision) {
-        log::debug!("Sending decision event to Event API");
+impl EventDispatcher for SimpleEventDispatcher {
+    fn dispatch_event(&self, event: LogEvent) {
+        log::debug!("Sending event to Event API");
 
         // Get mutable reference to request
         let mut request = match self.request.try_write() {
@@ -59,13 +72,20 @@ impl EventDispatcher for SimpleEventDispatcher {
             }
         };
 
-        // Create new request::Visitor
-        let visitor = Visitor::from(user_context);
+        // Use the account, project and revision of the datafile and the endpoint of the client at the time of the event
+        request.update_context(event.context());
+        request.update_client_ip(event.client_ip());
+        request.update_endpoint(event.endpoint_url());
 
-        // Add single decision
-        request.add_decision_event(visitor, decision);
+        // Add single event, which is skipped if it cannot be serialized
+        if let Err(report) = request.add_event(event) {
+            log::error!("Failed to add event to request");
+            log::error!("\n{report:?}");
+            return;
+        }
 
-        // Dispatch single decision
-        request.send()
+        // Dispatch single event
+        let outcome = request.send();
+        self.store_outcome(outcome);
     }
 }
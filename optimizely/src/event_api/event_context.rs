@@ -0,0 +1,55 @@
+// External imports
+use serde::{Deserialize, Serialize};
+
+// Imports from crate
+use crate::datafile::Datafile;
+
+/// Information about where an event comes from, rather than about the event itself
+///
+/// The Event API expects these fields once per payload, so events with a different context are sent in a different payload.
+/// Since every event of a datafile has the same context, events of different users can be sent in a single payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventContext {
+    account_id: String,
+    project_id: String,
+    revision: String,
+    anonymize_ip: bool,
+}
+
+impl EventContext {
+    /// Context of the events that are produced using the given datafile
+    pub fn new(datafile: &Datafile) -> EventContext {
+        EventContext {
+            account_id: datafile.account_id().to_owned(),
+            project_id: datafile.project_id().to_owned(),
+            revision: datafile.revision().to_string(),
+            anonymize_ip: datafile.anonymize_ip(),
+        }
+    }
+
+    /// Getter for `account_id` field
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// Getter for `project_id` field
+    pub fn project_id(&self) -> &str {
+        &self.project_id
+    }
+
+    /// Getter for `revision` field
+    pub fn revision(&self) -> &str {
+        &self.revision
+    }
+
+    /// Getter for `anonymize_ip` field
+    pub fn anonymize_ip(&self) -> bool {
+        self.anonymize_ip
+    }
+}
+
+impl From<&Datafile> for EventContext {
+    fn from(datafile: &Datafile) -> Self {
+        EventContext::new(datafile)
+    }
+}
@@ -0,0 +1,134 @@
+// External imports
+use serde::Serialize;
+use std::time::SystemTime;
+
+// Imports from crate
+use crate::client::UserContext;
+use crate::{Conversion, Decision, UserAttribute};
+
+// Imports from super
+use super::EventContext;
+
+/// A decision or conversion event, together with everything that is needed to send it
+///
+/// The event owns all of its data, so event dispatchers can queue, persist or forward it to another thread
+/// without borrowing from the client. It can be serialized to store it or to send it to another service.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEvent {
+    timestamp: u128,
+    visitor_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    attributes: Vec<UserAttribute>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_ip: Option<String>,
+    context: EventContext,
+    endpoint_url: String,
+    #[serde(flatten)]
+    kind: LogEventKind,
+}
+
+/// Type of a log event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogEventKind {
+    /// Decision that was made for a flag
+    Decision(Decision),
+    /// Conversion event that was tracked
+    Conversion(Conversion),
+}
+
+impl LogEvent {
+    /// Create an event for the user
+    ///
+    /// The context and endpoint are captured by the caller from the same datafile that produced the decision or
+    /// conversion, since the datafile of the client might be replaced in the meantime.
+    pub(crate) fn new(
+        user_context: &UserContext, context: EventContext, endpoint_url: String, kind: LogEventKind,
+    ) -> LogEvent {
+        // Conversions already have a timestamp, decisions are made right now
+        let timestamp = match &kind {
+            LogEventKind::Conversion(conversion) => conversion.timestamp(),
+            LogEventKind::Decision(_) => match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+                Ok(duration) => duration.as_millis(),
+                Err(_) => 0,
+            },
+        };
+
+        LogEvent {
+            timestamp,
+            visitor_id: user_context.user_id().to_owned(),
+            session_id: user_context.session_id().map(String::from),
+            attributes: user_context
+                .user_attributes()
+                .into_iter()
+                .cloned()
+                .collect(),
+            client_ip: user_context.client_ip().map(String::from),
+            context,
+            endpoint_url,
+            kind,
+        }
+    }
+
+    /// Get timestamp in milliseconds since the epoch
+    pub fn timestamp(&self) -> u128 {
+        self.timestamp
+    }
+
+    /// Getter for `visitor_id` field
+    pub fn visitor_id(&self) -> &str {
+        &self.visitor_id
+    }
+
+    /// Getter for `session_id` field
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+
+    /// Getter for `attributes` field
+    pub fn attributes(&self) -> &[UserAttribute] {
+        &self.attributes
+    }
+
+    /// Getter for `client_ip` field
+    pub fn client_ip(&self) -> Option<&str> {
+        self.client_ip.as_deref()
+    }
+
+    /// Get the account, project and revision of the datafile that was used for the event
+    pub fn context(&self) -> &EventContext {
+        &self.context
+    }
+
+    /// Get the URL to which the event should be sent, see [Client::event_endpoint_url](crate::Client::event_endpoint_url)
+    pub fn endpoint_url(&self) -> &str {
+        &self.endpoint_url
+    }
+
+    /// Getter for `kind` field
+    pub fn kind(&self) -> &LogEventKind {
+        &self.kind
+    }
+
+    /// Take the decision or conversion out of the event
+    pub fn into_kind(self) -> LogEventKind {
+        self.kind
+    }
+
+    /// Get the decision, if this is a decision event
+    pub fn decision(&self) -> Option<&Decision> {
+        match &self.kind {
+            LogEventKind::Decision(decision) => Some(decision),
+            LogEventKind::Conversion(_) => None,
+        }
+    }
+
+    /// Get the conversion, if this is a conversion event
+    pub fn conversion(&self) -> Option<&Conversion> {
+        match &self.kind {
+            LogEventKind::Conversion(conversion) => Some(conversion),
+            LogEventKind::Decision(_) => None,
+        }
+    }
+}
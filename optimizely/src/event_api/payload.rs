@@ -0,0 +1,121 @@
+// External imports
+use error_stack::{Result, ResultExt};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+// Imports from crate
+use crate::event_api::{EventContext, LogEvent, LogEventKind};
+use crate::{error::EventApiError, Conversion, Decision, EventTags};
+
+// Relative imports of sub modules
+use snapshot::Snapshot;
+use visitor::Visitor;
+
+mod snapshot;
+mod visitor;
+
+// Information regarding the SDK client
+const CLIENT_NAME: &str = "rust-sdk";
+const CLIENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Event key for activating an experiment
+const ACTIVATE_EVENT_KEY: &str = "campaign_activated";
+
+#[derive(Serialize)]
+/// HTTP request payload to send to Event API
+pub struct Payload {
+    #[serde(flatten)]
+    context: EventContext,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_ip: Option<String>,
+    visitors: Vec<Value>,
+    enrich_decisions: bool,
+    client_name: &'static str,
+    client_version: &'static str,
+}
+
+impl Payload {
+    /// Construct an empty payload for a given account, project and revision, and the IP address of its visitors
+    pub fn new(context: EventContext, client_ip: Option<String>) -> Payload {
+        Payload {
+            context,
+            client_ip,
+            visitors: Vec::new(),
+            enrich_decisions: true,
+            client_name: CLIENT_NAME,
+            client_version: CLIENT_VERSION,
+        }
+    }
+
+    /// Return the number of visitors in the payload
+    #[cfg(feature = "online")]
+    pub fn size(&self) -> usize {
+        self.visitors.len()
+    }
+
+    /// Add a decision or conversion event to the payload
+    #[cfg(feature = "testing")]
+    pub fn add_event(&mut self, event: LogEvent) -> Result<(), EventApiError> {
+        log::debug!("Adding event to payload");
+
+        self.add_visitor(Payload::event_visitor(event)?);
+        Ok(())
+    }
+
+    /// Add a visitor that was already serialized, for example by a durable queue
+    pub fn add_visitor(&mut self, visitor: Value) {
+        self.visitors.push(visitor);
+    }
+
+    /// Serialize the visitor of the event together with the event
+    pub fn event_visitor(event: LogEvent) -> Result<Value, EventApiError> {
+        let visitor = Visitor::from(&event);
+        match event.into_kind() {
+            LogEventKind::Decision(decision) => Payload::decision_visitor(visitor, decision),
+            LogEventKind::Conversion(conversion) => Payload::conversion_visitor(visitor, conversion),
+        }
+    }
+
+    /// Serialize the visitor with a conversion event
+    fn conversion_visitor(mut visitor: Visitor, event: Conversion) -> Result<Value, EventApiError> {
+        // Add custom event
+        visitor.add_conversion_event(event);
+
+        visitor.to_value()
+    }
+
+    /// Serialize the visitor with a decision event and the corresponding campaign activated event
+    ///
+    /// The campaign activated event gets a new UUID, so serialize once and reuse the value when sending it again.
+    fn decision_visitor(mut visitor: Visitor, decision: Decision) -> Result<Value, EventApiError> {
+        // Copy campaign_id as entity_id
+        let entity_id = decision.campaign_id().to_owned();
+
+        // Add decision to visitor
+        visitor.add_decision_event(decision);
+
+        // Campaign activated event does not have tags or properties
+        let properties = HashMap::default();
+        let tags = EventTags::default();
+
+        // Add campaign_activated event
+        let event = Conversion::new(ACTIVATE_EVENT_KEY.to_owned(), entity_id, properties, tags);
+        visitor.add_conversion_event(event);
+
+        visitor.to_value()
+    }
+
+    /// Convert the Payload struct to a JSON document
+    #[cfg(feature = "testing")]
+    pub fn to_value(&self) -> Result<Value, EventApiError> {
+        serde_json::to_value(self).change_context(EventApiError::FailedSerialize)
+    }
+
+    /// Convert the Payload struct to a JSON encoded text
+    #[cfg(feature = "online")]
+    pub fn to_string(&self) -> Result<String, EventApiError> {
+        // Convert to JSON document and dump as String
+        serde_json::to_string(self).change_context(EventApiError::FailedSerialize)
+    }
+}
@@ -0,0 +1,55 @@
+// External imports
+use error_stack::{Result, ResultExt};
+use serde::Serialize;
+use serde_json::Value;
+
+// Imports from crate
+use crate::error::EventApiError;
+use crate::event_api::LogEvent;
+use crate::{Conversion, Decision, UserAttribute};
+
+// Imports from super
+use super::Snapshot;
+
+#[derive(Serialize)]
+pub struct Visitor {
+    visitor_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    attributes: Vec<UserAttribute>,
+    snapshots: [Snapshot; 1],
+}
+
+impl Visitor {
+    pub fn new(visitor_id: String, session_id: Option<String>, attributes: Vec<UserAttribute>) -> Visitor {
+        Visitor {
+            visitor_id,
+            session_id,
+            attributes,
+            snapshots: [Snapshot::default()],
+        }
+    }
+
+    pub fn add_decision_event(&mut self, decision: Decision) {
+        self.snapshots[0].add_decision_event(decision);
+    }
+
+    pub fn add_conversion_event(&mut self, event: Conversion) {
+        self.snapshots[0].add_conversion_event(event);
+    }
+
+    /// Serialize to a JSON document, which can be stored and sent multiple times
+    pub fn to_value(&self) -> Result<Value, EventApiError> {
+        serde_json::to_value(self).change_context(EventApiError::FailedSerialize)
+    }
+}
+
+impl From<&LogEvent> for Visitor {
+    fn from(event: &LogEvent) -> Self {
+        let visitor_id = event.visitor_id().into();
+        let session_id = event.session_id().map(String::from);
+        let attributes = event.attributes().to_vec();
+
+        Visitor::new(visitor_id, session_id, attributes)
+    }
+}
@@ -1,30 +1,42 @@
 //! Structure for the request payload
 
 // External imports
-use error_stack::{Result, ResultExt};
+use error_stack::{Report, Result};
+use serde_json::Value;
+use std::thread::sleep;
+use std::time::Duration;
 
-//
+// Imports from crate
 use crate::datafile::Datafile;
 use crate::error::EventApiError;
-use crate::{Conversion, Decision};
+use crate::event_api::payload::Payload;
+use crate::event_api::{EventContext, LogEvent};
 
 // Relative imports of sub modules
-pub(crate) use payload::Payload;
-use snapshot::Snapshot;
-pub(crate) use visitor::Visitor;
+pub use dispatch_outcome::DispatchOutcome;
+pub use retry_policy::RetryPolicy;
 
-mod payload;
-mod snapshot;
-mod visitor;
+mod dispatch_outcome;
+mod retry_policy;
 
 // Information about the API endpoint
-const ENDPOINT_URL: &str = "https://logx.optimizely.com/v1/events";
 const CONTENT_TYPE_KEY: &str = "content-type";
 const CONTENT_TYPE_VALUE: &str = "application/json";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Result of a single attempt to send a payload
+enum Attempt {
+    Delivered,
+    Rejected { status: u16, body: String },
+    Failed(Report<EventApiError>),
+}
 
 /// Representation of an HTTP POST request to the EVENT API
 pub struct Request {
-    account_id: String,
+    context: EventContext,
+    client_ip: Option<String>,
+    endpoint_url: String,
+    retry_policy: RetryPolicy,
     payload: Option<Payload>,
 }
 
@@ -32,27 +44,89 @@ impl Request {
     /// Create a new empty Request
     pub fn new(datafile: &Datafile) -> Request {
         Request {
-            account_id: datafile.account_id().to_owned(),
+            context: EventContext::from(datafile),
+            client_ip: None,
+            endpoint_url: format!("{}/v1/events", datafile.region().event_api_url()),
+            retry_policy: RetryPolicy::default(),
             payload: Option::None,
         }
     }
 
+    /// Switch to the account, project and revision of the current datafile
+    ///
+    /// Any events that were added with a different context are sent first, since a payload has a single context.
+    pub fn update_context(&mut self, context: &EventContext) {
+        if &self.context != context {
+            if self.payload.is_some() {
+                self.send();
+            }
+            self.context = context.clone();
+        }
+    }
+
+    /// Switch to the IP address of the user
+    ///
+    /// The Event API expects the IP address once per payload, so any events of users with a different IP address
+    /// are sent first. Events without an IP address are batched together regardless of the user.
+    pub fn update_client_ip(&mut self, client_ip: Option<&str>) {
+        if self.client_ip.as_deref() != client_ip {
+            if self.payload.is_some() {
+                self.send();
+            }
+            self.client_ip = client_ip.map(String::from);
+        }
+    }
+
+    /// Switch to the endpoint of the client
+    ///
+    /// Any events that were added for a different endpoint are sent there first.
+    pub fn update_endpoint(&mut self, endpoint_url: &str) {
+        if self.endpoint_url != endpoint_url {
+            if self.payload.is_some() {
+                self.send();
+            }
+            self.endpoint_url = endpoint_url.to_owned();
+        }
+    }
+
+    /// Use a different retry policy for the next requests
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
     /// Use existing payload or create new one
     fn payload(&mut self) -> &mut Payload {
         self.payload.get_or_insert_with(|| {
-            let account_id = self.account_id.clone();
-            Payload::new(account_id)
+            let context = self.context.clone();
+            Payload::new(context, self.client_ip.clone())
         })
     }
 
-    /// Add a conversion event for a specific visitor to the payload
-    pub fn add_conversion_event(&mut self, visitor: Visitor, conversion: Conversion) {
-        self.payload().add_conversion_event(visitor, conversion);
+    /// Add a decision or conversion event to the payload
+    pub fn add_event(&mut self, event: LogEvent) -> Result<(), EventApiError> {
+        let visitor = Payload::event_visitor(event)?;
+        self.payload().add_visitor(visitor);
+        Ok(())
+    }
+
+    /// Add a visitor that was already serialized, for example by a durable queue
+    pub fn add_visitor(&mut self, visitor: Value) {
+        self.payload().add_visitor(visitor);
+    }
+
+    /// Getter for `context` field
+    pub fn context(&self) -> &EventContext {
+        &self.context
+    }
+
+    /// Getter for `client_ip` field
+    pub fn client_ip(&self) -> Option<&str> {
+        self.client_ip.as_deref()
     }
 
-    /// Add a decision event for a specific visitor to the payload
-    pub fn add_decision_event(&mut self, visitor: Visitor, decision: Decision) {
-        self.payload().add_decision_event(visitor, decision);
+    /// Getter for `endpoint_url` field
+    pub fn endpoint_url(&self) -> &str {
+        &self.endpoint_url
     }
 
     /// Get the number of visitors in the current payload
@@ -63,47 +137,81 @@ impl Request {
         }
     }
 
-    /// Send entire payload
-    pub fn send(&mut self) {
+    /// Send entire payload, retrying server errors, network errors, timeouts (408) and rate limits (429)
+    /// according to the retry policy
+    ///
+    /// The payload is serialized once, so every attempt contains the same UUIDs and the Event API can de-duplicate.
+    /// Returns `None` if there was nothing to send.
+    pub fn send(&mut self) -> Option<DispatchOutcome> {
         //  Take the payload, so it cannot be send another time
-        if let Some(payload) = self.payload.take() {
-            // Sending payload
-            log::debug!("Sending request to Event API");
-
-            // Convert to payload to string
-            let body = match payload.to_string() {
-                Ok(body) => body,
-                Err(report) => {
-                    log::error!("Failed to serialize payload");
-                    log::error!("\n{report:?}");
-                    return;
-                }
-            };
-
-            // Make POST request
-            let response = Self::post(&body);
+        let Some(payload) = self.payload.take() else {
+            log::warn!("Nothing to send");
+            return None;
+        };
+
+        // Sending payload
+        log::debug!("Sending request to Event API");
+
+        // Convert to payload to string
+        let body = match payload.to_string() {
+            Ok(body) => body,
+            Err(report) => {
+                log::error!("Failed to serialize payload");
+                log::error!("\n{report:?}");
+                return Some(DispatchOutcome::Failed { attempts: 0 });
+            }
+        };
 
-            // Send payload to endpoint
-            match response {
-                Ok(_) => {
-                    // TODO: verify status code
+        let max_attempts = self.retry_policy.max_attempts();
+        for attempt in 1..=max_attempts {
+            match self.post(&body) {
+                Attempt::Delivered => {
                     log::info!("Successfully sent request to Event API");
+                    return Some(DispatchOutcome::Delivered { attempts: attempt });
+                }
+                Attempt::Rejected { status, body } => {
+                    // Sending the same payload again would have the same result
+                    log::error!("Event API rejected request with status {status}: {body}");
+                    return Some(DispatchOutcome::Rejected { status, body });
                 }
-                Err(report) => {
-                    log::error!("Failed to send request to Event API");
+                Attempt::Failed(report) if attempt < max_attempts => {
+                    let backoff = self.retry_policy.backoff(attempt);
+                    log::warn!("Failed to send request to Event API, retrying in {backoff:?}");
+                    log::debug!("\n{report:?}");
+                    sleep(backoff);
+                }
+                Attempt::Failed(report) => {
+                    log::error!("Failed to send request to Event API after {attempt} attempts");
                     log::error!("\n{report:?}");
                 }
             }
-        } else {
-            log::warn!("Nothing to send");
         }
+
+        Some(DispatchOutcome::Failed {
+            attempts: max_attempts,
+        })
     }
 
-    fn post(body: &str) -> Result<ureq::Response, EventApiError> {
-        ureq::post(ENDPOINT_URL)
+    fn post(&self, body: &str) -> Attempt {
+        let response = ureq::post(&self.endpoint_url)
             .set(CONTENT_TYPE_KEY, CONTENT_TYPE_VALUE)
-            .send_string(body)
-            .change_context(EventApiError::FailedRequest)
+            .timeout(REQUEST_TIMEOUT)
+            .send_string(body);
+
+        match response {
+            Ok(_) => Attempt::Delivered,
+            // Sending the same payload again would have the same result, except for timeouts and rate limits
+            Err(ureq::Error::Status(status, response))
+                if (400..500).contains(&status) && ![408, 429].contains(&status) =>
+            {
+                let body = response.into_string().unwrap_or_default();
+                Attempt::Rejected { status, body }
+            }
+            Err(ureq::Error::Status(status, _)) => Attempt::Failed(
+                Report::new(EventApiError::FailedResponse).attach_printable(format!("Status code: {status}")),
+            ),
+            Err(error) => Attempt::Failed(Report::new(error).change_context(EventApiError::FailedRequest)),
+        }
     }
 }
 
@@ -113,7 +221,7 @@ impl Drop for Request {
 
         // If the Request is dropped and contained some data, make one last request to the Event API
         if self.payload.is_some() {
-            self.send()
+            self.send();
         }
     }
 }
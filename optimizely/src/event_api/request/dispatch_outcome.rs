@@ -0,0 +1,28 @@
+/// Result of sending a payload to the Event API
+#[derive(Debug, Clone, PartialEq)]
+pub enum DispatchOutcome {
+    /// The Event API accepted the payload
+    Delivered {
+        /// Number of attempts that were needed, including the successful one
+        attempts: u32,
+    },
+    /// The Event API rejected the payload with a client error, so it was dropped without retrying
+    Rejected {
+        /// Status code of the response
+        status: u16,
+        /// Body of the response, which describes the problem
+        body: String,
+    },
+    /// Every attempt failed with a server error or network error, so the payload was dropped
+    Failed {
+        /// Number of attempts that were made
+        attempts: u32,
+    },
+}
+
+impl DispatchOutcome {
+    /// Whether the Event API accepted the payload
+    pub fn is_delivered(&self) -> bool {
+        matches!(self, DispatchOutcome::Delivered { .. })
+    }
+}
@@ -0,0 +1,67 @@
+// External imports
+use std::time::Duration;
+
+/// How often and how long to retry a request to the Event API after a server error or network error
+///
+/// The time between attempts doubles after every attempt, up to the maximum backoff.
+/// Requests that are rejected with a client error (4xx) are never retried, since the result would be the same,
+/// except for 408 Request Timeout and 429 Too Many Requests.
+///
+/// ```
+/// use optimizely::event_api::RetryPolicy;
+/// use std::time::Duration;
+///
+/// // Wait 100ms, 200ms and 400ms between the attempts
+/// let retry_policy = RetryPolicy::new(4, Duration::from_millis(100));
+/// assert_eq!(retry_policy.backoff(3), Duration::from_millis(400));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Try at most `max_attempts` times, waiting `initial_backoff` after the first attempt
+    pub fn new(max_attempts: u32, initial_backoff: Duration) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            initial_backoff,
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+
+    /// Only make a single attempt
+    pub fn no_retries() -> RetryPolicy {
+        RetryPolicy::new(1, Duration::ZERO)
+    }
+
+    /// Limit the time between attempts
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> RetryPolicy {
+        self.max_backoff = max_backoff;
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Getter for `max_attempts` field
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Time to wait after the given attempt, where the first attempt is 1
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Try 3 times, waiting 1 second and then 2 seconds
+    fn default() -> Self {
+        RetryPolicy::new(3, Duration::from_secs(1))
+    }
+}
@@ -10,10 +10,12 @@ pub use types::*;
 
 // Standard modules
 pub mod client;
+pub mod config;
 pub mod datafile;
 pub mod error;
+pub mod event_api;
 mod types;
 
 // Optional module
-#[cfg(feature = "online")]
-pub mod event_api;
+#[cfg(feature = "testing")]
+pub mod testing;
@@ -0,0 +1,25 @@
+//! Utilities for testing code that uses the Optimizely SDK
+//!
+//! This module is only available with the `testing` feature.
+//!
+//! ```
+//! use optimizely::testing::{DatafileBuilder, FlagBuilder, RuleBuilder};
+//!
+//! // A flag that is enabled for half of the users
+//! let client = DatafileBuilder::new()
+//!     .flag(FlagBuilder::new("checkout").delivery_rule(RuleBuilder::new("half").variation("on", 0..5_000)))
+//!     .build_client()?
+//!     .initialize();
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+// Relative imports of sub modules
+pub use datafile_builder::{DatafileBuilder, FlagBuilder, RuleBuilder, VariationBuilder};
+pub use flag_overrides::{FlagOverride, FlagOverrides, RecordedDecision};
+pub use mock_server::{DatafileRequest, EventRequest, MockServer};
+pub use recording_event_dispatcher::{RecordedEvent, RecordingEventDispatcher};
+
+mod datafile_builder;
+mod flag_overrides;
+mod mock_server;
+mod recording_event_dispatcher;
@@ -0,0 +1,489 @@
+// External imports
+use error_stack::{Report, Result};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::ops::Range;
+
+// Imports from crate
+use crate::client::{Client, UninitializedClient};
+use crate::datafile::{Datafile, Region};
+use crate::error::{ClientError, DatafileError};
+
+/// Builder for a datafile, so tests do not depend on hand-written JSON
+///
+/// IDs are assigned sequentially in the order in which flags, rules, variations, audiences, attributes and events are
+/// added, so the same builder always results in the same decisions.
+/// Audiences are referenced by name and variables are referenced by key.
+///
+/// The resulting datafile is validated, so overlapping traffic ranges, unknown audiences, unknown variables or
+/// duplicate audience names result in an error.
+#[derive(Debug, Clone)]
+pub struct DatafileBuilder {
+    account_id: String,
+    project_id: String,
+    revision: u32,
+    region: Region,
+    anonymize_ip: bool,
+    send_flag_decisions: bool,
+    attributes: Vec<String>,
+    events: Vec<String>,
+    audiences: Vec<(String, Value)>,
+    flags: Vec<FlagBuilder>,
+}
+
+/// Builder for a flag with its experiment rules, delivery rules and variables
+#[derive(Debug, Clone)]
+pub struct FlagBuilder {
+    key: String,
+    variables: Vec<(String, String, String)>,
+    experiment_rules: Vec<RuleBuilder>,
+    delivery_rules: Vec<RuleBuilder>,
+}
+
+/// Builder for an experiment rule or delivery rule
+#[derive(Debug, Clone)]
+pub struct RuleBuilder {
+    key: String,
+    audiences: Vec<String>,
+    variations: Vec<(VariationBuilder, Range<u64>)>,
+}
+
+/// Builder for a variation with values for the variables of the flag
+#[derive(Debug, Clone)]
+pub struct VariationBuilder {
+    key: String,
+    feature_enabled: bool,
+    variables: Vec<(String, String)>,
+}
+
+impl DatafileBuilder {
+    /// Create an empty datafile with revision 1
+    pub fn new() -> DatafileBuilder {
+        DatafileBuilder {
+            account_id: String::from("10000"),
+            project_id: String::from("20000"),
+            revision: 1,
+            region: Region::default(),
+            anonymize_ip: true,
+            send_flag_decisions: false,
+            attributes: Vec::new(),
+            events: Vec::new(),
+            audiences: Vec::new(),
+            flags: Vec::new(),
+        }
+    }
+
+    /// Set the account ID
+    pub fn account_id(mut self, account_id: impl Into<String>) -> DatafileBuilder {
+        self.account_id = account_id.into();
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Set the project ID
+    pub fn project_id(mut self, project_id: impl Into<String>) -> DatafileBuilder {
+        self.project_id = project_id.into();
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Set the revision
+    pub fn revision(mut self, revision: u32) -> DatafileBuilder {
+        self.revision = revision;
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Set the data residency region
+    pub fn region(mut self, region: Region) -> DatafileBuilder {
+        self.region = region;
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Set whether the Event API should anonymize the IP address of users, which is enabled by default
+    pub fn anonymize_ip(mut self, anonymize_ip: bool) -> DatafileBuilder {
+        self.anonymize_ip = anonymize_ip;
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Set whether decision events are also sent for delivery rules and for the "off" variation
+    pub fn send_flag_decisions(mut self, send_flag_decisions: bool) -> DatafileBuilder {
+        self.send_flag_decisions = send_flag_decisions;
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Add an attribute, which can be used in audience conditions
+    pub fn attribute(mut self, key: impl Into<String>) -> DatafileBuilder {
+        self.attributes.push(key.into());
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Add an event, which can be tracked
+    pub fn event(mut self, key: impl Into<String>) -> DatafileBuilder {
+        self.events.push(key.into());
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Add an audience with conditions in the same JSON format as the datafile
+    ///
+    /// ```
+    /// use optimizely::testing::DatafileBuilder;
+    /// use serde_json::json;
+    ///
+    /// let builder = DatafileBuilder::new().attribute("country").audience(
+    ///     "Dutch users",
+    ///     json!(["and", {"type": "custom_attribute", "name": "country", "match": "exact", "value": "NL"}]),
+    /// );
+    /// ```
+    pub fn audience(mut self, name: impl Into<String>, conditions: Value) -> DatafileBuilder {
+        self.audiences.push((name.into(), conditions));
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Add a flag
+    pub fn flag(mut self, flag: FlagBuilder) -> DatafileBuilder {
+        self.flags.push(flag);
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Build the datafile as JSON document
+    pub fn build_json(&self) -> Value {
+        let mut ids = IdGenerator::default();
+
+        let attributes = self
+            .attributes
+            .iter()
+            .map(|key| json!({"id": ids.next(), "key": key}))
+            .collect::<Vec<_>>();
+
+        let audience_ids = self
+            .audiences
+            .iter()
+            .map(|(name, _)| (name.as_str(), ids.next()))
+            .collect::<HashMap<_, _>>();
+        let audiences = self
+            .audiences
+            .iter()
+            .map(
+                |(name, conditions)| json!({"id": audience_ids[name.as_str()], "name": name, "conditions": conditions}),
+            )
+            .collect::<Vec<_>>();
+
+        let mut experiments = Vec::new();
+        let mut rollouts = Vec::new();
+        let mut feature_flags = Vec::new();
+        for flag in &self.flags {
+            let flag_id = ids.next();
+            let rollout_id = ids.next();
+
+            // Variables are referenced by key within variations
+            let variable_ids = flag
+                .variables
+                .iter()
+                .map(|(key, _, _)| (key.as_str(), ids.next()))
+                .collect::<HashMap<_, _>>();
+            let variables = flag
+                .variables
+                .iter()
+                .map(|(key, variable_type, default_value)| {
+                    json!({"id": variable_ids[key.as_str()], "key": key, "type": variable_type, "defaultValue": default_value})
+                })
+                .collect::<Vec<_>>();
+
+            let mut build_rule = |rule: &RuleBuilder, layer_id: Option<&str>| {
+                rule.build_json(&mut ids, layer_id, &audience_ids, &variable_ids)
+            };
+
+            let flag_experiments = flag
+                .experiment_rules
+                .iter()
+                .map(|rule| build_rule(rule, None))
+                .collect::<Vec<_>>();
+
+            // Every rollout ends with the "Everyone Else" rule
+            let everyone_else = RuleBuilder::new(format!("default-rollout-{flag_id}")).variation("off", 0..10_000);
+            let rules = flag
+                .delivery_rules
+                .iter()
+                .chain([&everyone_else])
+                .map(|rule| build_rule(rule, Some(&rollout_id)))
+                .collect::<Vec<_>>();
+
+            feature_flags.push(json!({
+                "id": flag_id,
+                "key": flag.key,
+                "rolloutId": rollout_id,
+                "experimentIds": flag_experiments.iter().map(|experiment| experiment["id"].clone()).collect::<Vec<_>>(),
+                "variables": variables,
+            }));
+            rollouts.push(json!({"id": rollout_id, "experiments": rules}));
+            experiments.extend(flag_experiments);
+        }
+
+        // Every event is a metric of every experiment
+        let experiment_ids = experiments
+            .iter()
+            .map(|experiment| experiment["id"].clone())
+            .collect::<Vec<_>>();
+        let events = self
+            .events
+            .iter()
+            .map(|key| json!({"id": ids.next(), "key": key, "experimentIds": experiment_ids}))
+            .collect::<Vec<_>>();
+
+        json!({
+            "version": "4",
+            "accountId": self.account_id,
+            "projectId": self.project_id,
+            "environmentKey": "production",
+            "sdkKey": "testing",
+            "revision": self.revision.to_string(),
+            "region": self.region,
+            "botFiltering": false,
+            "anonymizeIP": self.anonymize_ip,
+            "sendFlagDecisions": self.send_flag_decisions,
+            "attributes": attributes,
+            "events": events,
+            "audiences": [],
+            "typedAudiences": audiences,
+            "experiments": experiments,
+            "rollouts": rollouts,
+            "featureFlags": feature_flags,
+            "groups": [],
+        })
+    }
+
+    /// Build and validate the datafile
+    pub fn build(&self) -> Result<Datafile, DatafileError> {
+        // Audiences are referenced by name, so a duplicate name would make a rule target the wrong audience
+        for (index, (name, _)) in self.audiences.iter().enumerate() {
+            if self.audiences[..index]
+                .iter()
+                .any(|(other, _)| other == name)
+            {
+                let report = Report::new(DatafileError::FailedValidation)
+                    .attach_printable(format!("Audience name '{name}' is used more than once"));
+                return Err(report);
+            }
+        }
+
+        let datafile = Datafile::from_string(self.build_json().to_string())?;
+        datafile.ensure_valid()?;
+        Ok(datafile)
+    }
+
+    /// Build the datafile and use it for a new client
+    pub fn build_client(&self) -> Result<UninitializedClient, ClientError> {
+        let datafile = self.build().map_err(ClientError::from_datafile_report)?;
+        Ok(Client::from_datafile(datafile))
+    }
+}
+
+impl Default for DatafileBuilder {
+    fn default() -> Self {
+        DatafileBuilder::new()
+    }
+}
+
+impl FlagBuilder {
+    /// Create a flag without any rules, for which every user gets the "off" variation
+    pub fn new(key: impl Into<String>) -> FlagBuilder {
+        FlagBuilder {
+            key: key.into(),
+            variables: Vec::new(),
+            experiment_rules: Vec::new(),
+            delivery_rules: Vec::new(),
+        }
+    }
+
+    /// Add a variable with type "string", "integer", "double", "boolean" or "json"
+    pub fn variable(
+        mut self, key: impl Into<String>, variable_type: impl Into<String>, default_value: impl Into<String>,
+    ) -> FlagBuilder {
+        self.variables
+            .push((key.into(), variable_type.into(), default_value.into()));
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Add an A/B test, which is evaluated before any delivery rule
+    pub fn experiment_rule(mut self, rule: RuleBuilder) -> FlagBuilder {
+        self.experiment_rules.push(rule);
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Add a targeted delivery, which is evaluated in the order in which it was added
+    pub fn delivery_rule(mut self, rule: RuleBuilder) -> FlagBuilder {
+        self.delivery_rules.push(rule);
+
+        // Return self, so can chain other functions
+        self
+    }
+}
+
+impl RuleBuilder {
+    /// Create a rule without variations and without audiences, so it targets everyone
+    pub fn new(key: impl Into<String>) -> RuleBuilder {
+        RuleBuilder {
+            key: key.into(),
+            audiences: Vec::new(),
+            variations: Vec::new(),
+        }
+    }
+
+    /// Target the audience with the given name
+    ///
+    /// A user needs to match any of the audiences of a rule.
+    pub fn audience(mut self, name: impl Into<String>) -> RuleBuilder {
+        self.audiences.push(name.into());
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Add a variation for the given range of buckets, where the buckets range from 0 to 10000
+    ///
+    /// Buckets that are not covered by any variation are not part of the rule.
+    pub fn variation(mut self, variation: impl Into<VariationBuilder>, traffic: Range<u64>) -> RuleBuilder {
+        self.variations.push((variation.into(), traffic));
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Build the rule as JSON, where A/B tests have their own layer and delivery rules share the layer of the rollout
+    fn build_json(
+        &self, ids: &mut IdGenerator, layer_id: Option<&str>, audience_ids: &HashMap<&str, String>,
+        variable_ids: &HashMap<&str, String>,
+    ) -> Value {
+        let id = ids.next();
+        let layer_id = layer_id.map(String::from).unwrap_or_else(|| ids.next());
+
+        let mut variations = Vec::new();
+        let mut traffic_allocation = Vec::new();
+        let mut end_of_previous_range = 0;
+        for (variation, traffic) in &self.variations {
+            let variation_id = ids.next();
+
+            // Traffic that is not allocated to any variation has an empty entity ID
+            if traffic.start > end_of_previous_range {
+                traffic_allocation.push(json!({"entityId": "", "endOfRange": traffic.start}));
+            }
+            traffic_allocation.push(json!({"entityId": variation_id, "endOfRange": traffic.end}));
+            end_of_previous_range = traffic.end;
+
+            let variables = variation
+                .variables
+                .iter()
+                .map(|(key, value)| {
+                    // Unknown variables are kept, so validation of the datafile can report them
+                    let id = variable_ids
+                        .get(key.as_str())
+                        .cloned()
+                        .unwrap_or_else(|| key.clone());
+                    json!({"id": id, "value": value})
+                })
+                .collect::<Vec<_>>();
+
+            variations.push(json!({
+                "id": variation_id,
+                "key": variation.key,
+                "featureEnabled": variation.feature_enabled,
+                "variables": variables,
+            }));
+        }
+
+        // Unknown audiences are kept, so validation of the datafile can report them
+        let audience_ids = self
+            .audiences
+            .iter()
+            .map(|name| {
+                audience_ids
+                    .get(name.as_str())
+                    .cloned()
+                    .unwrap_or_else(|| name.clone())
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "id": id,
+            "key": self.key,
+            "status": "Running",
+            "layerId": layer_id,
+            "variations": variations,
+            "trafficAllocation": traffic_allocation,
+            "audienceIds": audience_ids,
+            "forcedVariations": {},
+        })
+    }
+}
+
+impl VariationBuilder {
+    /// Create a variation, where only the variation with key "off" has the feature disabled
+    pub fn new(key: impl Into<String>) -> VariationBuilder {
+        let key = key.into();
+        VariationBuilder {
+            feature_enabled: key != "off",
+            key,
+            variables: Vec::new(),
+        }
+    }
+
+    /// Set whether the feature is enabled for this variation
+    pub fn feature_enabled(mut self, feature_enabled: bool) -> VariationBuilder {
+        self.feature_enabled = feature_enabled;
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Set the value of a variable of the flag for this variation
+    pub fn variable(mut self, key: impl Into<String>, value: impl Into<String>) -> VariationBuilder {
+        self.variables.push((key.into(), value.into()));
+
+        // Return self, so can chain other functions
+        self
+    }
+}
+
+impl From<&str> for VariationBuilder {
+    fn from(key: &str) -> Self {
+        VariationBuilder::new(key)
+    }
+}
+
+/// Sequential IDs, starting at 1000 so they are easy to recognize
+struct IdGenerator(u64);
+
+impl Default for IdGenerator {
+    fn default() -> Self {
+        IdGenerator(1000)
+    }
+}
+
+impl IdGenerator {
+    fn next(&mut self) -> String {
+        self.0 += 1;
+        self.0.to_string()
+    }
+}
@@ -0,0 +1,227 @@
+// External imports
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+// Imports from crate
+use crate::datafile::{Datafile, FeatureFlag};
+use crate::{Decision, RuleType};
+
+/// Overrides of flag decisions, which take precedence over the rules in the datafile
+///
+/// Overrides can be set for everyone or for a single user, where an override for a user takes precedence.
+/// All decisions of the client are recorded, so tests can assert which variations were shown.
+///
+/// This is a handle to shared state, so overrides can still be changed after the client is initialized.
+/// Decisions that are overridden do not send a decision event, since the user was not bucketed.
+///
+/// ```
+/// use optimizely::testing::{DatafileBuilder, FlagOverride, FlagOverrides};
+///
+/// let overrides = FlagOverrides::new();
+/// let client = DatafileBuilder::new()
+///     .build_client()?
+///     .with_flag_overrides(&overrides)
+///     .initialize();
+///
+/// // Show variation B of the buy button to everyone
+/// overrides.set("buy_button", FlagOverride::new("B").variable("color", "green"));
+///
+/// let decision = client.create_user_context("user-1").decide("buy_button");
+/// assert_eq!(decision.variation_key(), "B");
+/// assert_eq!(decision.variable("color"), Some("green"));
+/// assert_eq!(overrides.decisions().len(), 1);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FlagOverrides {
+    state: Arc<Mutex<OverrideState>>,
+}
+
+#[derive(Debug, Default)]
+struct OverrideState {
+    // Overrides by flag key and optional user ID
+    overrides: HashMap<(String, Option<String>), FlagOverride>,
+    decisions: Vec<RecordedDecision>,
+}
+
+/// Variation and variable values to use for an overridden flag
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlagOverride {
+    variation_key: String,
+    enabled: Option<bool>,
+    variables: Vec<(String, String)>,
+}
+
+/// Decision that was made by a client with flag overrides
+#[derive(Debug, Clone)]
+pub struct RecordedDecision {
+    user_id: String,
+    decision: Decision,
+    overridden: bool,
+}
+
+impl FlagOverrides {
+    /// Create overrides without any overridden flag
+    pub fn new() -> FlagOverrides {
+        FlagOverrides::default()
+    }
+
+    /// Override the flag for every user
+    pub fn set(&self, flag_key: impl Into<String>, flag_override: impl Into<FlagOverride>) {
+        self.lock()
+            .overrides
+            .insert((flag_key.into(), None), flag_override.into());
+    }
+
+    /// Override the flag for a single user
+    pub fn set_for_user(
+        &self, flag_key: impl Into<String>, user_id: impl Into<String>, flag_override: impl Into<FlagOverride>,
+    ) {
+        self.lock()
+            .overrides
+            .insert((flag_key.into(), Some(user_id.into())), flag_override.into());
+    }
+
+    /// Remove all overrides of the flag, both for every user and for single users
+    pub fn remove(&self, flag_key: &str) {
+        self.lock().overrides.retain(|(key, _), _| key != flag_key);
+    }
+
+    /// Remove all overrides
+    pub fn clear(&self) {
+        self.lock().overrides.clear();
+    }
+
+    /// Get all decisions that were made since the client was initialized, in order
+    pub fn decisions(&self) -> Vec<RecordedDecision> {
+        self.lock().decisions.clone()
+    }
+
+    /// Forget all recorded decisions
+    pub fn clear_decisions(&self) {
+        self.lock().decisions.clear();
+    }
+
+    /// Get the overridden decision for a user, if the flag is overridden
+    pub(crate) fn decide(&self, datafile: &Datafile, flag_key: &str, user_id: &str) -> Option<Decision> {
+        let flag_override = {
+            let state = self.lock();
+            state
+                .overrides
+                .get(&(flag_key.to_owned(), Some(user_id.to_owned())))
+                .or_else(|| state.overrides.get(&(flag_key.to_owned(), None)))
+                .cloned()?
+        };
+
+        Some(flag_override.decision(datafile, flag_key))
+    }
+
+    /// Store a decision, so it can be retrieved by the test
+    pub(crate) fn record(&self, user_id: &str, decision: Decision, overridden: bool) {
+        self.lock().decisions.push(RecordedDecision {
+            user_id: user_id.into(),
+            decision,
+            overridden,
+        });
+    }
+
+    fn lock(&self) -> MutexGuard<'_, OverrideState> {
+        // A panic in another test thread should not poison the overrides of this test
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl FlagOverride {
+    /// Override the flag with the given variation
+    ///
+    /// If the flag has a rule with a variation with this key, its ID, enabled state and variable values are used.
+    /// Otherwise, the feature is enabled unless the variation key is "off", and variables have their default value.
+    pub fn new(variation_key: impl Into<String>) -> FlagOverride {
+        FlagOverride {
+            variation_key: variation_key.into(),
+            enabled: None,
+            variables: Vec::new(),
+        }
+    }
+
+    /// Set whether the feature is enabled, regardless of the variation in the datafile
+    pub fn enabled(mut self, enabled: bool) -> FlagOverride {
+        self.enabled = Some(enabled);
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Set the value of a variable, regardless of the variation in the datafile
+    pub fn variable(mut self, key: impl Into<String>, value: impl Into<String>) -> FlagOverride {
+        self.variables.push((key.into(), value.into()));
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    fn decision(&self, datafile: &Datafile, flag_key: &str) -> Decision {
+        let flag = datafile.flag(flag_key);
+
+        let mut decision = flag
+            .and_then(|flag| self.decision_from_rules(datafile, flag))
+            .unwrap_or_else(|| {
+                let enabled = self.variation_key != "off";
+                Decision::forced(flag, flag_key, &self.variation_key, enabled)
+            });
+
+        if let Some(enabled) = self.enabled {
+            decision.set_enabled(enabled);
+        }
+        for (key, value) in &self.variables {
+            decision.set_variable(key, value);
+        }
+
+        decision
+    }
+
+    /// Find the variation in the experiment rules and delivery rules of the flag
+    fn decision_from_rules(&self, datafile: &Datafile, flag: &FeatureFlag) -> Option<Decision> {
+        let experiment_rules = flag
+            .experiments_ids()
+            .iter()
+            .filter_map(|experiment_id| datafile.experiment(experiment_id));
+        let delivery_rules = datafile
+            .rollout(flag.rollout_id())
+            .into_iter()
+            .flat_map(|rollout| rollout.experiments());
+
+        experiment_rules
+            .map(|experiment| (experiment, RuleType::FeatureTest))
+            .chain(delivery_rules.map(|experiment| (experiment, RuleType::Rollout)))
+            .find_map(|(experiment, rule_type)| {
+                experiment
+                    .variations()
+                    .find(|variation| variation.key() == self.variation_key)
+                    .map(|variation| Decision::from(flag, experiment, variation, rule_type))
+            })
+    }
+}
+
+impl From<&str> for FlagOverride {
+    fn from(variation_key: &str) -> Self {
+        FlagOverride::new(variation_key)
+    }
+}
+
+impl RecordedDecision {
+    /// Getter for `user_id` field
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    /// Getter for `decision` field
+    pub fn decision(&self) -> &Decision {
+        &self.decision
+    }
+
+    /// Whether the decision was taken from an override instead of the datafile
+    pub fn overridden(&self) -> bool {
+        self.overridden
+    }
+}
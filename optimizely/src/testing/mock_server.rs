@@ -0,0 +1,337 @@
+// External imports
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// Relative imports of sub modules
+use http::{HttpRequest, HttpResponse};
+use schema::validate_payload;
+
+mod http;
+mod schema;
+
+/// Time after which a connection that does not send a complete request is closed
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// In-process HTTP server that acts as both the CDN and the Event API, so tests do not need internet access
+///
+/// Datafiles are served at `/datafiles/{sdk_key}.json` with an ETag, and requests with a matching
+/// `If-None-Match` header receive 304 Not Modified. Event API requests to `/v1/events` are captured and
+/// validated against the schema of the Event API. Invalid payloads receive 400 Bad Request.
+///
+/// The server stops when it is dropped.
+///
+/// ```
+/// use optimizely::datafile::Datafile;
+/// use optimizely::testing::MockServer;
+/// use optimizely::Client;
+/// use std::time::Duration;
+///
+/// let server = MockServer::start()?;
+/// server.set_datafile("sdk-key", std::fs::read_to_string("../datafiles/sandbox.json")?);
+///
+/// let datafile = Datafile::from_cdn(&server.url(), "sdk-key")?;
+/// let client = Client::from_datafile(datafile)
+///     .with_event_api_url(server.url())
+///     .initialize();
+///
+/// client.create_user_context("user-1").track_event("purchase");
+/// assert!(server.wait_for_event_requests(1, Duration::from_secs(5)));
+/// assert!(server.event_requests()[0].is_valid());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct MockServer {
+    address: SocketAddr,
+    shared: Arc<Shared>,
+    stopped: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+struct Shared {
+    state: Mutex<ServerState>,
+    event_received: Condvar,
+}
+
+#[derive(Default)]
+struct ServerState {
+    datafiles: HashMap<String, ServedDatafile>,
+    datafile_status: Option<u16>,
+    datafile_requests: Vec<DatafileRequest>,
+    event_status: Option<u16>,
+    event_failures: VecDeque<u16>,
+    event_requests: Vec<EventRequest>,
+    etag_counter: u64,
+}
+
+struct ServedDatafile {
+    content: String,
+    etag: String,
+}
+
+/// Request for a datafile that was received by a [MockServer]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatafileRequest {
+    sdk_key: String,
+    status: u16,
+}
+
+/// Request to the Event API that was received by a [MockServer]
+#[derive(Debug, Clone)]
+pub struct EventRequest {
+    body: String,
+    payload: Option<Value>,
+    errors: Vec<String>,
+    status: u16,
+}
+
+impl MockServer {
+    /// Start a server on a random port of the loopback interface
+    pub fn start() -> io::Result<MockServer> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            state: Mutex::new(ServerState::default()),
+            event_received: Condvar::new(),
+        });
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let shared = shared.clone();
+            let stopped = stopped.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    // Handle every connection in a separate thread, so a slow client cannot block other clients
+                    if let Ok(stream) = stream {
+                        let shared = shared.clone();
+                        thread::spawn(move || shared.handle(stream));
+                    }
+                }
+            })
+        };
+
+        Ok(MockServer {
+            address,
+            shared,
+            stopped,
+            thread: Some(thread),
+        })
+    }
+
+    /// Base URL of the server, to use as CDN URL and Event API URL
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Serve the given datafile for the SDK key
+    ///
+    /// Every call results in a new ETag, so clients that poll the server receive the new content.
+    /// Use a datafile with a higher revision to have a running client switch to it.
+    pub fn set_datafile(&self, sdk_key: impl Into<String>, content: impl Into<String>) {
+        let mut state = self.shared.lock();
+        state.etag_counter += 1;
+        let etag = format!("\"{}\"", state.etag_counter);
+        let datafile = ServedDatafile {
+            content: content.into(),
+            etag,
+        };
+        state.datafiles.insert(sdk_key.into(), datafile);
+    }
+
+    /// Respond to all datafile requests with the given status code, or serve datafiles again with `None`
+    pub fn set_datafile_status(&self, status: Option<u16>) {
+        self.shared.lock().datafile_status = status;
+    }
+
+    /// Respond to all Event API requests with the given status code, or validate payloads again with `None`
+    ///
+    /// Requests are still captured and validated, regardless of the status code.
+    pub fn set_event_status(&self, status: Option<u16>) {
+        self.shared.lock().event_status = status;
+    }
+
+    /// Respond to the next Event API requests with the given status code, before anything else
+    ///
+    /// This can be used to simulate transient failures, for example `fail_next_event_requests(2, 503)`.
+    pub fn fail_next_event_requests(&self, count: usize, status: u16) {
+        let mut state = self.shared.lock();
+        state
+            .event_failures
+            .extend(std::iter::repeat_n(status, count));
+    }
+
+    /// Get all datafile requests in the order in which they were received
+    pub fn datafile_requests(&self) -> Vec<DatafileRequest> {
+        self.shared.lock().datafile_requests.clone()
+    }
+
+    /// Get all Event API requests in the order in which they were received
+    pub fn event_requests(&self) -> Vec<EventRequest> {
+        self.shared.lock().event_requests.clone()
+    }
+
+    /// Block until the server received the given number of Event API requests or the timeout elapsed
+    ///
+    /// Returns whether the server received enough requests.
+    pub fn wait_for_event_requests(&self, count: usize, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.lock();
+        while state.event_requests.len() < count {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return false;
+            }
+            state = match self.shared.event_received.wait_timeout(state, remaining) {
+                Ok((state, _)) => state,
+                Err(error) => error.into_inner().0,
+            };
+        }
+        true
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        // Connect to the listener to wake up the thread, which then sees that the server is stopped
+        self.stopped.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.address);
+
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                log::error!("Failed to stop mock server");
+            }
+        }
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, ServerState> {
+        // A panic in a test thread should not stop the server from responding
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn handle(&self, stream: TcpStream) {
+        let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+
+        let response = match HttpRequest::read(&stream) {
+            Ok(request) => self.respond(&request),
+            Err(_) => HttpResponse::new(400),
+        };
+
+        if let Err(error) = response.write(&stream) {
+            log::debug!("Failed to write response of mock server: {error}");
+        }
+    }
+
+    fn respond(&self, request: &HttpRequest) -> HttpResponse {
+        let datafile_key = request
+            .path
+            .strip_prefix("/datafiles/")
+            .and_then(|file_name| file_name.strip_suffix(".json"));
+
+        match (request.method.as_str(), request.path.as_str(), datafile_key) {
+            ("GET", _, Some(sdk_key)) => self.respond_datafile(request, sdk_key),
+            ("POST", "/v1/events", _) => self.respond_event(request),
+            (_, "/v1/events", _) => HttpResponse::new(405),
+            _ => HttpResponse::new(404),
+        }
+    }
+
+    fn respond_datafile(&self, request: &HttpRequest, sdk_key: &str) -> HttpResponse {
+        let mut state = self.lock();
+
+        let response = match (state.datafile_status, state.datafiles.get(sdk_key)) {
+            (Some(status), _) => HttpResponse::new(status),
+            (None, None) => HttpResponse::new(403),
+            (None, Some(datafile)) if request.header("If-None-Match") == Some(&datafile.etag) => {
+                HttpResponse::new(304).with_header("ETag", &datafile.etag)
+            }
+            (None, Some(datafile)) => HttpResponse::new(200)
+                .with_header("ETag", &datafile.etag)
+                .with_header("Content-Type", "application/json")
+                .with_body(&datafile.content),
+        };
+
+        state.datafile_requests.push(DatafileRequest {
+            sdk_key: sdk_key.to_owned(),
+            status: response.status,
+        });
+
+        response
+    }
+
+    fn respond_event(&self, request: &HttpRequest) -> HttpResponse {
+        let (payload, errors) = match serde_json::from_str::<Value>(&request.body) {
+            Ok(payload) => {
+                let errors = validate_payload(&payload);
+                (Some(payload), errors)
+            }
+            Err(error) => (None, vec![format!("Invalid JSON: {error}")]),
+        };
+
+        let mut state = self.lock();
+
+        let response = match state.event_failures.pop_front().or(state.event_status) {
+            Some(status) => HttpResponse::new(status),
+            None if errors.is_empty() => HttpResponse::new(204),
+            None => HttpResponse::new(400).with_body(errors.join("\n")),
+        };
+
+        state.event_requests.push(EventRequest {
+            body: request.body.clone(),
+            payload,
+            errors,
+            status: response.status,
+        });
+        self.event_received.notify_all();
+
+        response
+    }
+}
+
+impl DatafileRequest {
+    /// Getter for `sdk_key` field
+    pub fn sdk_key(&self) -> &str {
+        &self.sdk_key
+    }
+
+    /// Status code of the response
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+}
+
+impl EventRequest {
+    /// Getter for `body` field
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Body as JSON document, if it is valid JSON
+    pub fn payload(&self) -> Option<&Value> {
+        self.payload.as_ref()
+    }
+
+    /// Description of every problem with the payload, including its JSON path
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    /// Whether the payload matches the schema of the Event API
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Status code of the response
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+}
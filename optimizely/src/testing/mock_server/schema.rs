@@ -0,0 +1,116 @@
+// External imports
+use serde_json::Value;
+
+/// Check a payload against the schema of the Event API, and return a description of every problem
+///
+/// Unknown fields are allowed, since the Event API ignores them.
+pub(super) fn validate_payload(payload: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    expect_string(&mut errors, payload, "", "account_id");
+    expect_string(&mut errors, payload, "", "client_name");
+    expect_string(&mut errors, payload, "", "client_version");
+    expect_bool(&mut errors, payload, "", "enrich_decisions");
+    expect_bool(&mut errors, payload, "", "anonymize_ip");
+    expect_optional_string(&mut errors, payload, "", "project_id");
+    expect_optional_string(&mut errors, payload, "", "revision");
+    expect_optional_string(&mut errors, payload, "", "client_ip");
+
+    for (path, visitor) in expect_array(&mut errors, payload, "", "visitors", true) {
+        expect_string(&mut errors, visitor, &path, "visitor_id");
+        expect_optional_string(&mut errors, visitor, &path, "session_id");
+
+        for (path, attribute) in expect_array(&mut errors, visitor, &path, "attributes", false) {
+            expect_string(&mut errors, attribute, &path, "entity_id");
+            expect_string(&mut errors, attribute, &path, "key");
+            expect_string(&mut errors, attribute, &path, "type");
+            match attribute.get("value") {
+                Some(Value::String(_) | Value::Number(_) | Value::Bool(_)) => {}
+                _ => errors.push(format!("{path}.value: expected a string, number or boolean")),
+            }
+        }
+
+        for (path, snapshot) in expect_array(&mut errors, visitor, &path, "snapshots", true) {
+            if snapshot.get("decisions").is_some() {
+                for (path, decision) in expect_array(&mut errors, snapshot, &path, "decisions", false) {
+                    expect_string(&mut errors, decision, &path, "campaign_id");
+                    expect_string(&mut errors, decision, &path, "experiment_id");
+                    expect_string(&mut errors, decision, &path, "variation_id");
+                    if let Some(metadata) = decision.get("metadata") {
+                        let path = field_path(&path, "metadata");
+                        for field in ["flag_key", "rule_key", "rule_type", "variation_key"] {
+                            expect_string(&mut errors, metadata, &path, field);
+                        }
+                        expect_bool(&mut errors, metadata, &path, "enabled");
+                    }
+                }
+            }
+
+            for (path, event) in expect_array(&mut errors, snapshot, &path, "events", true) {
+                expect_string(&mut errors, event, &path, "entity_id");
+                expect_string(&mut errors, event, &path, "key");
+                expect_string(&mut errors, event, &path, "uuid");
+                if !event.get("timestamp").is_some_and(Value::is_u64) {
+                    errors.push(format!("{path}.timestamp: expected milliseconds since the epoch"));
+                }
+                if event.get("tags").is_some_and(|tags| !tags.is_object()) {
+                    errors.push(format!("{path}.tags: expected an object"));
+                }
+                for field in ["revenue", "value"] {
+                    if event.get(field).is_some_and(|value| !value.is_number()) {
+                        errors.push(format!("{path}.{field}: expected a number"));
+                    }
+                }
+            }
+        }
+    }
+
+    errors
+}
+
+fn field_path(path: &str, field: &str) -> String {
+    match path {
+        "" => field.to_owned(),
+        _ => format!("{path}.{field}"),
+    }
+}
+
+fn expect_string(errors: &mut Vec<String>, object: &Value, path: &str, field: &str) {
+    if !object.get(field).is_some_and(Value::is_string) {
+        errors.push(format!("{}: expected a string", field_path(path, field)));
+    }
+}
+
+fn expect_optional_string(errors: &mut Vec<String>, object: &Value, path: &str, field: &str) {
+    if object.get(field).is_some_and(|value| !value.is_string()) {
+        errors.push(format!("{}: expected a string", field_path(path, field)));
+    }
+}
+
+fn expect_bool(errors: &mut Vec<String>, object: &Value, path: &str, field: &str) {
+    if !object.get(field).is_some_and(Value::is_boolean) {
+        errors.push(format!("{}: expected a boolean", field_path(path, field)));
+    }
+}
+
+/// Return the items of the array together with their path
+fn expect_array<'a>(
+    errors: &mut Vec<String>, object: &'a Value, path: &str, field: &str, non_empty: bool,
+) -> Vec<(String, &'a Value)> {
+    let path = field_path(path, field);
+    match object.get(field) {
+        Some(Value::Array(items)) if non_empty && items.is_empty() => {
+            errors.push(format!("{path}: expected at least one item"));
+            Vec::new()
+        }
+        Some(Value::Array(items)) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (format!("{path}[{index}]"), item))
+            .collect(),
+        _ => {
+            errors.push(format!("{path}: expected an array"));
+            Vec::new()
+        }
+    }
+}
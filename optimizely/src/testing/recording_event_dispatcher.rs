@@ -0,0 +1,167 @@
+// External imports
+use error_stack::Result;
+use serde_json::Value;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+// Imports from crate
+use crate::error::EventApiError;
+use crate::event_api::payload::Payload;
+use crate::event_api::{EventDispatcher, LogEvent, LogEventKind};
+use crate::{Conversion, Decision, UserAttribute};
+
+/// Event dispatcher that keeps all events in memory instead of sending them to the Event API
+///
+/// This is a handle to shared state, so keep a clone to inspect the events after passing it to the client.
+///
+/// ```
+/// use optimizely::testing::RecordingEventDispatcher;
+/// use optimizely::Client;
+///
+/// let recorder = RecordingEventDispatcher::new();
+/// let client = Client::from_local_datafile("../datafiles/sandbox.json")?
+///     .with_event_dispatcher(|_datafile| recorder.clone())
+///     .initialize();
+///
+/// client.create_user_context("user-1").track_event("purchase");
+///
+/// let events = recorder.events();
+/// assert_eq!(events[0].visitor_id(), "user-1");
+/// assert_eq!(events[0].payload()["visitors"][0]["snapshots"][0]["events"][0]["key"], "purchase");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RecordingEventDispatcher {
+    events: Arc<Mutex<Vec<RecordedEvent>>>,
+}
+
+/// Event that was received by a [RecordingEventDispatcher]
+#[derive(Debug, Clone)]
+pub struct RecordedEvent {
+    event: LogEvent,
+    payload: Value,
+}
+
+impl RecordingEventDispatcher {
+    /// Create a dispatcher without any events
+    pub fn new() -> RecordingEventDispatcher {
+        RecordingEventDispatcher::default()
+    }
+
+    /// Get all events in the order in which they were dispatched
+    pub fn events(&self) -> Vec<RecordedEvent> {
+        self.lock().clone()
+    }
+
+    /// Get all decision events
+    pub fn decisions(&self) -> Vec<Decision> {
+        self.lock()
+            .iter()
+            .filter_map(|recorded| recorded.event.decision().cloned())
+            .collect()
+    }
+
+    /// Get all conversion events
+    pub fn conversions(&self) -> Vec<Conversion> {
+        self.lock()
+            .iter()
+            .filter_map(|recorded| recorded.event.conversion().cloned())
+            .collect()
+    }
+
+    /// Get the payloads that a batched event dispatcher would send, each containing all events that share a request
+    ///
+    /// A request has a single account, project, revision, IP address and endpoint, so events are grouped by these.
+    /// The payloads are in the order of their first event, and the list is empty if no events were dispatched.
+    pub fn batched_payloads(&self) -> Vec<Value> {
+        let events = self.lock();
+        let mut batches: Vec<(&RecordedEvent, Value)> = Vec::new();
+
+        for recorded in events.iter() {
+            match batches
+                .iter_mut()
+                .find(|(first, _)| first.shares_request(recorded))
+            {
+                // Events of the same request have the same envelope, so only the visitors need to be combined
+                Some((_, payload)) => {
+                    if let (Some(Value::Array(visitors)), Some(Value::Array(other_visitors))) =
+                        (payload.get_mut("visitors"), recorded.payload.get("visitors"))
+                    {
+                        visitors.extend(other_visitors.iter().cloned());
+                    }
+                }
+                None => batches.push((recorded, recorded.payload.clone())),
+            }
+        }
+
+        batches.into_iter().map(|(_, payload)| payload).collect()
+    }
+
+    /// Forget all recorded events
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn record(&self, event: LogEvent) {
+        match RecordedEvent::new(event) {
+            Ok(event) => self.lock().push(event),
+            Err(report) => {
+                log::error!("Failed to record event");
+                log::error!("\n{report:?}");
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<RecordedEvent>> {
+        // A panic in another test thread should not poison the events of this test
+        self.events.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl EventDispatcher for RecordingEventDispatcher {
+    fn dispatch_event(&self, event: LogEvent) {
+        self.record(event);
+    }
+}
+
+impl RecordedEvent {
+    fn new(event: LogEvent) -> Result<RecordedEvent, EventApiError> {
+        // Render the payload right away, since the decision event gets a new UUID and timestamp every time
+        let mut payload = Payload::new(event.context().clone(), event.client_ip().map(String::from));
+        payload.add_event(event.clone())?;
+        let payload = payload.to_value()?;
+
+        Ok(RecordedEvent { event, payload })
+    }
+
+    /// Whether both events would be sent in the same request by a batched event dispatcher
+    fn shares_request(&self, other: &RecordedEvent) -> bool {
+        self.event.context() == other.event.context()
+            && self.event.client_ip() == other.event.client_ip()
+            && self.event.endpoint_url() == other.event.endpoint_url()
+    }
+
+    /// Getter for `event` field
+    pub fn event(&self) -> &LogEvent {
+        &self.event
+    }
+
+    /// Get the ID of the visitor for whom the event was dispatched
+    pub fn visitor_id(&self) -> &str {
+        self.event.visitor_id()
+    }
+
+    /// Get the attributes of the visitor at the time of the event
+    pub fn attributes(&self) -> &[UserAttribute] {
+        self.event.attributes()
+    }
+
+    /// Get the decision or conversion of the event
+    pub fn kind(&self) -> &LogEventKind {
+        self.event.kind()
+    }
+
+    /// Get the JSON payload that the simple event dispatcher would send to the Event API for this event
+    pub fn payload(&self) -> &Value {
+        &self.payload
+    }
+}
@@ -1,16 +1,18 @@
 // Re-export all types
 pub use attribute_value::AttributeValue;
-#[cfg(feature = "online")]
 pub use conversion::Conversion;
 pub use decide_options::DecideOptions;
 pub use decision::Decision;
+pub use event_tags::{EventTags, TagValue};
+pub use rule_type::RuleType;
 pub use user_attribute::UserAttribute;
 pub(crate) use user_attribute_map::UserAttributeMap;
 
 mod attribute_value;
-#[cfg(feature = "online")]
 mod conversion;
 mod decide_options;
 mod decision;
+mod event_tags;
+mod rule_type;
 mod user_attribute;
 mod user_attribute_map;
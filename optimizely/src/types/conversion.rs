@@ -5,6 +5,9 @@ use serde::ser::{Serialize, SerializeStruct, Serializer};
 use std::{collections::HashMap, time::SystemTime};
 use uuid::Uuid;
 
+// Imports from crate
+use crate::EventTags;
+
 /// A conversion event
 #[derive(Debug, Clone)]
 pub struct Conversion {
@@ -13,13 +16,16 @@ pub struct Conversion {
     event_key: String,
     event_id: String,
     properties: HashMap<String, String>,
-    tags: HashMap<String, String>,
+    tags: EventTags,
 }
 
 impl Conversion {
     pub(crate) fn new<T: Into<String>>(
-        event_key: T, event_id: T, properties: HashMap<String, String>, tags: HashMap<String, String>,
+        event_key: T, event_id: T, properties: HashMap<String, String>, tags: EventTags,
     ) -> Conversion {
+        // Reserved tags are only sent as top-level fields if they are valid
+        tags.validate();
+
         // Generate a UUID
         // This will avoid duplication when resending the same conversion to the Event API
         let uuid = Uuid::new_v4().as_hyphenated().to_string();
@@ -47,6 +53,11 @@ impl Conversion {
         &self.event_key
     }
 
+    /// Get timestamp in milliseconds since the epoch
+    pub fn timestamp(&self) -> u128 {
+        self.timestamp
+    }
+
     /// Get id
     pub fn event_id(&self) -> &str {
         &self.event_id
@@ -58,7 +69,7 @@ impl Conversion {
     }
 
     /// Get tags
-    pub fn tags(&self) -> &HashMap<String, String> {
+    pub fn tags(&self) -> &EventTags {
         &self.tags
     }
 }
@@ -68,13 +79,23 @@ impl Serialize for Conversion {
     where
         S: Serializer,
     {
-        let mut st = serializer.serialize_struct("Conversion", 6)?;
+        let mut st = serializer.serialize_struct("Conversion", 8)?;
         st.serialize_field("uuid", &self.uuid)?;
         st.serialize_field("timestamp", &self.timestamp)?;
         st.serialize_field("entity_id", &self.event_id)?;
         st.serialize_field("key", &self.event_key)?;
         st.serialize_field("properties", &self.properties)?;
         st.serialize_field("tags", &self.tags)?;
+
+        // The Event API expects the reserved tags as numbers next to the tags
+        match self.tags.revenue() {
+            Some(revenue) => st.serialize_field("revenue", &revenue)?,
+            None => st.skip_field("revenue")?,
+        }
+        match self.tags.value() {
+            Some(value) => st.serialize_field("value", &value)?,
+            None => st.skip_field("value")?,
+        }
         st.end()
     }
 }
@@ -1,9 +1,13 @@
 //! Result of a feature flag
 
 // External imports
-use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+use std::collections::HashMap;
 
+// Imports from crate
 use crate::datafile::{Experiment, FeatureFlag, Variation};
+use crate::RuleType;
 
 /// Decision for a specific user and feature flag
 ///
@@ -11,37 +15,82 @@ use crate::datafile::{Experiment, FeatureFlag, Variation};
 #[derive(Debug, Clone)]
 pub struct Decision {
     flag_key: String,
+    rule_key: String,
+    rule_type: RuleType,
     campaign_id: String,
     experiment_id: String,
     variation_id: String,
     variation_key: String,
     enabled: bool,
+    variables: HashMap<String, String>,
 }
 
 impl Decision {
-    pub(crate) fn from(flag: &FeatureFlag, experiment: &Experiment, variation: &Variation) -> Decision {
+    pub(crate) fn from(
+        flag: &FeatureFlag, experiment: &Experiment, variation: &Variation, rule_type: RuleType,
+    ) -> Decision {
         // Unfortunately, we will have to clone all Strings in order to release the read/write lock on the Datafile
         Decision {
             flag_key: flag.key().into(),
+            rule_key: experiment.key().into(),
+            rule_type,
             campaign_id: experiment.campaign_id().into(),
             experiment_id: experiment.id().into(),
             variation_id: variation.id().into(),
             variation_key: variation.key().into(),
             enabled: variation.is_feature_enabled(),
+            variables: variable_values(flag, Some(variation)),
         }
     }
 
     pub(crate) fn off(flag_key: &str) -> Decision {
         Decision {
             flag_key: flag_key.into(),
+            // Like the other Optimizely SDKs, the fall-through is reported as a rollout without a rule
+            rule_key: String::default(),
+            rule_type: RuleType::Rollout,
             campaign_id: String::default(),
             experiment_id: String::default(),
             variation_id: String::default(),
             variation_key: String::from("off"),
             enabled: false,
+            variables: HashMap::default(),
         }
     }
 
+    /// Decision for the "off" variation, where all variables have their default value
+    pub(crate) fn off_for_flag(flag: &FeatureFlag) -> Decision {
+        Decision {
+            variables: variable_values(flag, None),
+            ..Decision::off(flag.key())
+        }
+    }
+
+    /// Decision for a variation that is not part of any rule, which is used for overrides
+    #[cfg(feature = "testing")]
+    pub(crate) fn forced(flag: Option<&FeatureFlag>, flag_key: &str, variation_key: &str, enabled: bool) -> Decision {
+        Decision {
+            variation_key: variation_key.into(),
+            enabled,
+            variables: flag
+                .map(|flag| variable_values(flag, None))
+                .unwrap_or_default(),
+            ..Decision::off(flag_key)
+        }
+    }
+
+    /// Replace the enabled state of the decision
+    #[cfg(feature = "testing")]
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Replace the value of a variable within the decision
+    #[cfg(feature = "testing")]
+    pub(crate) fn set_variable(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.variables.insert(key.into(), value.into());
+    }
+
     /// Get the flag key for which this decision was made
     pub fn flag_key(&self) -> &str {
         &self.flag_key
@@ -52,6 +101,16 @@ impl Decision {
         self.enabled
     }
 
+    /// Get the key of the experiment rule or delivery rule, which is empty if no rule applied
+    pub fn rule_key(&self) -> &str {
+        &self.rule_key
+    }
+
+    /// Get the type of the rule
+    pub fn rule_type(&self) -> RuleType {
+        self.rule_type
+    }
+
     /// Get the campaign ID
     pub fn campaign_id(&self) -> &str {
         &self.campaign_id
@@ -71,6 +130,32 @@ impl Decision {
     pub fn variation_key(&self) -> &str {
         &self.variation_key
     }
+
+    /// Get the values of all variables of the flag by variable key
+    ///
+    /// Values are formatted as in the datafile, so for example an integer variable has the value `"42"`.
+    pub fn variables(&self) -> &HashMap<String, String> {
+        &self.variables
+    }
+
+    /// Get the value of a single variable of the flag
+    pub fn variable(&self, variable_key: &str) -> Option<&str> {
+        self.variables.get(variable_key).map(String::as_str)
+    }
+}
+
+/// Values of all variables of a flag, where variables only take the value of the variation if the feature is enabled
+fn variable_values(flag: &FeatureFlag, variation: Option<&Variation>) -> HashMap<String, String> {
+    let variation = variation.filter(|variation| variation.is_feature_enabled());
+    flag.variables()
+        .iter()
+        .map(|variable| {
+            let value = variation
+                .and_then(|variation| variation.variable_value(variable.id()))
+                .unwrap_or(variable.default_value());
+            (variable.key().to_owned(), value.to_owned())
+        })
+        .collect()
 }
 
 impl Serialize for Decision {
@@ -78,11 +163,30 @@ impl Serialize for Decision {
     where
         S: Serializer,
     {
-        let mut st = serializer.serialize_struct("Decision", 4)?;
+        let metadata = DecisionMetadata {
+            flag_key: self.flag_key(),
+            rule_key: self.rule_key(),
+            rule_type: self.rule_type(),
+            variation_key: self.variation_key(),
+            enabled: self.enabled(),
+        };
+
+        let mut st = serializer.serialize_struct("Decision", 5)?;
         st.serialize_field("campaign_id", self.campaign_id())?;
         st.serialize_field("experiment_id", self.experiment_id())?;
         st.serialize_field("variation_id", self.variation_id())?;
         st.serialize_field("is_campaign_holdback", &false)?;
+        st.serialize_field("metadata", &metadata)?;
         st.end()
     }
 }
+
+/// Metadata of a decision event, which Optimizely uses in the results and the flags overview
+#[derive(Serialize)]
+struct DecisionMetadata<'a> {
+    flag_key: &'a str,
+    rule_key: &'a str,
+    rule_type: RuleType,
+    variation_key: &'a str,
+    enabled: bool,
+}
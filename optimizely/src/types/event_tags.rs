@@ -0,0 +1,177 @@
+//! Tags of a conversion event
+
+// External imports
+use serde::Serialize;
+use std::collections::HashMap;
+
+// Reserved tag keys, which the Event API also expects as top-level fields of the event
+const REVENUE_TAG_KEY: &str = "revenue";
+const VALUE_TAG_KEY: &str = "value";
+
+/// The value of an event tag
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TagValue {
+    /// An integer number
+    Integer(i64),
+    /// A decimal number
+    Decimal(f64),
+    /// A true or false value
+    Boolean(bool),
+    /// A string of text
+    String(String),
+}
+
+/// Tags of a conversion event, such as the revenue of a purchase
+///
+/// The `revenue` tag is an integer in cents, and the `value` tag is a decimal number for numeric metrics.
+/// Both are also sent as top-level fields, so Optimizely can use them in metrics.
+///
+/// ```
+/// use optimizely::EventTags;
+///
+/// let tags = EventTags::new()
+///     .with_revenue(4200)
+///     .with_tag("category", "shoes")
+///     .with_tag("gift_wrap", true);
+/// assert_eq!(tags.revenue(), Some(4200));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct EventTags(HashMap<String, TagValue>);
+
+impl EventTags {
+    /// Create a set of tags without any tags
+    pub fn new() -> EventTags {
+        EventTags::default()
+    }
+
+    /// Add a tag, replacing any tag with the same key
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<TagValue>) -> EventTags {
+        self.0.insert(key.into(), value.into());
+
+        // Return self, so can chain other functions
+        self
+    }
+
+    /// Add the revenue in cents
+    pub fn with_revenue(self, revenue: i64) -> EventTags {
+        self.with_tag(REVENUE_TAG_KEY, revenue)
+    }
+
+    /// Add the value for numeric metrics
+    pub fn with_value(self, value: f64) -> EventTags {
+        self.with_tag(VALUE_TAG_KEY, value)
+    }
+
+    /// Get the tag with the given key
+    pub fn get(&self, key: &str) -> Option<&TagValue> {
+        self.0.get(key)
+    }
+
+    /// Iterate over all tags
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &TagValue)> {
+        self.0.iter()
+    }
+
+    /// Get the number of tags
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no tags
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the revenue tag, if it is an integer or a string that contains an integer
+    pub fn revenue(&self) -> Option<i64> {
+        match self.get(REVENUE_TAG_KEY)? {
+            TagValue::Integer(revenue) => Some(*revenue),
+            TagValue::String(text) => text.trim().parse().ok(),
+            TagValue::Decimal(_) | TagValue::Boolean(_) => None,
+        }
+    }
+
+    /// Get the value tag, if it is a finite number or a string that contains one
+    pub fn value(&self) -> Option<f64> {
+        let value = match self.get(VALUE_TAG_KEY)? {
+            TagValue::Integer(value) => *value as f64,
+            TagValue::Decimal(value) => *value,
+            TagValue::String(text) => text.trim().parse().ok()?,
+            TagValue::Boolean(_) => return None,
+        };
+
+        value.is_finite().then_some(value)
+    }
+
+    /// Check the reserved tags, and log a warning for every tag that is not sent as a top-level field
+    pub(crate) fn validate(&self) {
+        if self.get(REVENUE_TAG_KEY).is_some() && self.revenue().is_none() {
+            log::warn!("Tag '{REVENUE_TAG_KEY}' should be an integer number of cents, it is only sent as a tag");
+        }
+        if self.get(VALUE_TAG_KEY).is_some() && self.value().is_none() {
+            log::warn!("Tag '{VALUE_TAG_KEY}' should be a finite number, it is only sent as a tag");
+        }
+    }
+}
+
+impl From<HashMap<String, TagValue>> for EventTags {
+    fn from(tags: HashMap<String, TagValue>) -> Self {
+        EventTags(tags)
+    }
+}
+
+impl From<HashMap<String, String>> for EventTags {
+    /// Tags with text values, where `revenue` and `value` are still used if they contain a number
+    fn from(tags: HashMap<String, String>) -> Self {
+        let tags = tags
+            .into_iter()
+            .map(|(key, value)| (key, TagValue::String(value)))
+            .collect();
+
+        EventTags(tags)
+    }
+}
+
+impl From<i64> for TagValue {
+    fn from(value: i64) -> Self {
+        TagValue::Integer(value)
+    }
+}
+
+impl From<i32> for TagValue {
+    fn from(value: i32) -> Self {
+        TagValue::Integer(value.into())
+    }
+}
+
+impl From<u32> for TagValue {
+    fn from(value: u32) -> Self {
+        TagValue::Integer(value.into())
+    }
+}
+
+impl From<f64> for TagValue {
+    fn from(value: f64) -> Self {
+        TagValue::Decimal(value)
+    }
+}
+
+impl From<bool> for TagValue {
+    fn from(value: bool) -> Self {
+        TagValue::Boolean(value)
+    }
+}
+
+impl From<&str> for TagValue {
+    fn from(value: &str) -> Self {
+        TagValue::String(value.to_owned())
+    }
+}
+
+impl From<String> for TagValue {
+    fn from(value: String) -> Self {
+        TagValue::String(value)
+    }
+}
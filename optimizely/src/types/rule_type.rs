@@ -0,0 +1,27 @@
+//! Type of the rule that produced a decision
+
+// External imports
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+/// Type of the rule that produced a decision, as used in the metadata of decision events
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RuleType {
+    /// Legacy experiment, which is not part of a flag
+    Experiment,
+    /// Delivery rule of a flag, or the "off" variation when no rule applies
+    Rollout,
+    /// Experiment rule of a flag
+    FeatureTest,
+}
+
+impl Display for RuleType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleType::Experiment => write!(f, "experiment"),
+            RuleType::Rollout => write!(f, "rollout"),
+            RuleType::FeatureTest => write!(f, "feature-test"),
+        }
+    }
+}
@@ -51,7 +51,7 @@ impl Serialize for UserAttribute {
         let mut st = serializer.serialize_struct("UserAttribute", 4)?;
         st.serialize_field("entity_id", &self.id)?;
         st.serialize_field("key", &self.key)?;
-        st.serialize_field("attribute_type", "custom")?;
+        st.serialize_field("type", "custom")?;
         // Convert the dynamic type into a String,
         // then pass it to the "serialize_field" method which accepts a generic type
         st.serialize_field("value", &String::from(&self.value))?;
@@ -0,0 +1,210 @@
+// External imports
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Imports from Optimizely crate
+use optimizely::event_api::{
+    BackpressurePolicy, BatchedEventDispatcher, BatchedEventDispatcherBuilder, EventDispatcher, LogEvent, RetryPolicy,
+};
+use optimizely::testing::MockServer;
+use optimizely::Client;
+
+// Relative imports of sub modules
+use common::FILE_PATH;
+mod common;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// Dispatcher that is shared with the test, so the test can inspect it while the client uses it
+struct SharedDispatcher(Arc<BatchedEventDispatcher>);
+
+impl EventDispatcher for SharedDispatcher {
+    fn dispatch_event(&self, event: LogEvent) {
+        self.0.dispatch_event(event);
+    }
+
+    fn flush(&self, timeout: Duration) -> bool {
+        self.0.flush(timeout)
+    }
+}
+
+fn setup(
+    server: &MockServer, builder: BatchedEventDispatcherBuilder,
+) -> Result<(Client, Arc<BatchedEventDispatcher>), Box<dyn Error>> {
+    let client = Client::from_local_datafile(FILE_PATH)?.initialize();
+    let dispatcher = Arc::new(builder.build(&client.datafile()));
+
+    let shared = dispatcher.clone();
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_api_url(server.url())
+        .with_event_dispatcher(|_datafile| SharedDispatcher(shared))
+        .initialize();
+
+    Ok((client, dispatcher))
+}
+
+fn visitor_ids(server: &MockServer) -> Vec<String> {
+    server
+        .event_requests()
+        .iter()
+        .filter(|request| request.status() == 204)
+        .filter_map(|request| request.payload()?["visitors"].as_array().cloned())
+        .flatten()
+        .filter_map(|visitor| visitor["visitor_id"].as_str().map(String::from))
+        .collect()
+}
+
+#[test]
+fn flush_interval() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    let builder = BatchedEventDispatcher::builder()
+        .with_batch_size(100)
+        .with_flush_interval(Duration::from_millis(50));
+    let (client, _dispatcher) = setup(&server, builder)?;
+
+    // Batch is sent while the client is still running
+    client.create_user_context("user-1").track_event("purchase");
+    assert!(server.wait_for_event_requests(1, TIMEOUT));
+    assert_eq!(visitor_ids(&server), ["user-1"]);
+
+    Ok(())
+}
+
+#[test]
+fn flush_events() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    let builder = BatchedEventDispatcher::builder().with_batch_size(100);
+    let (client, _dispatcher) = setup(&server, builder)?;
+
+    // Nothing to send
+    assert!(client.flush_events(TIMEOUT));
+    assert!(server.event_requests().is_empty());
+
+    for index in 0..3 {
+        client
+            .create_user_context(&format!("user-{index}"))
+            .track_event("purchase");
+    }
+
+    // Batch is sent before flush returns, without waiting for the flush interval
+    assert!(client.flush_events(TIMEOUT));
+    assert_eq!(visitor_ids(&server), ["user-0", "user-1", "user-2"]);
+
+    // Timeout elapses while the batch is retried
+    server.fail_next_event_requests(1, 503);
+    client.create_user_context("user-3").track_event("purchase");
+    assert!(!client.flush_events(Duration::from_millis(100)));
+
+    Ok(())
+}
+
+#[test]
+fn max_payload_bytes() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    let builder = BatchedEventDispatcher::builder()
+        .with_batch_size(100)
+        .with_max_payload_bytes(1_000);
+    let (client, dispatcher) = setup(&server, builder)?;
+
+    for index in 0..10 {
+        client
+            .create_user_context(&format!("user-{index}"))
+            .track_event("purchase");
+    }
+    drop(client);
+    drop(dispatcher);
+
+    // Every visitor is sent exactly once, split over multiple payloads
+    let requests = server.event_requests();
+    assert!(requests.len() > 1);
+    assert!(requests.iter().all(|request| request.body().len() <= 1_000));
+    let expected = (0..10)
+        .map(|index| format!("user-{index}"))
+        .collect::<Vec<_>>();
+    assert_eq!(visitor_ids(&server), expected);
+
+    Ok(())
+}
+
+// Keep the receiver thread busy with the first event, while the other events fill up the queue
+fn fill_queue(policy: BackpressurePolicy) -> Result<(MockServer, u64), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    server.fail_next_event_requests(1, 503);
+    let builder = BatchedEventDispatcher::builder()
+        .with_batch_size(1)
+        .with_queue_capacity(1, policy)
+        .with_retry_policy(RetryPolicy::new(2, Duration::from_millis(500)));
+    let (client, dispatcher) = setup(&server, builder)?;
+
+    client.create_user_context("user-0").track_event("purchase");
+    assert!(server.wait_for_event_requests(1, TIMEOUT));
+    for index in 1..4 {
+        client
+            .create_user_context(&format!("user-{index}"))
+            .track_event("purchase");
+    }
+
+    drop(client);
+    let dropped = dispatcher.dropped_events();
+    drop(dispatcher);
+
+    Ok((server, dropped))
+}
+
+#[test]
+fn drop_newest() -> Result<(), Box<dyn Error>> {
+    let (server, dropped) = fill_queue(BackpressurePolicy::DropNewest)?;
+    assert_eq!(dropped, 2);
+    assert_eq!(visitor_ids(&server), ["user-0", "user-1"]);
+
+    Ok(())
+}
+
+#[test]
+fn drop_oldest() -> Result<(), Box<dyn Error>> {
+    let (server, dropped) = fill_queue(BackpressurePolicy::DropOldest)?;
+    assert_eq!(dropped, 2);
+    assert_eq!(visitor_ids(&server), ["user-0", "user-3"]);
+
+    Ok(())
+}
+
+#[test]
+fn block() -> Result<(), Box<dyn Error>> {
+    let (server, dropped) = fill_queue(BackpressurePolicy::Block)?;
+    assert_eq!(dropped, 0);
+    assert_eq!(visitor_ids(&server), ["user-0", "user-1", "user-2", "user-3"]);
+
+    Ok(())
+}
+
+#[test]
+fn batch_per_client_ip() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    let builder = BatchedEventDispatcher::builder().with_batch_size(100);
+    let (client, dispatcher) = setup(&server, builder)?;
+
+    // Users without an IP address share a batch, regardless of their other properties
+    client.create_user_context("user-1").track_event("purchase");
+    let mut user_context = client.create_user_context("user-2");
+    user_context.set_session_id("session-2");
+    user_context.track_event("purchase");
+
+    // The Event API expects the IP address once per payload
+    let mut user_context = client.create_user_context("user-3");
+    user_context.set_client_ip("192.0.2.1");
+    user_context.track_event("purchase");
+    assert!(dispatcher.flush(TIMEOUT));
+
+    let requests = server.event_requests();
+    assert_eq!(requests.len(), 2);
+    let first = requests[0].payload().ok_or("Missing payload")?;
+    assert!(first.get("client_ip").is_none());
+    assert_eq!(first["visitors"].as_array().map(Vec::len), Some(2));
+    let second = requests[1].payload().ok_or("Missing payload")?;
+    assert_eq!(second["client_ip"], "192.0.2.1");
+    assert_eq!(visitor_ids(&server), ["user-1", "user-2", "user-3"]);
+
+    Ok(())
+}
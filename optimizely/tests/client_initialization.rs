@@ -1,9 +1,16 @@
 // External imports
 use std::error::Error;
+use std::fs;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
 
 // Imports from Optimizely crate
+#[cfg(feature = "online")]
+use optimizely::datafile::{Datafile, DatafileCache};
 use optimizely::{
-    error::{ClientError, DatafileError},
+    error::{ClientError, DatafileError, ParseLocation},
     Client,
 };
 
@@ -25,7 +32,7 @@ fn with_invalid_json() -> Result<(), Box<dyn Error>> {
     let client_error = report
         .downcast_ref::<ClientError>()
         .ok_or("ClientError not found")?;
-    assert_eq!(client_error, &ClientError::InvalidDatafile);
+    assert_eq!(client_error, &ClientError::InvalidJson);
 
     // Verify the json error type
     let datafile_error = report
@@ -53,13 +60,13 @@ fn with_missing_properties() -> Result<(), Box<dyn Error>> {
     let client_error = report
         .downcast_ref::<ClientError>()
         .ok_or("ClientError not found")?;
-    assert_eq!(client_error, &ClientError::InvalidDatafile);
+    assert_eq!(client_error, &ClientError::InvalidSchema);
 
     // Verify the json error type
     let datafile_error = report
         .downcast_ref::<DatafileError>()
         .ok_or("DatafileError not found")?;
-    assert_eq!(datafile_error, &DatafileError::InvalidJson);
+    assert_eq!(datafile_error, &DatafileError::InvalidSchema);
 
     Ok(())
 }
@@ -86,13 +93,13 @@ fn with_invalid_array_properties() -> Result<(), Box<dyn Error>> {
     let client_error = report
         .downcast_ref::<ClientError>()
         .ok_or("ClientError not found")?;
-    assert_eq!(client_error, &ClientError::InvalidDatafile);
+    assert_eq!(client_error, &ClientError::InvalidSchema);
 
     // Verify the json error type
     let datafile_error = report
         .downcast_ref::<DatafileError>()
         .ok_or("DatafileError not found")?;
-    assert_eq!(datafile_error, &DatafileError::InvalidJson);
+    assert_eq!(datafile_error, &DatafileError::InvalidSchema);
 
     Ok(())
 }
@@ -112,10 +119,128 @@ fn with_sdk_key() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+#[cfg(feature = "online")]
+fn in_background_without_fallback() -> Result<(), Box<dyn Error>> {
+    // This SDK key does not exist, so the client never becomes ready
+    let client = Client::from_sdk_key_in_background("invalid-sdk-key").initialize();
+    assert!(!client.wait_until_ready(Duration::from_millis(100)));
+    assert!(!client.is_ready());
+
+    // Every decision is off, while the client is not ready
+    let decision = client.create_user_context("user0").decide("a_b_test");
+    assert!(!decision.enabled());
+    assert_eq!(decision.variation_key(), "off");
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "online")]
+fn in_background_with_fallback() -> Result<(), Box<dyn Error>> {
+    // This SDK key does not exist, so the fallback datafile is used
+    let client = Client::from_sdk_key_in_background("invalid-sdk-key")
+        .with_fallback_datafile(Datafile::from_local_datafile(FILE_PATH)?)
+        .initialize();
+    assert!(!client.is_ready());
+
+    // Check account id property on client
+    assert_eq!(client.datafile().account_id(), ACCOUNT_ID);
+
+    // Decisions are made using the fallback datafile
+    let decision = client.create_user_context("user0").decide("a_b_test");
+    assert_eq!(decision.variation_key(), "variation_1");
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "online")]
+fn with_cache_fallback() -> Result<(), Box<dyn Error>> {
+    // This SDK key does not exist, so the CDN request fails
+    let sdk_key = "invalid-sdk-key-cache-fallback";
+
+    // Fill the cache with the bundled datafile
+    let cache_directory = std::env::temp_dir().join("optimizely-test-cache-fallback");
+    let cache = DatafileCache::new(&cache_directory);
+    cache.store(sdk_key, &fs::read_to_string(FILE_PATH)?)?;
+
+    // The cached datafile is used, since the CDN request fails
+    let client = Client::from_sdk_key_with_cache(sdk_key, &cache_directory)?.initialize();
+    assert_eq!(client.datafile().account_id(), ACCOUNT_ID);
+    assert_eq!(client.datafile().revision(), REVISION);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "online")]
+fn with_empty_cache() -> Result<(), Box<dyn Error>> {
+    // This SDK key does not exist, so the CDN request fails
+    let sdk_key = "invalid-sdk-key-empty-cache";
+    let cache_directory = std::env::temp_dir().join("optimizely-test-empty-cache");
+
+    // Without a cached datafile, initialization fails
+    let report = Client::from_sdk_key_with_cache(sdk_key, &cache_directory)
+        .err()
+        .ok_or("Unexpected Result::Ok")?;
+
+    // Verify the client error type
+    let client_error = report
+        .downcast_ref::<ClientError>()
+        .ok_or("ClientError not found")?;
+    assert_eq!(client_error, &ClientError::FailedDownload);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "online")]
+fn cache_file_per_sdk_key() -> Result<(), Box<dyn Error>> {
+    let cache_directory = std::env::temp_dir().join("optimizely-test-cache-file-names");
+    let cache = DatafileCache::new(&cache_directory);
+
+    // Regular SDK keys keep their name
+    assert_eq!(cache.path(common::SDK_KEY), cache_directory.join(format!("{}.json", common::SDK_KEY)));
+
+    // Keys that only differ in special characters are stored in different files
+    let paths = ["a/b", "a.b", "ab", "a%2Fb", "a_b"].map(|sdk_key| cache.path(sdk_key));
+    for (index, path) in paths.iter().enumerate() {
+        assert_eq!(path.parent(), Some(cache_directory.as_path()));
+        assert!(!paths[index + 1..].contains(path), "{}", path.display());
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "online")]
+fn in_background_with_cache() -> Result<(), Box<dyn Error>> {
+    // This SDK key does not exist, so the CDN request fails
+    let sdk_key = "invalid-sdk-key-background-cache";
+
+    // Fill the cache with the bundled datafile
+    let cache_directory = std::env::temp_dir().join("optimizely-test-background-cache");
+    let cache = DatafileCache::new(&cache_directory);
+    cache.store(sdk_key, &fs::read_to_string(FILE_PATH)?)?;
+
+    // The cached datafile is used while the download is pending
+    let client = Client::from_sdk_key_in_background(sdk_key)
+        .with_datafile_cache(&cache_directory)
+        .initialize();
+    assert!(!client.is_ready());
+    assert_eq!(client.datafile().revision(), REVISION);
+
+    Ok(())
+}
+
 #[test]
 fn with_fixed_datafile() -> Result<(), Box<dyn Error>> {
     let client = Client::from_local_datafile(FILE_PATH)?.initialize();
 
+    // A client with a local datafile is ready immediately
+    assert!(client.is_ready());
+
     // Check account id property on client
     assert_eq!(client.datafile().account_id(), ACCOUNT_ID);
 
@@ -124,3 +249,127 @@ fn with_fixed_datafile() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn with_file_watch() -> Result<(), Box<dyn Error>> {
+    // Copy the bundled datafile, so it can be modified
+    let content = fs::read_to_string(FILE_PATH)?;
+    let file_path = std::env::temp_dir().join("optimizely-test-file-watch.json");
+    fs::write(&file_path, &content)?;
+
+    let client = Client::from_local_datafile(&file_path)?
+        .with_file_watch(Duration::from_millis(10))
+        .initialize();
+    assert_eq!(client.datafile().revision(), REVISION);
+
+    // Wait until the revision of the client matches the expected revision
+    let wait_for_revision = |revision: u32| {
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while client.datafile().revision() != revision && Instant::now() < deadline {
+            sleep(Duration::from_millis(10));
+        }
+        client.datafile().revision()
+    };
+
+    // An invalid datafile is ignored
+    fs::write(&file_path, "{}")?;
+    sleep(Duration::from_millis(100));
+    assert_eq!(client.datafile().revision(), REVISION);
+
+    // A datafile with a newer revision is used
+    let newer_revision = REVISION + 1;
+    let newer_content =
+        content.replace(&format!(r#""revision": "{REVISION}""#), &format!(r#""revision": "{newer_revision}""#));
+    fs::write(&file_path, newer_content)?;
+    assert_eq!(wait_for_revision(newer_revision), newer_revision);
+
+    // A datafile with an older revision is ignored
+    fs::write(&file_path, &content)?;
+    sleep(Duration::from_millis(100));
+    assert_eq!(client.datafile().revision(), newer_revision);
+
+    Ok(())
+}
+
+#[test]
+fn file_watch_change_during_initialization() -> Result<(), Box<dyn Error>> {
+    let content = fs::read_to_string(FILE_PATH)?;
+    let file_path = std::env::temp_dir().join("optimizely-test-file-watch-initialization.json");
+    fs::write(&file_path, &content)?;
+
+    let uninitialized_client = Client::from_local_datafile(&file_path)?.with_file_watch(Duration::from_millis(10));
+
+    // The file changes after it was loaded, but before the watcher started
+    let newer_revision = REVISION + 1;
+    let newer_content =
+        content.replace(&format!(r#""revision": "{REVISION}""#), &format!(r#""revision": "{newer_revision}""#));
+    fs::write(&file_path, format!("{newer_content}\n"))?;
+
+    let client = uninitialized_client.initialize();
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while client.datafile().revision() != newer_revision && Instant::now() < deadline {
+        sleep(Duration::from_millis(10));
+    }
+    assert_eq!(client.datafile().revision(), newer_revision);
+
+    Ok(())
+}
+
+#[test]
+fn with_manual_update() -> Result<(), Box<dyn Error>> {
+    let content = fs::read_to_string(FILE_PATH)?;
+    let client = Client::from_string(&content)?.initialize();
+
+    // Keep track of the latest revision that listeners were notified of
+    let notified_revision = Arc::new(AtomicU32::new(0));
+    let notified_revision_clone = notified_revision.clone();
+    client.add_config_update_listener(move |revision| notified_revision_clone.store(revision, Ordering::SeqCst));
+
+    // An invalid datafile is rejected
+    let report = client
+        .update_datafile("{}")
+        .err()
+        .ok_or("Unexpected Result::Ok")?;
+    assert_eq!(report.current_context(), &ClientError::InvalidSchema);
+
+    // A datafile with the same revision is rejected
+    let report = client
+        .update_datafile(&content)
+        .err()
+        .ok_or("Unexpected Result::Ok")?;
+    assert_eq!(report.current_context(), &ClientError::OutdatedDatafile);
+    assert_eq!(notified_revision.load(Ordering::SeqCst), 0);
+
+    // A datafile with a newer revision is used
+    let newer_revision = REVISION + 1;
+    let newer_content =
+        content.replace(&format!(r#""revision": "{REVISION}""#), &format!(r#""revision": "{newer_revision}""#));
+    client.update_datafile(newer_content)?;
+    assert_eq!(client.datafile().revision(), newer_revision);
+    assert_eq!(notified_revision.load(Ordering::SeqCst), newer_revision);
+
+    Ok(())
+}
+
+#[test]
+fn with_parse_location() -> Result<(), Box<dyn Error>> {
+    // Valid JSON, but the end of a traffic range should be a number
+    let content = fs::read_to_string(FILE_PATH)?.replacen(r#""endOfRange": 10000"#, r#""endOfRange": "all""#, 1);
+
+    // Get error report
+    let report = Client::from_string(&content)
+        .err()
+        .ok_or("Unexpected Result::Ok")?;
+    assert_eq!(report.current_context(), &ClientError::InvalidSchema);
+
+    // Verify the location of the error
+    let location = report
+        .downcast_ref::<ParseLocation>()
+        .ok_or("ParseLocation not found")?;
+    assert_eq!(location.path(), "rollouts[0].experiments[0].trafficAllocation[0].endOfRange");
+    assert_eq!(location.message(), r#"invalid type: string "all", expected u64"#);
+    assert_eq!(location.line(), 195);
+    assert_eq!(location.column(), 33);
+
+    Ok(())
+}
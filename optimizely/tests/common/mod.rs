@@ -6,7 +6,8 @@ use std::error::Error;
 use std::sync::{Arc, RwLock};
 
 // Imports from Optimizely crate
-use optimizely::{client::UserContext, event_api::EventDispatcher, Client, Conversion, Decision};
+use optimizely::event_api::{EventDispatcher, LogEvent, LogEventKind};
+use optimizely::Client;
 
 // This is the account ID of mark.biesheuvel@optimizely.com
 pub const ACCOUNT_ID: &str = "21537940595";
@@ -53,12 +54,11 @@ pub struct EventStore {
 
 // Implementing the EventDispatcher using the interior mutability pattern
 impl EventDispatcher for EventStore {
-    fn send_conversion_event(&self, _user_context: &UserContext, _conversion: Conversion) {
-        self.conversion_counter.increment();
-    }
-
-    fn send_decision_event(&self, _user_context: &UserContext, _decision: Decision) {
-        self.decision_counter.increment();
+    fn dispatch_event(&self, event: LogEvent) {
+        match event.kind() {
+            LogEventKind::Conversion(_) => self.conversion_counter.increment(),
+            LogEventKind::Decision(_) => self.decision_counter.increment(),
+        }
     }
 }
 
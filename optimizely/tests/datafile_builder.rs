@@ -0,0 +1,178 @@
+// External imports
+use serde_json::json;
+use std::error::Error;
+
+// Imports from Optimizely crate
+use optimizely::config::OptimizelyConfig;
+use optimizely::error::DatafileError;
+use optimizely::testing::{DatafileBuilder, FlagBuilder, RuleBuilder, VariationBuilder};
+use optimizely::AttributeValue;
+
+// Relative imports of sub modules
+use common::EventStore;
+mod common;
+
+fn logged_out_builder() -> DatafileBuilder {
+    DatafileBuilder::new()
+        .revision(42)
+        .attribute("isLoggedIn")
+        .event("purchase")
+        .audience(
+            "Logged out users",
+            json!(["and", {"type": "custom_attribute", "name": "isLoggedIn", "match": "exact", "value": false}]),
+        )
+        .flag(
+            FlagBuilder::new("checkout")
+                .variable("label", "string", "Buy")
+                .experiment_rule(
+                    RuleBuilder::new("checkout_test")
+                        .audience("Logged out users")
+                        .variation(VariationBuilder::new("treatment").variable("label", "Buy now"), 0..10_000),
+                )
+                .delivery_rule(RuleBuilder::new("everyone").variation("on", 0..10_000)),
+        )
+        .flag(FlagBuilder::new("dark_mode"))
+}
+
+#[test]
+fn build_datafile() -> Result<(), Box<dyn Error>> {
+    let datafile = logged_out_builder().build()?;
+
+    let config = OptimizelyConfig::from(&datafile);
+
+    assert_eq!(config.revision(), 42);
+    assert_eq!(config.features_map().len(), 2);
+    assert_eq!(config.experiments_map().len(), 1);
+    assert_eq!(config.audiences().len(), 1);
+    assert_eq!(config.attributes_map().len(), 1);
+
+    // Experiment targets the audience by name
+    let experiment = config
+        .experiment("checkout_test")
+        .ok_or("Experiment not found")?;
+    assert_eq!(experiment.audiences(), "\"Logged out users\"");
+
+    // Events are metrics of all experiments
+    let event = config.event("purchase").ok_or("Event not found")?;
+    assert_eq!(event.experiment_ids(), [experiment.id()]);
+
+    // Everyone Else rule is added to every flag
+    let flag = config.feature("dark_mode").ok_or("Flag not found")?;
+    assert_eq!(flag.delivery_rules().len(), 1);
+
+    // Same builder results in the same datafile
+    assert_eq!(datafile.to_json()?, logged_out_builder().build()?.to_json()?);
+
+    Ok(())
+}
+
+#[test]
+fn build_client() -> Result<(), Box<dyn Error>> {
+    let client = logged_out_builder()
+        .build_client()?
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .initialize();
+
+    // Logged out users are part of the experiment
+    let mut user_context = client.create_user_context("user-1");
+    user_context.set_attribute("isLoggedIn", AttributeValue::Boolean(false));
+    let decision = user_context.decide("checkout");
+    assert!(decision.enabled());
+    assert_eq!(decision.variation_key(), "treatment");
+    assert_eq!(decision.variable("label"), Some("Buy now"));
+
+    // Logged in users fall through to the delivery rule
+    let mut user_context = client.create_user_context("user-2");
+    user_context.set_attribute("isLoggedIn", AttributeValue::Boolean(true));
+    let decision = user_context.decide("checkout");
+    assert!(decision.enabled());
+    assert_eq!(decision.variation_key(), "on");
+    assert_eq!(decision.variable("label"), Some("Buy"));
+
+    // Flags without rules are off for everyone
+    let decision = user_context.decide("dark_mode");
+    assert!(!decision.enabled());
+    assert_eq!(decision.variation_key(), "off");
+
+    Ok(())
+}
+
+#[test]
+fn explicit_traffic_ranges() -> Result<(), Box<dyn Error>> {
+    let client = DatafileBuilder::new()
+        .flag(
+            FlagBuilder::new("split").experiment_rule(
+                RuleBuilder::new("split_test")
+                    .variation("a", 0..5_000)
+                    .variation("b", 5_000..10_000),
+            ),
+        )
+        .flag(FlagBuilder::new("nobody").delivery_rule(RuleBuilder::new("gap").variation("on", 0..0)))
+        .build_client()?
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .initialize();
+
+    // Every user ends up in one of both variations
+    let variation_keys = (0..100)
+        .map(|index| {
+            let user_id = format!("user-{index}");
+            let user_context = client.create_user_context(&user_id);
+            user_context.decide("split").variation_key().to_owned()
+        })
+        .collect::<Vec<_>>();
+    assert!(variation_keys.iter().any(|key| key == "a"));
+    assert!(variation_keys.iter().any(|key| key == "b"));
+    assert!(variation_keys.iter().all(|key| key == "a" || key == "b"));
+
+    // Empty traffic range, so the flag falls through to "Everyone Else"
+    let decision = client.create_user_context("user-1").decide("nobody");
+    assert_eq!(decision.variation_key(), "off");
+
+    Ok(())
+}
+
+#[test]
+fn unknown_audience() {
+    let result = DatafileBuilder::new()
+        .flag(FlagBuilder::new("checkout").delivery_rule(RuleBuilder::new("rule").audience("Unknown")))
+        .build();
+
+    let report = result.unwrap_err();
+    assert_eq!(report.current_context(), &DatafileError::FailedValidation);
+}
+
+#[test]
+fn unknown_variable() {
+    let result = DatafileBuilder::new()
+        .flag(
+            FlagBuilder::new("checkout")
+                .variable("label", "string", "Buy")
+                .delivery_rule(
+                    RuleBuilder::new("rule")
+                        .variation(VariationBuilder::new("on").variable("lable", "Buy now"), 0..10_000),
+                ),
+        )
+        .build();
+
+    let report = result.unwrap_err();
+    assert_eq!(report.current_context(), &DatafileError::FailedValidation);
+}
+
+#[test]
+fn duplicate_audience_name() {
+    let result = DatafileBuilder::new()
+        .attribute("isLoggedIn")
+        .audience(
+            "Users",
+            json!(["and", {"type": "custom_attribute", "name": "isLoggedIn", "match": "exact", "value": false}]),
+        )
+        .audience(
+            "Users",
+            json!(["and", {"type": "custom_attribute", "name": "isLoggedIn", "match": "exact", "value": true}]),
+        )
+        .flag(FlagBuilder::new("checkout").delivery_rule(RuleBuilder::new("rule").audience("Users")))
+        .build();
+
+    let report = result.unwrap_err();
+    assert_eq!(report.current_context(), &DatafileError::FailedValidation);
+}
@@ -0,0 +1,95 @@
+// External imports
+use serde_json::Value;
+use std::error::Error;
+
+// Imports from Optimizely crate
+use optimizely::{datafile::Datafile, Client};
+
+// Relative imports of sub modules
+use common::{EventStore, FILE_PATH, REVISION};
+mod common;
+
+// Collect the given property of every element in a list
+fn collect<'a>(document: &'a Value, list: &str, property: &str) -> Vec<&'a str> {
+    let mut values = document[list]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|element| element[property].as_str())
+        .collect::<Vec<_>>();
+    values.sort();
+    values
+}
+
+#[test]
+fn round_trip() -> Result<(), Box<dyn Error>> {
+    let datafile = Datafile::from_local_datafile(FILE_PATH)?;
+    let json = datafile.to_json()?;
+
+    // The serialized datafile contains all properties that other SDKs expect
+    let document = serde_json::from_str::<Value>(&json)?;
+    assert_eq!(document["revision"], REVISION.to_string());
+    assert_eq!(document["version"], "4");
+    assert_eq!(document["audiences"], Value::Array(Vec::new()));
+    assert_eq!(document["groups"], Value::Array(Vec::new()));
+    assert_eq!(collect(&document, "typedAudiences", "id").len(), 4);
+    assert_eq!(collect(&document, "featureFlags", "key"), ["a_b_test", "feature_rollout", "targeted_delivery"]);
+
+    // Parsing the serialized datafile results in the same datafile
+    let parsed_datafile = Datafile::from_string(&json)?;
+    assert!(parsed_datafile.validate().is_empty());
+    assert_eq!(parsed_datafile.to_json()?, json);
+
+    // Decisions are the same for both datafiles, without dispatching any events
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .initialize();
+    let parsed_client = Client::from_string(&json)?
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .initialize();
+    for user_id in ["user0", "user1", "user2", "user3", "user4", "user5"] {
+        let decision = client.create_user_context(user_id).decide("a_b_test");
+        let parsed_decision = parsed_client
+            .create_user_context(user_id)
+            .decide("a_b_test");
+        assert_eq!(decision.variation_key(), parsed_decision.variation_key());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn subset() -> Result<(), Box<dyn Error>> {
+    let datafile = Datafile::from_local_datafile(FILE_PATH)?;
+
+    // A/B test without audiences
+    let document = serde_json::from_str::<Value>(&datafile.subset(["a_b_test"]).to_json()?)?;
+    assert_eq!(collect(&document, "featureFlags", "key"), ["a_b_test"]);
+    assert_eq!(collect(&document, "experiments", "key"), ["a_b_test_rule"]);
+    assert_eq!(collect(&document, "rollouts", "id"), ["rollout-503553-911768386522787"]);
+    assert_eq!(collect(&document, "typedAudiences", "id"), Vec::<&str>::new());
+    assert_eq!(collect(&document, "attributes", "key"), Vec::<&str>::new());
+    assert_eq!(collect(&document, "events", "key"), ["add_to_cart"]);
+
+    // Targeted delivery with audiences
+    let document = serde_json::from_str::<Value>(&datafile.subset(["targeted_delivery"]).to_json()?)?;
+    assert_eq!(collect(&document, "featureFlags", "key"), ["targeted_delivery"]);
+    assert_eq!(collect(&document, "experiments", "key"), Vec::<&str>::new());
+    assert_eq!(collect(&document, "typedAudiences", "id").len(), 4);
+    assert_eq!(
+        collect(&document, "attributes", "key"),
+        [
+            "appVersion",
+            "currentPath",
+            "isLoggedIn",
+            "numberOfProductsInCart"
+        ]
+    );
+    assert_eq!(collect(&document, "events", "key"), Vec::<&str>::new());
+
+    // The subset is a valid datafile on its own
+    let subset = datafile.subset(["targeted_delivery", "unknown_flag"]);
+    assert!(subset.validate().is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+// External imports
+use std::error::Error;
+use std::fs;
+
+// Imports from Optimizely crate
+use optimizely::{
+    datafile::{Datafile, ValidationIssue},
+    error::{ClientError, DatafileError},
+    Client,
+};
+
+// Relative imports of sub modules
+use common::FILE_PATH;
+mod common;
+
+// Datafile with a problem for every type of validation issue
+fn invalid_content() -> Result<String, Box<dyn Error>> {
+    let content = fs::read_to_string(FILE_PATH)?
+        .replacen(r#""version": "4""#, r#""version": "3""#, 1)
+        .replacen(r#""rolloutId": "rollout-503559-911768386522787""#, r#""rolloutId": "rollout-404""#, 1)
+        .replacen(
+            "\"rolloutId\": \"rollout-503553-911768386522787\",\n      \"experimentIds\": [",
+            "\"rolloutId\": \"rollout-503553-911768386522787\",\n      \"experimentIds\": [\"experiment-404\",",
+            1,
+        )
+        .replacen(r#""audienceIds": []"#, r#""audienceIds": ["audience-404"]"#, 1)
+        .replacen(r#""entityId": "1601785""#, r#""entityId": "variation-404""#, 1)
+        .replacen(r#""endOfRange": 3333"#, r#""endOfRange": 6666"#, 1)
+        .replacen(r#""endOfRange": 2500"#, r#""endOfRange": 2500}, {"entityId": "1601798", "endOfRange": 1000"#, 1)
+        .replacen(r#""endOfRange": 10000"#, r#""endOfRange": 10001"#, 1)
+        .replacen(
+            "\"featureEnabled\": false,\n              \"variables\": []",
+            "\"featureEnabled\": false,\n              \"variables\": [{\"id\": \"variable-404\", \"value\": \"x\"}]",
+            1,
+        );
+
+    Ok(content)
+}
+
+#[test]
+fn valid_datafile() -> Result<(), Box<dyn Error>> {
+    let datafile = Datafile::from_local_datafile(FILE_PATH)?;
+
+    assert_eq!(datafile.validate(), Vec::new());
+    datafile.ensure_valid()?;
+
+    Ok(())
+}
+
+#[test]
+fn invalid_datafile() -> Result<(), Box<dyn Error>> {
+    // The datafile can still be parsed
+    let datafile = Datafile::from_string(invalid_content()?)?;
+
+    let issues = datafile.validate();
+    let expected_issues = [
+        ValidationIssue::UnsupportedVersion { version: "3".into() },
+        ValidationIssue::MissingRollout {
+            flag_key: "feature_rollout".into(),
+            rollout_id: "rollout-404".into(),
+        },
+        ValidationIssue::MissingExperiment {
+            flag_key: "a_b_test".into(),
+            experiment_id: "experiment-404".into(),
+        },
+        ValidationIssue::MissingAudience {
+            experiment_id: "default-rollout-503553-911768386522787".into(),
+            audience_id: "audience-404".into(),
+        },
+        ValidationIssue::MissingVariation {
+            experiment_id: "9300002920769".into(),
+            variation_id: "variation-404".into(),
+        },
+        ValidationIssue::MissingVariable {
+            experiment_id: "default-rollout-503553-911768386522787".into(),
+            variation_id: "1601781".into(),
+            variable_id: "variable-404".into(),
+        },
+        ValidationIssue::UnsortedTrafficRange {
+            experiment_id: "9300002920799".into(),
+            end_of_range: 1000,
+        },
+        ValidationIssue::OverlappingTrafficRange {
+            experiment_id: "9300002920769".into(),
+            end_of_range: 6666,
+        },
+        ValidationIssue::TrafficRangeOutOfBounds {
+            experiment_id: "default-rollout-503553-911768386522787".into(),
+            end_of_range: 10001,
+        },
+    ];
+
+    // Every issue should be reported
+    for expected_issue in &expected_issues {
+        assert!(issues.contains(expected_issue), "{expected_issue} not found in {issues:?}");
+    }
+    assert_eq!(issues.len(), expected_issues.len());
+
+    // The report contains the datafile error
+    let report = datafile
+        .ensure_valid()
+        .err()
+        .ok_or("Unexpected Result::Ok")?;
+    assert_eq!(report.current_context(), &DatafileError::FailedValidation);
+
+    Ok(())
+}
+
+#[test]
+fn strict_validation() -> Result<(), Box<dyn Error>> {
+    let content = invalid_content()?;
+
+    // Without strict validation, the datafile is accepted
+    Client::from_string(&content)?.initialize();
+
+    // With strict validation, the datafile is rejected
+    let report = Client::from_string(&content)?
+        .with_strict_validation()
+        .err()
+        .ok_or("Unexpected Result::Ok")?;
+    assert_eq!(report.current_context(), &ClientError::FailedValidation);
+
+    // An update with validation issues is rejected as well
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_strict_validation()?
+        .initialize();
+    let report = client
+        .update_datafile(content.replace(r#""revision": "21""#, r#""revision": "22""#))
+        .err()
+        .ok_or("Unexpected Result::Ok")?;
+    assert_eq!(report.current_context(), &ClientError::FailedValidation);
+    assert_eq!(client.datafile().revision(), 21);
+
+    Ok(())
+}
@@ -2,7 +2,7 @@
 use std::error::Error;
 
 //
-use optimizely::AttributeValue;
+use optimizely::{AttributeValue, RuleType};
 
 // Relative imports of sub modules
 use common::setup;
@@ -168,3 +168,27 @@ fn targeted_delivery() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn rule_metadata() -> Result<(), Box<dyn Error>> {
+    let ctx = setup()?;
+    let user_context = ctx.client.create_user_context("user-1");
+
+    let decision = user_context.decide("a_b_test");
+    assert_eq!(decision.rule_key(), "a_b_test_rule");
+    assert_eq!(decision.rule_type(), RuleType::FeatureTest);
+
+    let decision = ctx
+        .client
+        .create_user_context("user6")
+        .decide("feature_rollout");
+    assert_eq!(decision.rule_key(), "feature_rollout_rule");
+    assert_eq!(decision.rule_type(), RuleType::Rollout);
+
+    // No rule applies to a flag that does not exist
+    let decision = user_context.decide("unknown");
+    assert_eq!(decision.rule_key(), "");
+    assert_eq!(decision.rule_type(), RuleType::Rollout);
+
+    Ok(())
+}
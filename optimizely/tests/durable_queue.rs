@@ -0,0 +1,195 @@
+// External imports
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// Imports from Optimizely crate
+use optimizely::event_api::{BackpressurePolicy, BatchedEventDispatcher, RetryPolicy};
+use optimizely::testing::MockServer;
+use optimizely::Client;
+
+// Relative imports of sub modules
+use common::FILE_PATH;
+mod common;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// Empty directory that is unique for the test
+fn queue_directory(name: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let directory = std::env::temp_dir().join(format!("optimizely-{name}-{}", std::process::id()));
+    if directory.exists() {
+        fs::remove_dir_all(&directory)?;
+    }
+    Ok(directory)
+}
+
+fn segment_count(directory: &Path) -> Result<usize, Box<dyn Error>> {
+    Ok(fs::read_dir(directory)?.count())
+}
+
+#[test]
+fn acknowledged_events_are_removed() -> Result<(), Box<dyn Error>> {
+    let directory = queue_directory("acknowledged")?;
+    let server = MockServer::start()?;
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_api_url(server.url())
+        .with_event_dispatcher(|datafile| BatchedEventDispatcher::with_durable_queue(datafile, &directory))
+        .initialize();
+
+    client.create_user_context("user-1").track_event("purchase");
+    client.create_user_context("user-2").track_event("purchase");
+    drop(client);
+
+    assert!(server.wait_for_event_requests(1, TIMEOUT));
+    assert_eq!(server.event_requests().len(), 1);
+    assert_eq!(segment_count(&directory)?, 0);
+
+    fs::remove_dir_all(&directory)?;
+    Ok(())
+}
+
+#[test]
+fn unsent_events_are_replayed() -> Result<(), Box<dyn Error>> {
+    let directory = queue_directory("replayed")?;
+    let server = MockServer::start()?;
+    server.set_event_status(Some(503));
+
+    // Event API is down, so the events stay on disk
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_api_url(server.url())
+        .with_event_dispatcher(|datafile| BatchedEventDispatcher::with_durable_queue(datafile, &directory))
+        .initialize();
+    client.create_user_context("user-1").track_event("purchase");
+    client.create_user_context("user-2").decide("a_b_test");
+    drop(client);
+
+    let failed_requests = server.event_requests();
+    assert!(failed_requests
+        .iter()
+        .all(|request| request.status() == 503));
+    assert_eq!(segment_count(&directory)?, 1);
+
+    // Event API is up again, so the next start sends the same events
+    server.set_event_status(None);
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_api_url(server.url())
+        .with_event_dispatcher(|datafile| BatchedEventDispatcher::with_durable_queue(datafile, &directory))
+        .initialize();
+    drop(client);
+
+    let requests = server.event_requests();
+    let replayed = requests.last().ok_or("Missing request")?;
+    assert_eq!(replayed.status(), 204);
+    assert_eq!(replayed.body(), failed_requests[0].body());
+    assert_eq!(segment_count(&directory)?, 0);
+
+    fs::remove_dir_all(&directory)?;
+    Ok(())
+}
+
+#[test]
+fn events_are_stored_before_dispatch_returns() -> Result<(), Box<dyn Error>> {
+    let directory = queue_directory("stored")?;
+    let server = MockServer::start()?;
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_api_url(server.url())
+        .with_event_dispatcher(|datafile| {
+            BatchedEventDispatcher::builder()
+                .with_flush_interval(Duration::from_secs(60))
+                .with_durable_queue(&directory)
+                .build(datafile)
+        })
+        .initialize();
+
+    // The event is on disk before the batch is sent
+    client.create_user_context("user-1").track_event("purchase");
+    let segments = fs::read_dir(&directory)?.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(segments.len(), 1);
+    assert!(fs::read_to_string(segments[0].path())?.contains("purchase"));
+    assert!(server.event_requests().is_empty());
+
+    // The segment is removed once the batch is acknowledged
+    drop(client);
+    assert_eq!(server.event_requests().len(), 1);
+    assert_eq!(segment_count(&directory)?, 0);
+
+    fs::remove_dir_all(&directory)?;
+    Ok(())
+}
+
+#[test]
+fn failed_events_are_replayed_after_next_batch() -> Result<(), Box<dyn Error>> {
+    let directory = queue_directory("failed")?;
+    let server = MockServer::start()?;
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_api_url(server.url())
+        .with_event_dispatcher(|datafile| {
+            BatchedEventDispatcher::builder()
+                .with_flush_interval(Duration::from_secs(60))
+                .with_retry_policy(RetryPolicy::no_retries())
+                .with_durable_queue(&directory)
+                .build(datafile)
+        })
+        .initialize();
+
+    // Event API is down, so the event stays on disk
+    server.fail_next_event_requests(1, 503);
+    client.create_user_context("user-1").track_event("purchase");
+    assert!(client.flush_events(TIMEOUT));
+    assert_eq!(segment_count(&directory)?, 1);
+
+    // Event API is up again, so the failed event is sent after the next batch
+    client.create_user_context("user-2").track_event("purchase");
+    assert!(client.flush_events(TIMEOUT));
+
+    let requests = server.event_requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[2].status(), 204);
+    assert_eq!(requests[2].body(), requests[0].body());
+    assert_eq!(segment_count(&directory)?, 0);
+
+    drop(client);
+    fs::remove_dir_all(&directory)?;
+    Ok(())
+}
+
+#[test]
+fn dropped_events_are_not_replayed() -> Result<(), Box<dyn Error>> {
+    let directory = queue_directory("dropped")?;
+    let server = MockServer::start()?;
+    server.fail_next_event_requests(1, 503);
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_api_url(server.url())
+        .with_event_dispatcher(|datafile| {
+            BatchedEventDispatcher::builder()
+                .with_batch_size(1)
+                .with_queue_capacity(1, BackpressurePolicy::DropNewest)
+                .with_retry_policy(RetryPolicy::new(2, Duration::from_millis(500)))
+                .with_durable_queue(&directory)
+                .build(datafile)
+        })
+        .initialize();
+
+    // Keep the receiver thread busy with the first event, while the other events fill up the queue
+    client.create_user_context("user-0").track_event("purchase");
+    assert!(server.wait_for_event_requests(1, TIMEOUT));
+    for index in 1..4 {
+        client
+            .create_user_context(&format!("user-{index}"))
+            .track_event("purchase");
+    }
+    drop(client);
+
+    // Only the events that were not dropped are sent, and nothing is left for the next start
+    let delivered = server
+        .event_requests()
+        .iter()
+        .filter(|request| request.status() == 204)
+        .count();
+    assert_eq!(delivered, 2);
+    assert_eq!(segment_count(&directory)?, 0);
+
+    fs::remove_dir_all(&directory)?;
+    Ok(())
+}
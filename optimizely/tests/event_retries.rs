@@ -0,0 +1,128 @@
+// External imports
+use std::error::Error;
+use std::time::Duration;
+
+// Imports from Optimizely crate
+use optimizely::event_api::{RetryPolicy, SimpleEventDispatcher};
+use optimizely::testing::MockServer;
+use optimizely::Client;
+
+// Relative imports of sub modules
+use common::FILE_PATH;
+mod common;
+
+fn setup(server: &MockServer) -> Result<Client, Box<dyn Error>> {
+    let retry_policy = RetryPolicy::new(3, Duration::from_millis(10));
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_api_url(server.url())
+        .with_event_dispatcher(|datafile| SimpleEventDispatcher::new(datafile).with_retry_policy(retry_policy))
+        .initialize();
+    Ok(client)
+}
+
+#[test]
+fn retry_server_errors() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    let client = setup(&server)?;
+
+    // Succeeds on the third attempt
+    server.fail_next_event_requests(2, 503);
+    client.create_user_context("user-1").track_event("purchase");
+
+    let requests = server.event_requests();
+    let statuses = requests
+        .iter()
+        .map(|request| request.status())
+        .collect::<Vec<_>>();
+    assert_eq!(statuses, [503, 503, 204]);
+
+    // Every attempt has the same body, so the Event API can de-duplicate using the UUID
+    assert!(requests
+        .iter()
+        .all(|request| request.body() == requests[0].body()));
+
+    Ok(())
+}
+
+#[test]
+fn maximum_attempts() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    let client = setup(&server)?;
+
+    server.set_event_status(Some(500));
+    client.create_user_context("user-1").track_event("purchase");
+    assert_eq!(server.event_requests().len(), 3);
+
+    // Next event is sent again after the Event API recovered
+    server.set_event_status(None);
+    client.create_user_context("user-1").track_event("purchase");
+    assert_eq!(server.event_requests().len(), 4);
+    assert_eq!(server.event_requests()[3].status(), 204);
+
+    Ok(())
+}
+
+#[test]
+fn no_retries_by_default() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_api_url(server.url())
+        .with_event_dispatcher(SimpleEventDispatcher::new)
+        .initialize();
+
+    // The caller is not blocked by retrying server errors
+    server.set_event_status(Some(500));
+    client.create_user_context("user-1").track_event("purchase");
+    assert_eq!(server.event_requests().len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn retry_timeouts_and_rate_limits() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    let client = setup(&server)?;
+
+    // Request Timeout and Too Many Requests are transient, unlike other client errors
+    server.fail_next_event_requests(1, 408);
+    client.create_user_context("user-1").track_event("purchase");
+    server.fail_next_event_requests(1, 429);
+    client.create_user_context("user-2").track_event("purchase");
+
+    let statuses = server
+        .event_requests()
+        .iter()
+        .map(|request| request.status())
+        .collect::<Vec<_>>();
+    assert_eq!(statuses, [408, 204, 429, 204]);
+
+    Ok(())
+}
+
+#[test]
+fn drop_client_errors() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    let client = setup(&server)?;
+
+    // Client errors are not retried
+    server.fail_next_event_requests(1, 400);
+    client.create_user_context("user-1").track_event("purchase");
+    assert_eq!(server.event_requests().len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn backoff() {
+    let retry_policy = RetryPolicy::new(10, Duration::from_millis(100)).with_max_backoff(Duration::from_secs(1));
+
+    assert_eq!(retry_policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(retry_policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(retry_policy.backoff(4), Duration::from_millis(800));
+    assert_eq!(retry_policy.backoff(5), Duration::from_secs(1));
+    assert_eq!(retry_policy.backoff(100), Duration::from_secs(1));
+
+    // At least one attempt is made
+    assert_eq!(RetryPolicy::new(0, Duration::ZERO).max_attempts(), 1);
+    assert_eq!(RetryPolicy::no_retries().max_attempts(), 1);
+}
@@ -0,0 +1,163 @@
+// External imports
+use serde_json::Value;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Imports from Optimizely crate
+use optimizely::event_api::{EventFormat, FileEventDispatcher};
+use optimizely::{AttributeValue, Client, EventTags};
+
+// Relative imports of sub modules
+use common::FILE_PATH;
+mod common;
+
+const CSV_HEADER: &str = "timestamp,event_type,visitor_id,session_id,attributes,flag_key,rule_key,experiment_id,\
+                          variation_key,enabled,event_key,tags";
+
+// Path in an empty directory that is unique for the test
+fn event_file(name: &str, extension: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let directory = std::env::temp_dir().join(format!("optimizely-events-{name}-{}", std::process::id()));
+    if directory.exists() {
+        fs::remove_dir_all(&directory)?;
+    }
+    fs::create_dir_all(&directory)?;
+    Ok(directory.join(format!("events.{extension}")))
+}
+
+fn setup(dispatcher: FileEventDispatcher) -> Result<Client, Box<dyn Error>> {
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_dispatcher(|_datafile| dispatcher)
+        .initialize();
+    Ok(client)
+}
+
+fn lines(path: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(String::from)
+        .collect())
+}
+
+#[test]
+fn ndjson_records() -> Result<(), Box<dyn Error>> {
+    let path = event_file("ndjson", "ndjson")?;
+    let client = setup(FileEventDispatcher::file(&path, EventFormat::Ndjson)?)?;
+
+    let mut user_context = client.create_user_context("user-1");
+    user_context.set_attribute("isLoggedIn", AttributeValue::Boolean(true));
+    user_context.set_session_id("session-1");
+    let decision = user_context.decide("a_b_test");
+    user_context.track_event_with_properties_and_tags(
+        "purchase",
+        Default::default(),
+        EventTags::new()
+            .with_revenue(4200)
+            .with_tag("category", "shoes"),
+    );
+    assert!(client.flush_events(std::time::Duration::from_secs(1)));
+
+    let records = lines(&path)?
+        .iter()
+        .map(|line| serde_json::from_str::<Value>(line))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(records.len(), 2);
+
+    let record = &records[0];
+    assert_eq!(record["event_type"], "decision");
+    assert_eq!(record["visitor_id"], "user-1");
+    assert_eq!(record["session_id"], "session-1");
+    assert_eq!(record["attributes"]["isLoggedIn"], "true");
+    assert_eq!(record["flag_key"], "a_b_test");
+    assert_eq!(record["rule_key"], "a_b_test_rule");
+    assert_eq!(record["experiment_id"], "9300002920769");
+    assert_eq!(record["variation_key"], decision.variation_key());
+    assert_eq!(record["enabled"], decision.enabled());
+    assert_eq!(record["event_key"], Value::Null);
+    assert_eq!(record["tags"], Value::Null);
+    assert!(record["timestamp"]
+        .as_u64()
+        .is_some_and(|timestamp| timestamp > 0));
+
+    let record = &records[1];
+    assert_eq!(record["event_type"], "conversion");
+    assert_eq!(record["visitor_id"], "user-1");
+    assert_eq!(record["event_key"], "purchase");
+    assert_eq!(record["tags"]["revenue"], 4200);
+    assert_eq!(record["tags"]["category"], "shoes");
+    assert_eq!(record["flag_key"], Value::Null);
+
+    Ok(())
+}
+
+#[test]
+fn csv_records() -> Result<(), Box<dyn Error>> {
+    let path = event_file("csv", "csv")?;
+    let client = setup(FileEventDispatcher::file(&path, EventFormat::Csv)?)?;
+
+    let mut user_context = client.create_user_context("user-1");
+    user_context.set_attribute("isLoggedIn", AttributeValue::Boolean(false));
+    let decision = user_context.decide("a_b_test");
+    user_context.track_event_with_properties_and_tags(
+        "purchase",
+        Default::default(),
+        EventTags::new().with_tag("category", "shoes, \"red\""),
+    );
+
+    let lines = lines(&path)?;
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], CSV_HEADER);
+
+    // Fields with separators or quotes are quoted
+    let decision_record = format!(
+        ",decision,user-1,,\"{{\"\"isLoggedIn\"\":\"\"false\"\"}}\",a_b_test,a_b_test_rule,9300002920769,{},{},,",
+        decision.variation_key(),
+        decision.enabled()
+    );
+    assert!(lines[1].ends_with(&decision_record), "{}", lines[1]);
+    let conversion_record = ",purchase,\"{\"\"category\"\":\"\"shoes, \\\"\"red\\\"\"\"\"}\"";
+    assert!(lines[2].contains(",conversion,user-1,,"), "{}", lines[2]);
+    assert!(lines[2].ends_with(conversion_record), "{}", lines[2]);
+
+    // Appending to an existing file does not repeat the header
+    drop(client);
+    let client = setup(FileEventDispatcher::file(&path, EventFormat::Csv)?)?;
+    client.create_user_context("user-2").track_event("purchase");
+    let lines = self::lines(&path)?;
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines.iter().filter(|line| *line == CSV_HEADER).count(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn rotation() -> Result<(), Box<dyn Error>> {
+    let path = event_file("rotation", "csv")?;
+    let dispatcher = FileEventDispatcher::file(&path, EventFormat::Csv)?.with_rotation(300, 2);
+    let client = setup(dispatcher)?;
+
+    for index in 0..20 {
+        client
+            .create_user_context(&format!("user-{index}"))
+            .track_event("purchase");
+    }
+
+    // Only two rotated files are kept
+    let rotated_path = |index: usize| PathBuf::from(format!("{}.{index}", path.display()));
+    assert!(rotated_path(1).exists());
+    assert!(rotated_path(2).exists());
+    assert!(!rotated_path(3).exists());
+
+    // Every file has a header and stays within the maximum size
+    for file in [path.clone(), rotated_path(1), rotated_path(2)] {
+        assert!(fs::metadata(&file)?.len() <= 300);
+        assert_eq!(lines(&file)?[0], CSV_HEADER);
+    }
+
+    // The most recent events are in the current file
+    assert!(lines(&path)?
+        .last()
+        .is_some_and(|line| line.contains("user-19")));
+
+    Ok(())
+}
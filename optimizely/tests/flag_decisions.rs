@@ -0,0 +1,98 @@
+// External imports
+use std::error::Error;
+use std::fs;
+
+// Imports from Optimizely crate
+use optimizely::testing::RecordingEventDispatcher;
+use optimizely::{Client, DecideOptions, RuleType};
+
+// Relative imports of sub modules
+use common::FILE_PATH;
+mod common;
+
+fn setup(send_flag_decisions: bool) -> Result<(Client, RecordingEventDispatcher), Box<dyn Error>> {
+    let content = fs::read_to_string(FILE_PATH)?.replace(
+        "\"anonymizeIP\": true,",
+        &format!("\"anonymizeIP\": true, \"sendFlagDecisions\": {send_flag_decisions},"),
+    );
+
+    let recorder = RecordingEventDispatcher::new();
+    let client = Client::from_string(content)?
+        .with_event_dispatcher(|_datafile| recorder.clone())
+        .initialize();
+    assert_eq!(client.datafile().send_flag_decisions(), send_flag_decisions);
+
+    Ok((client, recorder))
+}
+
+#[test]
+fn without_flag_decisions() -> Result<(), Box<dyn Error>> {
+    let (client, recorder) = setup(false)?;
+
+    client
+        .create_user_context("user6")
+        .decide("feature_rollout");
+    client
+        .create_user_context("user0")
+        .decide("feature_rollout");
+    assert!(recorder.events().is_empty());
+
+    // Experiments still send decision events
+    client.create_user_context("user0").decide("a_b_test");
+    assert_eq!(recorder.decisions().len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn rollout_decisions() -> Result<(), Box<dyn Error>> {
+    let (client, recorder) = setup(true)?;
+
+    let decision = client
+        .create_user_context("user6")
+        .decide("feature_rollout");
+    assert!(decision.enabled());
+
+    let events = recorder.events();
+    assert_eq!(events.len(), 1);
+    let metadata = &events[0].payload()["visitors"][0]["snapshots"][0]["decisions"][0]["metadata"];
+    assert_eq!(metadata["flag_key"], "feature_rollout");
+    assert_eq!(metadata["rule_key"], "feature_rollout_rule");
+    assert_eq!(metadata["rule_type"], "rollout");
+    assert_eq!(metadata["variation_key"], "on");
+    assert_eq!(metadata["enabled"], true);
+
+    Ok(())
+}
+
+#[test]
+fn off_decisions() -> Result<(), Box<dyn Error>> {
+    let (client, recorder) = setup(true)?;
+
+    let decision = client
+        .create_user_context("user0")
+        .decide("feature_rollout");
+    assert!(!decision.enabled());
+
+    let decisions = recorder.decisions();
+    assert_eq!(decisions.len(), 1);
+    assert_eq!(decisions[0].variation_key(), "off");
+    assert_eq!(decisions[0].rule_key(), "");
+    assert_eq!(decisions[0].rule_type(), RuleType::Rollout);
+
+    // Option to disable decision events still applies
+    let options = DecideOptions {
+        disable_decision_event: true,
+        ..DecideOptions::default()
+    };
+    client
+        .create_user_context("user6")
+        .decide_with_options("feature_rollout", &options);
+    assert_eq!(recorder.decisions().len(), 1);
+
+    // Flags that do not exist do not send decision events
+    client.create_user_context("user0").decide("unknown");
+    assert_eq!(recorder.decisions().len(), 1);
+
+    Ok(())
+}
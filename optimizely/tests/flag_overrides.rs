@@ -0,0 +1,165 @@
+// External imports
+use std::error::Error;
+
+// Imports from Optimizely crate
+use optimizely::testing::{DatafileBuilder, FlagBuilder, FlagOverride, FlagOverrides, RuleBuilder, VariationBuilder};
+use optimizely::Client;
+
+// Relative imports of sub modules
+use common::{EventStore, FILE_PATH};
+mod common;
+
+#[test]
+fn override_for_everyone() -> Result<(), Box<dyn Error>> {
+    let overrides = FlagOverrides::new();
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .with_flag_overrides(&overrides)
+        .initialize();
+
+    // Variation of the A/B test, so the IDs are taken from the datafile
+    overrides.set("a_b_test", "variation_3");
+    for user_id in ["user-1", "user-2", "user-3"] {
+        let decision = client.create_user_context(user_id).decide("a_b_test");
+        assert!(decision.enabled());
+        assert_eq!(decision.variation_key(), "variation_3");
+        assert_eq!(decision.variation_id(), "1601785");
+        assert_eq!(decision.experiment_id(), "9300002920769");
+    }
+
+    // Variation of the "Everyone Else" rule
+    overrides.set("feature_rollout", "off");
+    let decision = client
+        .create_user_context("user-1")
+        .decide("feature_rollout");
+    assert!(!decision.enabled());
+    assert_eq!(decision.variation_key(), "off");
+    assert_eq!(decision.variation_id(), "1601797");
+
+    // Variation that does not exist in the datafile
+    overrides.set("feature_rollout", "beta");
+    let decision = client
+        .create_user_context("user-1")
+        .decide("feature_rollout");
+    assert!(decision.enabled());
+    assert_eq!(decision.variation_key(), "beta");
+    assert_eq!(decision.variation_id(), "");
+
+    // Flag that does not exist in the datafile
+    overrides.set("buy_button", FlagOverride::new("B").variable("color", "green"));
+    let decision = client.create_user_context("user-1").decide("buy_button");
+    assert!(decision.enabled());
+    assert_eq!(decision.variation_key(), "B");
+    assert_eq!(decision.variable("color"), Some("green"));
+
+    // Removed overrides fall back to the datafile
+    overrides.remove("buy_button");
+    let decision = client.create_user_context("user-1").decide("buy_button");
+    assert_eq!(decision.variation_key(), "off");
+
+    Ok(())
+}
+
+#[test]
+fn override_for_user() -> Result<(), Box<dyn Error>> {
+    let overrides = FlagOverrides::new();
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .with_flag_overrides(&overrides)
+        .initialize();
+
+    overrides.set("a_b_test", "variation_1");
+    overrides.set_for_user("a_b_test", "user-2", "variation_2");
+
+    // Override for a single user takes precedence
+    let decision = client.create_user_context("user-1").decide("a_b_test");
+    assert_eq!(decision.variation_key(), "variation_1");
+    let decision = client.create_user_context("user-2").decide("a_b_test");
+    assert_eq!(decision.variation_key(), "variation_2");
+
+    // Override for a single user only
+    overrides.clear();
+    overrides.set_for_user("feature_rollout", "user-1", FlagOverride::new("on").enabled(false));
+    let decision = client
+        .create_user_context("user-1")
+        .decide("feature_rollout");
+    assert!(!decision.enabled());
+    assert_eq!(decision.variation_key(), "on");
+    assert_eq!(decision.experiment_id(), "9300002920799");
+
+    Ok(())
+}
+
+#[test]
+fn override_variables() -> Result<(), Box<dyn Error>> {
+    let overrides = FlagOverrides::new();
+    let client = DatafileBuilder::new()
+        .flag(
+            FlagBuilder::new("checkout")
+                .variable("label", "string", "Buy")
+                .variable("discount", "integer", "0")
+                .experiment_rule(
+                    RuleBuilder::new("checkout_test")
+                        .variation(VariationBuilder::new("a").variable("label", "Buy now"), 0..5_000)
+                        .variation(VariationBuilder::new("b").variable("label", "Order"), 5_000..10_000),
+                ),
+        )
+        .build_client()?
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .with_flag_overrides(&overrides)
+        .initialize();
+
+    // Variables of the variation within the datafile
+    overrides.set("checkout", "b");
+    let decision = client.create_user_context("user-1").decide("checkout");
+    assert_eq!(decision.variable("label"), Some("Order"));
+    assert_eq!(decision.variable("discount"), Some("0"));
+
+    // Variables of the override take precedence
+    overrides.set("checkout", FlagOverride::new("b").variable("discount", "10"));
+    let decision = client.create_user_context("user-1").decide("checkout");
+    assert_eq!(decision.variable("label"), Some("Order"));
+    assert_eq!(decision.variable("discount"), Some("10"));
+
+    // Unknown variation has default values
+    overrides.set("checkout", "c");
+    let decision = client.create_user_context("user-1").decide("checkout");
+    assert_eq!(decision.variable("label"), Some("Buy"));
+
+    Ok(())
+}
+
+#[test]
+fn recorded_decisions() -> Result<(), Box<dyn Error>> {
+    let overrides = FlagOverrides::new();
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .with_flag_overrides(&overrides)
+        .initialize();
+
+    overrides.set_for_user("a_b_test", "user-1", "variation_2");
+    client.create_user_context("user-1").decide("a_b_test");
+    client.create_user_context("user-2").decide("a_b_test");
+    client.create_user_context("user-2").decide("unknown_flag");
+
+    // Both overridden and bucketed decisions are recorded in order
+    let decisions = overrides.decisions();
+    let recorded = decisions
+        .iter()
+        .map(|recorded| (recorded.user_id(), recorded.decision().flag_key(), recorded.overridden()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        recorded,
+        [
+            ("user-1", "a_b_test", true),
+            ("user-2", "a_b_test", false),
+            ("user-2", "unknown_flag", false)
+        ]
+    );
+    assert_eq!(decisions[0].decision().variation_key(), "variation_2");
+
+    overrides.clear_decisions();
+    assert!(overrides.decisions().is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+// External imports
+use std::error::Error;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+// Imports from Optimizely crate
+use optimizely::event_api::{EventDispatcher, LogEvent, LogEventKind};
+use optimizely::{AttributeValue, Client};
+
+// Relative imports of sub modules
+use common::{ACCOUNT_ID, FILE_PATH, REVISION};
+mod common;
+
+// Dispatcher that moves every event to another thread
+struct ChannelEventDispatcher(Mutex<mpsc::Sender<LogEvent>>);
+
+impl EventDispatcher for ChannelEventDispatcher {
+    fn dispatch_event(&self, event: LogEvent) {
+        let sender = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = sender.send(event);
+    }
+}
+
+#[test]
+fn owned_events() -> Result<(), Box<dyn Error>> {
+    let (sender, receiver) = mpsc::channel();
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_dispatcher(|_datafile| ChannelEventDispatcher(Mutex::new(sender)))
+        .initialize();
+
+    let mut user_context = client.create_user_context("user-1");
+    user_context.set_attribute("isLoggedIn", AttributeValue::Boolean(true));
+    user_context.set_session_id("session-1");
+    user_context.set_client_ip("192.0.2.1");
+    let decision = user_context.decide("a_b_test");
+    user_context.track_event("purchase");
+
+    // Events do not borrow from the client, so they can outlive it and be processed on another thread
+    drop(client);
+    let events = thread::spawn(move || receiver.iter().collect::<Vec<_>>())
+        .join()
+        .map_err(|_| "Receiver thread panicked")?;
+    assert_eq!(events.len(), 2);
+
+    let event = &events[0];
+    assert_eq!(event.visitor_id(), "user-1");
+    assert_eq!(event.session_id(), Some("session-1"));
+    assert_eq!(event.attributes()[0].key(), "isLoggedIn");
+    assert_eq!(event.context().account_id(), ACCOUNT_ID);
+    assert_eq!(event.context().revision(), REVISION.to_string());
+    assert_eq!(event.client_ip(), Some("192.0.2.1"));
+    assert_eq!(event.endpoint_url(), "https://logx.optimizely.com/v1/events");
+    assert!(event.timestamp() > 0);
+    match event.kind() {
+        LogEventKind::Decision(recorded) => assert_eq!(recorded.variation_key(), decision.variation_key()),
+        LogEventKind::Conversion(_) => return Err("Expected decision".into()),
+    }
+
+    let event = &events[1];
+    assert!(event.decision().is_none());
+    assert_eq!(event.conversion().map(|conversion| conversion.event_key()), Some("purchase"));
+    assert_eq!(event.conversion().map(|conversion| conversion.timestamp()), Some(event.timestamp()));
+
+    Ok(())
+}
+
+#[test]
+fn serialize_event() -> Result<(), Box<dyn Error>> {
+    let (sender, receiver) = mpsc::channel();
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_dispatcher(|_datafile| ChannelEventDispatcher(Mutex::new(sender)))
+        .initialize();
+
+    client.create_user_context("user-1").decide("a_b_test");
+    client.create_user_context("user-2").track_event("purchase");
+
+    let value = serde_json::to_value(receiver.recv()?)?;
+    assert_eq!(value["visitor_id"], "user-1");
+    assert_eq!(value["context"]["account_id"], ACCOUNT_ID);
+    assert_eq!(value["endpoint_url"], "https://logx.optimizely.com/v1/events");
+    assert_eq!(value["decision"]["metadata"]["flag_key"], "a_b_test");
+    assert!(value.get("session_id").is_none());
+
+    let value = serde_json::to_value(receiver.recv()?)?;
+    assert_eq!(value["visitor_id"], "user-2");
+    assert_eq!(value["conversion"]["key"], "purchase");
+    assert!(value.get("decision").is_none());
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+// External imports
+use std::error::Error;
+use std::fs;
+
+// Imports from Optimizely crate
+use optimizely::{AttributeValue, Client};
+
+// Relative imports of sub modules
+use common::{EventStore, FILE_PATH};
+mod common;
+
+// Parse the content and make a decision for every flag, which should never panic
+fn parse_and_decide(content: &str) {
+    let Ok(client) = Client::from_string(content) else {
+        return;
+    };
+    let client = client
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .initialize();
+
+    let mut user_context = client.create_user_context("user123");
+    user_context.set_attribute("appVersion", AttributeValue::String(String::from("1.2.3")));
+    user_context.set_attribute("numberOfProductsInCart", AttributeValue::Integer(3));
+
+    for flag_key in ["a_b_test", "feature_rollout", "targeted_delivery"] {
+        user_context.decide(flag_key);
+    }
+}
+
+#[test]
+fn truncated_datafile() -> Result<(), Box<dyn Error>> {
+    let content = fs::read_to_string(FILE_PATH)?;
+
+    // Every truncated datafile is invalid JSON
+    for (index, _) in content.char_indices().step_by(7) {
+        assert!(Client::from_string(&content[..index]).is_err());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn mutated_datafile() -> Result<(), Box<dyn Error>> {
+    let content = fs::read_to_string(FILE_PATH)?;
+
+    // Replace values by values of different types or out of range values
+    let replacements = [
+        "0",
+        "-1",
+        "1e308",
+        "18446744073709551616",
+        "null",
+        "true",
+        r#""""#,
+        "[]",
+        "{}",
+        r#"["not"]"#,
+    ];
+    let needles = [
+        r#""value": "1.0.0""#,
+        r#""value": 1"#,
+        r#""endOfRange": 10000"#,
+        r#""revision": "21""#,
+        r#""match": "semver_ge""#,
+        r#""type": "custom_attribute""#,
+        r#""conditions": ["#,
+        r#""trafficAllocation": ["#,
+        r#""audienceIds": []"#,
+    ];
+
+    for needle in needles {
+        assert!(content.contains(needle), "Needle {needle} not found");
+        let (key, _) = needle.split_once(':').ok_or("Needle without key")?;
+
+        for replacement in replacements {
+            parse_and_decide(&content.replacen(needle, &format!("{key}: {replacement}"), 1));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn deeply_nested_condition() -> Result<(), Box<dyn Error>> {
+    let content = fs::read_to_string(FILE_PATH)?;
+
+    // Reasonable nesting is accepted, but deep nesting is rejected instead of overflowing the stack
+    for (depth, is_valid) in [(20, true), (100, false), (100_000, false)] {
+        let condition = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        let content = content.replacen(r#""conditions": ["#, &format!(r#""conditions": [{condition},"#), 1);
+
+        assert_eq!(Client::from_string(&content).is_ok(), is_valid);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,288 @@
+// External imports
+use std::error::Error;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+// Imports from Optimizely crate
+use optimizely::datafile::{Datafile, DatafileCache};
+use optimizely::event_api::BatchedEventDispatcher;
+use optimizely::testing::MockServer;
+use optimizely::Client;
+
+// Relative imports of sub modules
+use common::{EventStore, ACCOUNT_ID, FILE_PATH, REVISION, SDK_KEY};
+mod common;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+const UPDATE_INTERVAL: Duration = Duration::from_millis(20);
+
+// Wait until the condition holds, or fail after the timeout
+fn wait_until(condition: impl Fn() -> bool) -> bool {
+    let deadline = Instant::now() + TIMEOUT;
+    while Instant::now() < deadline {
+        if condition() {
+            return true;
+        }
+        sleep(UPDATE_INTERVAL);
+    }
+    false
+}
+
+#[test]
+fn download_and_poll() -> Result<(), Box<dyn Error>> {
+    let content = fs::read_to_string(FILE_PATH)?;
+    let server = MockServer::start()?;
+    server.set_datafile(SDK_KEY, &content);
+
+    let client = Client::from_sdk_key_in_background(SDK_KEY)
+        .with_cdn_url(server.url())
+        .with_update_interval(UPDATE_INTERVAL)
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .initialize();
+    assert!(client.wait_until_ready(TIMEOUT));
+    assert_eq!(client.datafile().revision(), REVISION);
+
+    // Unchanged datafile is not sent again
+    assert!(wait_until(|| server
+        .datafile_requests()
+        .iter()
+        .any(|request| request.status() == 304)));
+    assert_eq!(server.datafile_requests()[0].status(), 200);
+    assert_eq!(server.datafile_requests()[0].sdk_key(), SDK_KEY);
+
+    // Revision bump is picked up by the polling thread
+    let content = content.replace("\"revision\": \"21\"", "\"revision\": \"22\"");
+    server.set_datafile(SDK_KEY, content);
+    assert!(wait_until(|| client.datafile().revision() == REVISION + 1));
+
+    Ok(())
+}
+
+#[test]
+fn blocking_download_and_poll() -> Result<(), Box<dyn Error>> {
+    let content = fs::read_to_string(FILE_PATH)?;
+    let server = MockServer::start()?;
+    server.set_datafile(SDK_KEY, &content);
+
+    // Both the initial download and polling use the given CDN
+    let client = Client::from_cdn(&server.url(), SDK_KEY)?
+        .with_update_interval(UPDATE_INTERVAL)
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .initialize();
+    assert!(client.is_ready());
+    assert_eq!(client.datafile().revision(), REVISION);
+    assert_eq!(server.datafile_requests()[0].status(), 200);
+    assert!(wait_until(|| server.datafile_requests().len() > 1));
+
+    // Fall back to the cache, which was filled by the previous download
+    let cache_directory = std::env::temp_dir().join("optimizely-test-mock-server-cache");
+    drop(Client::from_cdn_with_cache(&server.url(), SDK_KEY, &cache_directory)?);
+    server.set_datafile_status(Some(500));
+    let client = Client::from_cdn_with_cache(&server.url(), SDK_KEY, &cache_directory)?
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .initialize();
+    assert_eq!(client.datafile().revision(), REVISION);
+
+    Ok(())
+}
+
+#[test]
+fn failing_cdn() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    server.set_datafile(SDK_KEY, fs::read_to_string(FILE_PATH)?);
+    server.set_datafile_status(Some(500));
+
+    // Blocking download fails
+    assert!(Datafile::from_cdn(&server.url(), SDK_KEY).is_err());
+
+    // Download in the background keeps trying
+    let client = Client::from_sdk_key_in_background(SDK_KEY)
+        .with_cdn_url(server.url())
+        .with_update_interval(UPDATE_INTERVAL)
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .initialize();
+    assert!(!client.wait_until_ready(UPDATE_INTERVAL * 5));
+    assert!(!server.datafile_requests().is_empty());
+
+    // Client recovers when the CDN does
+    server.set_datafile_status(None);
+    assert!(client.wait_until_ready(TIMEOUT));
+
+    // Unknown SDK keys are forbidden, like the actual CDN
+    assert!(Datafile::from_cdn(&server.url(), "unknown").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn unreachable_cdn_falls_back_to_cache() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    server.set_datafile(SDK_KEY, fs::read_to_string(FILE_PATH)?);
+    server.set_datafile_status(Some(503));
+
+    // Fill the cache with the bundled datafile
+    let cache_directory = std::env::temp_dir().join(format!("optimizely-test-unreachable-cdn-{}", std::process::id()));
+    let cache = DatafileCache::new(&cache_directory);
+    cache.store(SDK_KEY, &fs::read_to_string(FILE_PATH)?)?;
+
+    // The cache is configured after the download failed, and is still used
+    let client = Client::from_cdn(&server.url(), SDK_KEY)?
+        .with_datafile_cache(&cache_directory)
+        .with_update_interval(UPDATE_INTERVAL)
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .initialize();
+    assert!(!client.is_ready());
+    assert_eq!(client.datafile().account_id(), ACCOUNT_ID);
+    assert_eq!(client.datafile().revision(), REVISION);
+
+    // Client recovers when the CDN does
+    server.set_datafile_status(None);
+    assert!(client.wait_until_ready(TIMEOUT));
+
+    // Unknown SDK keys still fail immediately
+    assert!(Client::from_cdn(&server.url(), "unknown").is_err());
+
+    fs::remove_dir_all(&cache_directory)?;
+    Ok(())
+}
+
+#[test]
+fn rejected_sdk_key() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+
+    // The CDN responds with 403 Forbidden for unknown SDK keys, which does not change by retrying
+    let client = Client::from_sdk_key_in_background("unknown")
+        .with_cdn_url(server.url())
+        .with_update_interval(UPDATE_INTERVAL)
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .initialize();
+    assert!(wait_until(|| !server.datafile_requests().is_empty()));
+    sleep(UPDATE_INTERVAL * 5);
+    assert_eq!(server.datafile_requests().len(), 1);
+    assert!(!client.is_ready());
+
+    Ok(())
+}
+
+#[test]
+fn stop_polling_after_drop() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    server.set_datafile(SDK_KEY, fs::read_to_string(FILE_PATH)?);
+
+    let client = Client::from_sdk_key_in_background(SDK_KEY)
+        .with_cdn_url(server.url())
+        .with_update_interval(UPDATE_INTERVAL)
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .initialize();
+    assert!(client.wait_until_ready(TIMEOUT));
+    drop(client);
+
+    // A request that was already in flight might still arrive
+    sleep(UPDATE_INTERVAL * 2);
+    let requests = server.datafile_requests().len();
+    sleep(UPDATE_INTERVAL * 5);
+    assert_eq!(server.datafile_requests().len(), requests);
+
+    Ok(())
+}
+
+#[test]
+fn strict_validation_in_background() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    server.set_datafile(SDK_KEY, fs::read_to_string(FILE_PATH)?);
+
+    // The placeholder is not validated, but the downloaded datafile is
+    let client = Client::from_sdk_key_in_background(SDK_KEY)
+        .with_cdn_url(server.url())
+        .with_strict_validation()?
+        .with_event_dispatcher(|_datafile| EventStore::default())
+        .initialize();
+    assert!(client.wait_until_ready(TIMEOUT));
+    assert_eq!(client.datafile().revision(), REVISION);
+
+    Ok(())
+}
+
+#[test]
+fn simple_event_dispatcher() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_api_url(server.url())
+        .initialize();
+    assert_eq!(client.event_endpoint_url(), format!("{}/v1/events", server.url()));
+
+    let user_context = client.create_user_context("user-1");
+    user_context.decide("a_b_test");
+    user_context.track_event("purchase");
+
+    assert!(server.wait_for_event_requests(2, TIMEOUT));
+    for request in server.event_requests() {
+        assert!(request.is_valid(), "{:?}", request.errors());
+        assert_eq!(request.status(), 204);
+
+        let payload = request.payload().ok_or("Missing payload")?;
+        assert_eq!(payload["account_id"], ACCOUNT_ID);
+        assert_eq!(payload["visitors"][0]["visitor_id"], "user-1");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn batched_event_dispatcher() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_api_url(server.url())
+        .with_event_dispatcher(BatchedEventDispatcher::new)
+        .initialize();
+
+    for index in 0..3 {
+        client
+            .create_user_context(&format!("user-{index}"))
+            .track_event("purchase");
+    }
+
+    // Dropping the client sends the last batch
+    drop(client);
+
+    assert!(server.wait_for_event_requests(1, TIMEOUT));
+    let requests = server.event_requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].is_valid(), "{:?}", requests[0].errors());
+    let payload = requests[0].payload().ok_or("Missing payload")?;
+    assert_eq!(payload["visitors"].as_array().map(Vec::len), Some(3));
+
+    Ok(())
+}
+
+#[test]
+fn invalid_payload() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start()?;
+
+    // Visitor without ID and without snapshots
+    let body = r#"{"account_id": "1", "visitors": [{"attributes": []}]}"#;
+    let mut stream = TcpStream::connect(server.url().trim_start_matches("http://"))?;
+    write!(
+        stream,
+        "POST /v1/events HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    assert!(response.starts_with("HTTP/1.1 400"));
+
+    let requests = server.event_requests();
+    assert_eq!(requests[0].body(), body);
+    assert!(!requests[0].is_valid());
+    assert!(requests[0]
+        .errors()
+        .contains(&String::from("visitors[0].visitor_id: expected a string")));
+    assert!(requests[0]
+        .errors()
+        .contains(&String::from("visitors[0].snapshots: expected an array")));
+
+    Ok(())
+}
@@ -0,0 +1,156 @@
+// External imports
+use std::error::Error;
+use std::fs;
+
+// Imports from Optimizely crate
+use optimizely::Client;
+
+// Relative imports of sub modules
+use common::{FILE_PATH, REVISION};
+mod common;
+
+#[test]
+fn flags_and_rules() -> Result<(), Box<dyn Error>> {
+    let client = Client::from_local_datafile(FILE_PATH)?.initialize();
+    let config = client.optimizely_config();
+
+    assert_eq!(config.revision(), REVISION);
+    assert_eq!(config.features_map().len(), 3);
+
+    // Flag with an A/B test
+    let flag = config.feature("a_b_test").ok_or("Flag not found")?;
+    assert_eq!(flag.id(), "503553");
+    assert_eq!(flag.experiment_rules().len(), 1);
+    assert_eq!(flag.delivery_rules().len(), 1);
+    assert_eq!(config.features_by_id().get("503553"), Some(&flag));
+
+    // Experiment can be found by key and by ID
+    let experiment = config
+        .experiment("a_b_test_rule")
+        .ok_or("Experiment not found")?;
+    assert_eq!(experiment.id(), "9300002920769");
+    assert_eq!(experiment.audiences(), "");
+    assert_eq!(experiment.variations_map().len(), 3);
+    assert_eq!(config.experiments_by_id().get("9300002920769"), Some(&experiment));
+
+    // Delivery rules are listed in order of evaluation
+    let flag = config
+        .feature("targeted_delivery")
+        .ok_or("Flag not found")?;
+    let rule_keys = flag
+        .delivery_rules()
+        .iter()
+        .map(|rule| rule.key())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rule_keys,
+        [
+            "targeted_delivery_logged_out",
+            "targeted_delivery_on_checkout",
+            "targeted_delivery_more_than_1_product_in_cart",
+            "targeted_delivery_version_1_0_and_up",
+            "default-rollout-503562-911768386522787",
+        ]
+    );
+
+    // Delivery rules are not experiments, but can be found as rule
+    assert!(config.experiment("targeted_delivery_logged_out").is_none());
+    let rule = config
+        .rule("targeted_delivery_logged_out")
+        .ok_or("Rule not found")?;
+    assert_eq!(rule.audiences(), r#""Logged out Users""#);
+
+    Ok(())
+}
+
+#[test]
+fn events_attributes_and_audiences() -> Result<(), Box<dyn Error>> {
+    let client = Client::from_local_datafile(FILE_PATH)?.initialize();
+    let config = client.optimizely_config();
+
+    // Events
+    let event = config.event("add_to_cart").ok_or("Event not found")?;
+    assert_eq!(event.id(), "6385081309200384");
+    assert_eq!(event.experiment_ids(), ["9300002920769"]);
+    assert_eq!(
+        config
+            .events_by_id()
+            .get("6570940541173760")
+            .map(|event| event.key()),
+        Some("purchase")
+    );
+
+    // Attributes
+    let attribute = config
+        .attribute("appVersion")
+        .ok_or("Attribute not found")?;
+    assert_eq!(attribute.id(), "5714602848157696");
+    assert_eq!(config.attributes_by_id().len(), 4);
+
+    // Audiences with readable conditions
+    let audience = config
+        .audience("4534887915454464")
+        .ok_or("Audience not found")?;
+    assert_eq!(audience.name(), "Version 1 and higher");
+    assert_eq!(
+        audience.conditions(),
+        r#"["and",["or",["or",{"type":"custom_attribute","name":"appVersion","match":"semver_ge","value":"1.0.0"}]]]"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn variables() -> Result<(), Box<dyn Error>> {
+    // Add a variable to the flag, and override its value in one variation
+    let content = fs::read_to_string(FILE_PATH)?
+        .replacen(
+            r#""experimentIds": [
+        "9300002920769"
+      ],
+      "variables": []"#,
+            r#""experimentIds": [
+        "9300002920769"
+      ],
+      "variables": [{"id": "1", "key": "button_color", "type": "string", "defaultValue": "blue"}]"#,
+            1,
+        )
+        .replacen(
+            r#""key": "variation_1",
+          "featureEnabled": true,
+          "variables": []"#,
+            r#""key": "variation_1",
+          "featureEnabled": true,
+          "variables": [{"id": "1", "value": "red"}]"#,
+            1,
+        );
+    let client = Client::from_string(content)?.initialize();
+    let config = client.optimizely_config();
+
+    // Flag contains the default value
+    let flag = config.feature("a_b_test").ok_or("Flag not found")?;
+    let variable = flag
+        .variables_map()
+        .get("button_color")
+        .ok_or("Variable not found")?;
+    assert_eq!(variable.variable_type(), "string");
+    assert_eq!(variable.value(), "blue");
+
+    // Variations contain their own value, or the default value
+    let experiment = config
+        .experiment("a_b_test_rule")
+        .ok_or("Experiment not found")?;
+    for (variation_key, expected_value) in [("variation_1", "red"), ("variation_2", "blue")] {
+        let variation = experiment
+            .variations_map()
+            .get(variation_key)
+            .ok_or("Variation not found")?;
+        let variable = variation
+            .variables_map()
+            .get("button_color")
+            .ok_or("Variable not found")?;
+        assert_eq!(variable.value(), expected_value);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,196 @@
+// External imports
+use std::collections::HashMap;
+use std::error::Error;
+
+// Imports from Optimizely crate
+use optimizely::event_api::LogEventKind;
+use optimizely::testing::{DatafileBuilder, RecordingEventDispatcher};
+use optimizely::{AttributeValue, Client, EventTags};
+
+// Relative imports of sub modules
+use common::{ACCOUNT_ID, FILE_PATH, REVISION};
+mod common;
+
+#[test]
+fn decision_payload() -> Result<(), Box<dyn Error>> {
+    let recorder = RecordingEventDispatcher::new();
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_dispatcher(|_datafile| recorder.clone())
+        .initialize();
+
+    let mut user_context = client.create_user_context("user-1");
+    user_context.set_attribute("isLoggedIn", AttributeValue::Boolean(true));
+    let decision = user_context.decide("a_b_test");
+
+    // Only the A/B test sends a decision event
+    user_context.decide("feature_rollout");
+
+    let events = recorder.events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].visitor_id(), "user-1");
+    assert_eq!(events[0].attributes().len(), 1);
+    assert_eq!(events[0].attributes()[0].key(), "isLoggedIn");
+    match events[0].kind() {
+        LogEventKind::Decision(recorded) => assert_eq!(recorded.variation_key(), decision.variation_key()),
+        LogEventKind::Conversion(_) => return Err("Expected decision".into()),
+    }
+
+    // Payload as the Event API receives it
+    let payload = events[0].payload();
+    assert_eq!(payload["account_id"], ACCOUNT_ID);
+    assert_eq!(payload["project_id"], "6544469315551232");
+    assert_eq!(payload["revision"], REVISION.to_string());
+    assert_eq!(payload["anonymize_ip"], true);
+    assert_eq!(payload["client_name"], "rust-sdk");
+    let visitor = &payload["visitors"][0];
+    assert_eq!(visitor["visitor_id"], "user-1");
+    assert_eq!(visitor["attributes"][0]["entity_id"], "6300309014708224");
+    assert_eq!(visitor["attributes"][0]["value"], "true");
+    let snapshot = &visitor["snapshots"][0];
+    assert_eq!(snapshot["decisions"][0]["experiment_id"], "9300002920769");
+    assert_eq!(snapshot["decisions"][0]["variation_id"], decision.variation_id());
+    let metadata = &snapshot["decisions"][0]["metadata"];
+    assert_eq!(metadata["flag_key"], "a_b_test");
+    assert_eq!(metadata["rule_key"], "a_b_test_rule");
+    assert_eq!(metadata["rule_type"], "feature-test");
+    assert_eq!(metadata["variation_key"], decision.variation_key());
+    assert_eq!(metadata["enabled"], decision.enabled());
+    assert_eq!(snapshot["events"][0]["key"], "campaign_activated");
+    assert_eq!(snapshot["events"][0]["entity_id"], decision.campaign_id());
+
+    // The same payload is returned every time
+    assert_eq!(recorder.events()[0].payload(), payload);
+
+    Ok(())
+}
+
+#[test]
+fn conversion_payload() -> Result<(), Box<dyn Error>> {
+    let recorder = RecordingEventDispatcher::new();
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_dispatcher(|_datafile| recorder.clone())
+        .initialize();
+
+    let properties = HashMap::from([(String::from("category"), String::from("shoes"))]);
+    let tags = HashMap::from([(String::from("revenue"), String::from("4200"))]);
+    client
+        .create_user_context("user-1")
+        .track_event_with_properties_and_tags("purchase", properties, tags);
+    client
+        .create_user_context("user-2")
+        .track_event("add_to_cart");
+
+    // Unknown events are not dispatched
+    client.create_user_context("user-3").track_event("unknown");
+
+    let conversions = recorder.conversions();
+    let event_keys = conversions
+        .iter()
+        .map(|conversion| conversion.event_key())
+        .collect::<Vec<_>>();
+    assert_eq!(event_keys, ["purchase", "add_to_cart"]);
+    assert!(recorder.decisions().is_empty());
+
+    let events = recorder.events();
+    let event = &events[0].payload()["visitors"][0]["snapshots"][0]["events"][0];
+    assert_eq!(event["entity_id"], "6570940541173760");
+    assert_eq!(event["properties"]["category"], "shoes");
+    assert_eq!(event["tags"]["revenue"], "4200");
+
+    // Numeric text is also sent as top-level revenue
+    assert_eq!(event["revenue"], 4200);
+
+    // All visitors in a single payload
+    let payloads = recorder.batched_payloads();
+    assert_eq!(payloads.len(), 1);
+    assert_eq!(payloads[0]["visitors"][0]["visitor_id"], "user-1");
+    assert_eq!(payloads[0]["visitors"][1]["visitor_id"], "user-2");
+
+    recorder.clear();
+    assert!(recorder.batched_payloads().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn session_and_client_ip() -> Result<(), Box<dyn Error>> {
+    let recorder = RecordingEventDispatcher::new();
+    let datafile = DatafileBuilder::new()
+        .account_id("123")
+        .project_id("456")
+        .revision(7)
+        .anonymize_ip(false)
+        .event("purchase")
+        .build()?;
+    let client = Client::from_datafile(datafile)
+        .with_event_dispatcher(|_datafile| recorder.clone())
+        .initialize();
+
+    // Without session and IP address, the fields are left out
+    client.create_user_context("user-1").track_event("purchase");
+
+    let mut user_context = client.create_user_context("user-2");
+    user_context.set_session_id("session-1");
+    user_context.set_client_ip("192.0.2.1");
+    user_context.track_event("purchase");
+
+    let events = recorder.events();
+    let payload = events[0].payload();
+    assert_eq!(payload["account_id"], "123");
+    assert_eq!(payload["project_id"], "456");
+    assert_eq!(payload["revision"], "7");
+    assert_eq!(payload["anonymize_ip"], false);
+    assert!(payload.get("client_ip").is_none());
+    assert!(payload["visitors"][0].get("session_id").is_none());
+
+    let payload = events[1].payload();
+    assert_eq!(payload["client_ip"], "192.0.2.1");
+    assert_eq!(payload["visitors"][0]["session_id"], "session-1");
+
+    // A request has a single IP address, so the events are not batched together
+    let payloads = recorder.batched_payloads();
+    assert_eq!(payloads.len(), 2);
+    assert!(payloads[0].get("client_ip").is_none());
+    assert_eq!(payloads[1]["client_ip"], "192.0.2.1");
+
+    Ok(())
+}
+
+#[test]
+fn typed_tags() -> Result<(), Box<dyn Error>> {
+    let recorder = RecordingEventDispatcher::new();
+    let client = Client::from_local_datafile(FILE_PATH)?
+        .with_event_dispatcher(|_datafile| recorder.clone())
+        .initialize();
+    let user_context = client.create_user_context("user-1");
+
+    let tags = EventTags::new()
+        .with_revenue(4200)
+        .with_value(1.5)
+        .with_tag("gift_wrap", true)
+        .with_tag("category", "shoes");
+    user_context.track_event_with_properties_and_tags("purchase", HashMap::new(), tags);
+
+    // Revenue should be in cents and value should be a number, otherwise they are only tags
+    let tags = EventTags::new()
+        .with_tag("revenue", 42.5)
+        .with_tag("value", "unknown");
+    user_context.track_event_with_properties_and_tags("purchase", HashMap::new(), tags);
+
+    let events = recorder.events();
+    let event = &events[0].payload()["visitors"][0]["snapshots"][0]["events"][0];
+    assert_eq!(event["revenue"], 4200);
+    assert_eq!(event["value"], 1.5);
+    assert_eq!(event["tags"]["revenue"], 4200);
+    assert_eq!(event["tags"]["value"], 1.5);
+    assert_eq!(event["tags"]["gift_wrap"], true);
+    assert_eq!(event["tags"]["category"], "shoes");
+
+    let event = &events[1].payload()["visitors"][0]["snapshots"][0]["events"][0];
+    assert!(event.get("revenue").is_none());
+    assert!(event.get("value").is_none());
+    assert_eq!(event["tags"]["revenue"], 42.5);
+    assert_eq!(event["tags"]["value"], "unknown");
+
+    Ok(())
+}
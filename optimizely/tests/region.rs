@@ -0,0 +1,64 @@
+// External imports
+use std::error::Error;
+use std::fs;
+
+// Imports from Optimizely crate
+use optimizely::datafile::{Datafile, Region};
+use optimizely::testing::DatafileBuilder;
+use optimizely::Client;
+
+// Relative imports of sub modules
+use common::FILE_PATH;
+mod common;
+
+const US_ENDPOINT: &str = "https://logx.optimizely.com/v1/events";
+const EU_ENDPOINT: &str = "https://eu.logx.optimizely.com/v1/events";
+
+#[test]
+fn parse_region() -> Result<(), Box<dyn Error>> {
+    let content = fs::read_to_string(FILE_PATH)?;
+    assert_eq!(Datafile::from_string(&content)?.region(), Region::US);
+
+    let content = content.replace("\"region\": \"US\"", "\"region\": \"EU\"");
+    assert_eq!(Datafile::from_string(&content)?.region(), Region::EU);
+
+    // Older datafiles without a region and unknown regions use the US
+    let content = content.replace("\"region\": \"EU\"", "\"region\": \"APAC\"");
+    assert_eq!(Datafile::from_string(&content)?.region(), Region::US);
+    let datafile = DatafileBuilder::new().build_json();
+    let mut datafile = datafile.as_object().cloned().ok_or("Expected object")?;
+    datafile.remove("region");
+    assert_eq!(Datafile::from_string(&serde_json::to_string(&datafile)?)?.region(), Region::US);
+
+    Ok(())
+}
+
+#[test]
+fn endpoint_follows_region() -> Result<(), Box<dyn Error>> {
+    let client = Client::from_local_datafile(FILE_PATH)?.initialize();
+    assert_eq!(client.event_endpoint_url(), US_ENDPOINT);
+
+    // Datafile update moves the project to the EU
+    let content = DatafileBuilder::new()
+        .revision(100)
+        .region(Region::EU)
+        .build_json()
+        .to_string();
+    client.update_datafile(content)?;
+    assert_eq!(client.event_endpoint_url(), EU_ENDPOINT);
+
+    Ok(())
+}
+
+#[test]
+fn custom_event_api_url() -> Result<(), Box<dyn Error>> {
+    let datafile = DatafileBuilder::new().region(Region::EU).build()?;
+    let client = Client::from_datafile(datafile)
+        .with_event_api_url("http://127.0.0.1:8080/")
+        .initialize();
+
+    // Custom URL applies to every region
+    assert_eq!(client.event_endpoint_url(), "http://127.0.0.1:8080/v1/events");
+
+    Ok(())
+}